@@ -0,0 +1,593 @@
+//! Read-only HTTP admin API for the local-ILM reasoner.
+//!
+//! Turns `InsightStore`/`Metrics` from log-only components into something an
+//! operator or dashboard can poll: list recent insights (filtered by class
+//! and/or confidence), fetch the telemetry window that produced one, stream
+//! new ones as `emit_insight` records them, and read the headline health
+//! counters. Also exposes `IncidentStore`'s bulk JSONL export/import so an
+//! operator can back up, migrate, or restore incidents without touching the
+//! SQLite file directly. Handlers are exported individually rather than as
+//! one `Router` builder, matching `telemetry::metrics_handler` -- the binary
+//! mounts them at whatever paths and alongside whatever other routes it
+//! needs.
+
+use std::convert::Infallible;
+use std::sync::Arc;
+
+use axum::Json;
+use axum::body::Bytes;
+use axum::extract::{Path, Query, State};
+use axum::response::IntoResponse;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use futures_util::Stream;
+use serde::{Deserialize, Serialize};
+use tokio_stream::StreamExt as _;
+use tokio_stream::wrappers::BroadcastStream;
+
+use crate::enforcement::{ActionType, EnforcementQueue, RemediationOutcome};
+use crate::incidents::{ImportIdMode, IncidentStore};
+use crate::insights::{InsightRecord, InsightStore};
+use crate::metrics::Metrics;
+
+const DEFAULT_INSIGHTS_LIMIT: usize = 100;
+
+/// Shared state for every handler in this module.
+#[derive(Clone)]
+pub struct AdminApiState {
+    pub insights: Arc<InsightStore>,
+    pub metrics: Arc<Metrics>,
+    pub incidents: Arc<IncidentStore>,
+    pub enforcement: Arc<EnforcementQueue>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListInsightsQuery {
+    class: Option<String>,
+    min_confidence: Option<f64>,
+    limit: Option<usize>,
+}
+
+/// `GET /admin/insights` -- recent insights, most recent first, optionally
+/// filtered by `class` and/or `min_confidence`.
+pub async fn list_insights(
+    State(state): State<AdminApiState>,
+    Query(query): Query<ListInsightsQuery>,
+) -> Json<Vec<InsightRecord>> {
+    let limit = query.limit.unwrap_or(DEFAULT_INSIGHTS_LIMIT).max(1);
+    let min_confidence = query.min_confidence.unwrap_or(0.0);
+    let records = state
+        .insights
+        .recent(limit)
+        .into_iter()
+        .filter(|record| {
+            query
+                .class
+                .as_deref()
+                .is_none_or(|class| record.insight.class.as_str() == class)
+        })
+        .filter(|record| record.insight.confidence >= min_confidence)
+        .collect();
+    Json(records)
+}
+
+/// `GET /admin/insights/:id` -- the telemetry window (and, depending on
+/// `AuditLevel`, the tool calls/prompts/raw response) behind one insight, so
+/// an enforcement action proposed from it can be explained after the fact.
+pub async fn get_insight(State(state): State<AdminApiState>, Path(id): Path<u64>) -> impl IntoResponse {
+    match state.insights.get(id) {
+        Some(record) => Json(record).into_response(),
+        None => axum::http::StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+/// `GET /admin/insights/stream` -- SSE stream of insights as `emit_insight`
+/// records them. A subscriber that falls behind the store's broadcast
+/// backlog silently misses the oldest dropped insights rather than blocking.
+pub async fn stream_insights(
+    State(state): State<AdminApiState>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let stream = BroadcastStream::new(state.insights.subscribe())
+        .filter_map(|record| record.ok().and_then(|record| Event::default().json_data(&record).ok()))
+        .map(Ok);
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Counters {
+    pub ilm_insights: u64,
+    pub ilm_schema_errors: u64,
+    pub ilm_timeouts: u64,
+    pub alerts_emitted: u64,
+}
+
+/// `GET /admin/counters` -- the reasoner's headline health counters.
+pub async fn counters(State(state): State<AdminApiState>) -> Json<Counters> {
+    Json(Counters {
+        ilm_insights: state.metrics.ilm_insights(),
+        ilm_schema_errors: state.metrics.ilm_schema_errors(),
+        ilm_timeouts: state.metrics.ilm_timeouts(),
+        alerts_emitted: state.metrics.alerts_emitted(),
+    })
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImportIncidentsQuery {
+    #[serde(default)]
+    id_mode: ImportIdModeParam,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ImportIdModeParam {
+    #[default]
+    Reassign,
+    Preserve,
+}
+
+impl From<ImportIdModeParam> for ImportIdMode {
+    fn from(mode: ImportIdModeParam) -> Self {
+        match mode {
+            ImportIdModeParam::Reassign => ImportIdMode::Reassign,
+            ImportIdModeParam::Preserve => ImportIdMode::Preserve,
+        }
+    }
+}
+
+/// `GET /admin/incidents/export` -- every stored incident as
+/// newline-delimited JSON, ordered by timestamp, for piping into cold
+/// storage or another host's `import` endpoint.
+pub async fn export_incidents(State(state): State<AdminApiState>) -> impl IntoResponse {
+    let mut body = Vec::new();
+    match state.incidents.export_jsonl(&mut body).await {
+        Ok(_) => (
+            [(axum::http::header::CONTENT_TYPE, "application/x-ndjson")],
+            body,
+        )
+            .into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "failed to export incidents");
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+/// `POST /admin/incidents/import?id_mode=preserve|reassign` -- bulk-load a
+/// newline-delimited JSON incident stream produced by `export_incidents`,
+/// inserting in batched transactions. Defaults to `reassign` so restoring
+/// an archive into an already-populated store can't collide on `id`; pass
+/// `id_mode=preserve` for an exact mirror restore onto an empty database.
+pub async fn import_incidents(
+    State(state): State<AdminApiState>,
+    Query(query): Query<ImportIncidentsQuery>,
+    body: Bytes,
+) -> impl IntoResponse {
+    let reader = tokio::io::BufReader::new(std::io::Cursor::new(body));
+    match state.incidents.import_jsonl(reader, query.id_mode.into()).await {
+        Ok(inserted) => Json(serde_json::json!({ "inserted": inserted })).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "failed to import incidents");
+            (
+                axum::http::StatusCode::BAD_REQUEST,
+                format!("import failed: {e}"),
+            )
+                .into_response()
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum RemediateActionRequest {
+    Signal { signal: String },
+    Renice { nice: i32 },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RemediateResponse {
+    pub outcome: String,
+    pub detail: String,
+}
+
+impl From<RemediationOutcome> for RemediateResponse {
+    fn from(outcome: RemediationOutcome) -> Self {
+        match outcome {
+            RemediationOutcome::Applied { detail } => Self {
+                outcome: "applied".to_string(),
+                detail,
+            },
+            RemediationOutcome::IdentityMismatch { expected, found } => Self {
+                outcome: "identity_mismatch".to_string(),
+                detail: format!("expected {expected:?}, found {found:?}"),
+            },
+            RemediationOutcome::Failed { reason } => Self {
+                outcome: "failed".to_string(),
+                detail: reason,
+            },
+        }
+    }
+}
+
+fn signal_number(name: &str) -> Option<i32> {
+    match name {
+        "SIGTERM" => Some(libc::SIGTERM),
+        "SIGKILL" => Some(libc::SIGKILL),
+        _ => None,
+    }
+}
+
+/// `POST /insights/:id/remediate` -- act on a classified insight: resolve
+/// its `primary_process` pid, propose the requested action, and run it
+/// straight through `EnforcementQueue::approve`/`execute` rather than
+/// parking it for manual approval, since a client reaching this endpoint
+/// (the `linnix remediate --execute` CLI) has already made that call;
+/// `execute` still re-validates the pid's identity before touching it.
+pub async fn remediate_insight(
+    State(state): State<AdminApiState>,
+    Path(id): Path<u64>,
+    Json(request): Json<RemediateActionRequest>,
+) -> impl IntoResponse {
+    let Some(record) = state.insights.get(id) else {
+        return axum::http::StatusCode::NOT_FOUND.into_response();
+    };
+    let Some(pid) = record.insight.primary_process else {
+        return (
+            axum::http::StatusCode::UNPROCESSABLE_ENTITY,
+            "insight has no primary process to act on".to_string(),
+        )
+            .into_response();
+    };
+
+    let action = match request {
+        RemediateActionRequest::Signal { signal } => match signal_number(&signal) {
+            Some(signal) => ActionType::KillProcess { pid, signal },
+            None => {
+                return (
+                    axum::http::StatusCode::BAD_REQUEST,
+                    format!("unknown signal: {signal}"),
+                )
+                    .into_response();
+            }
+        },
+        RemediateActionRequest::Renice { nice } => ActionType::Renice { pid, nice },
+    };
+
+    let reason = format!("insight {id}: {}", record.insight.why);
+    let proposal_id = match state
+        .enforcement
+        .propose(action, reason, "operator".to_string(), Some(record.insight.confidence))
+        .await
+    {
+        Ok(proposal_id) => proposal_id,
+        Err(e) => return (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e).into_response(),
+    };
+    if let Err(e) = state.enforcement.approve(&proposal_id, "operator".to_string()).await {
+        return (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e).into_response();
+    }
+
+    match state.enforcement.execute(&proposal_id).await {
+        Ok(outcome) => Json(RemediateResponse::from(outcome)).into_response(),
+        Err(e) => (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e).into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handler::local_ilm::schema::{Insight, InsightClass};
+    use axum::Router;
+    use axum::routing::{get, post};
+    use tempfile::NamedTempFile;
+
+    fn sample_insight(class: InsightClass, confidence: f64) -> Insight {
+        Insight {
+            class,
+            confidence,
+            primary_process: None,
+            why: "why".to_string(),
+            actions: Vec::new(),
+        }
+    }
+
+    async fn test_incident_store() -> (NamedTempFile, Arc<IncidentStore>) {
+        let file = NamedTempFile::new().unwrap();
+        let store = IncidentStore::new(file.path()).await.unwrap();
+        (file, Arc::new(store))
+    }
+
+    async fn test_enforcement_queue() -> (tempfile::TempDir, Arc<EnforcementQueue>) {
+        let dir = tempfile::tempdir().unwrap();
+        let queue = EnforcementQueue::new(dir.path().join("enforcement.db"), 300)
+            .await
+            .unwrap();
+        (dir, Arc::new(queue))
+    }
+
+    async fn spawn_app(state: AdminApiState) -> String {
+        // `capabilities_handler` takes its own `CapabilitiesState` rather
+        // than `AdminApiState` -- it's computed once from config/probe
+        // state at startup, not per-request admin data -- so it's merged
+        // in as its own sub-router instead of sharing `.with_state(state)`.
+        let capabilities_router = Router::new()
+            .route(
+                "/capabilities",
+                get(crate::capabilities::capabilities_handler),
+            )
+            .with_state(crate::capabilities::CapabilitiesState(
+                crate::capabilities::Capabilities::new(false, false, true, false, true, true),
+            ));
+        let app = Router::new()
+            .route("/admin/insights", get(list_insights))
+            .route("/admin/insights/{id}", get(get_insight))
+            .route("/admin/insights/stream", get(stream_insights))
+            .route("/admin/counters", get(counters))
+            .route("/admin/incidents/export", get(export_incidents))
+            .route("/admin/incidents/import", post(import_incidents))
+            .route("/insights/{id}/remediate", post(remediate_insight))
+            .with_state(state)
+            .merge(capabilities_router);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let _ = axum::serve(listener, app.into_make_service()).await;
+        });
+        format!("http://{addr}")
+    }
+
+    #[tokio::test]
+    async fn lists_insights_filtered_by_class_and_confidence() {
+        let insights = Arc::new(InsightStore::new(8, None));
+        insights.record(sample_insight(InsightClass::ForkStorm, 0.9));
+        insights.record(sample_insight(InsightClass::Normal, 0.2));
+        let (_tmp, incidents) = test_incident_store().await;
+        let (_enf, enforcement) = test_enforcement_queue().await;
+        let state = AdminApiState {
+            insights,
+            metrics: Arc::new(Metrics::new()),
+            incidents,
+            enforcement,
+        };
+        let base = spawn_app(state).await;
+
+        let body: serde_json::Value = reqwest::get(format!("{base}/admin/insights?class=fork_storm"))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        let records = body.as_array().unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0]["insight"]["class"], "fork_storm");
+
+        let body: serde_json::Value = reqwest::get(format!("{base}/admin/insights?min_confidence=0.5"))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(body.as_array().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn fetches_one_insight_by_id_and_404s_on_unknown_id() {
+        let insights = Arc::new(InsightStore::new(8, None));
+        insights.record(sample_insight(InsightClass::CpuSpin, 0.7));
+        let (_tmp, incidents) = test_incident_store().await;
+        let (_enf, enforcement) = test_enforcement_queue().await;
+        let state = AdminApiState {
+            insights: Arc::clone(&insights),
+            metrics: Arc::new(Metrics::new()),
+            incidents,
+            enforcement,
+        };
+        let base = spawn_app(state).await;
+
+        let id = insights.recent(1)[0].id;
+        let resp = reqwest::get(format!("{base}/admin/insights/{id}")).await.unwrap();
+        assert_eq!(resp.status(), reqwest::StatusCode::OK);
+
+        let resp = reqwest::get(format!("{base}/admin/insights/999999")).await.unwrap();
+        assert_eq!(resp.status(), reqwest::StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn counters_reflect_metrics_snapshot() {
+        let metrics = Arc::new(Metrics::new());
+        metrics.inc_ilm_insights();
+        metrics.inc_ilm_insights();
+        metrics.inc_alerts_emitted();
+        let (_tmp, incidents) = test_incident_store().await;
+        let (_enf, enforcement) = test_enforcement_queue().await;
+        let state = AdminApiState {
+            insights: Arc::new(InsightStore::new(8, None)),
+            metrics,
+            incidents,
+            enforcement,
+        };
+        let base = spawn_app(state).await;
+
+        let body: Counters = reqwest::get(format!("{base}/admin/counters"))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(body.ilm_insights, 2);
+        assert_eq!(body.alerts_emitted, 1);
+    }
+
+    #[tokio::test]
+    async fn exports_and_reimports_incidents_as_jsonl() {
+        let (_tmp, incidents) = test_incident_store().await;
+        incidents
+            .insert(&crate::incidents::Incident {
+                id: None,
+                timestamp: 1,
+                event_type: "circuit_breaker".to_string(),
+                psi_cpu: 90.0,
+                psi_memory: 10.0,
+                cpu_percent: 95.0,
+                load_avg: "1.0,1.0,1.0".to_string(),
+                action: "kill".to_string(),
+                target_pid: Some(1234),
+                target_name: Some("runaway".to_string()),
+                system_snapshot: None,
+                llm_analysis: None,
+                llm_analyzed_at: None,
+                recovery_time_ms: None,
+                psi_after: None,
+            })
+            .await
+            .unwrap();
+        let (_enf, enforcement) = test_enforcement_queue().await;
+        let state = AdminApiState {
+            insights: Arc::new(InsightStore::new(8, None)),
+            metrics: Arc::new(Metrics::new()),
+            incidents,
+            enforcement,
+        };
+        let base = spawn_app(state).await;
+
+        let exported = reqwest::get(format!("{base}/admin/incidents/export"))
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+        assert_eq!(exported.lines().count(), 1);
+
+        let (_tmp2, fresh) = test_incident_store().await;
+        let (_enf2, enforcement) = test_enforcement_queue().await;
+        let state = AdminApiState {
+            insights: Arc::new(InsightStore::new(8, None)),
+            metrics: Arc::new(Metrics::new()),
+            incidents: fresh,
+            enforcement,
+        };
+        let base = spawn_app(state).await;
+
+        let client = reqwest::Client::new();
+        let resp = client
+            .post(format!("{base}/admin/incidents/import"))
+            .body(exported)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), reqwest::StatusCode::OK);
+        let body: serde_json::Value = resp.json().await.unwrap();
+        assert_eq!(body["inserted"], 1);
+    }
+
+    #[tokio::test]
+    async fn remediate_insight_proposes_approves_and_executes_the_action() {
+        let insights = Arc::new(InsightStore::new(8, None));
+        let mut insight = sample_insight(InsightClass::CpuSpin, 0.9);
+        // A pid that (almost certainly) doesn't exist on the test host, so
+        // `ProcessIdentity::read` fails and the action resolves to
+        // `Failed` rather than touching a real process -- same convention
+        // `execute_refuses_on_identity_mismatch` in enforcement.rs uses.
+        insight.primary_process = Some(999_999);
+        insights.record(insight);
+        let (_tmp, incidents) = test_incident_store().await;
+        let (_enf, enforcement) = test_enforcement_queue().await;
+        let enforcement_check = Arc::clone(&enforcement);
+        let state = AdminApiState {
+            insights: Arc::clone(&insights),
+            metrics: Arc::new(Metrics::new()),
+            incidents,
+            enforcement,
+        };
+        let base = spawn_app(state).await;
+        let id = insights.recent(1)[0].id;
+
+        let client = reqwest::Client::new();
+        let resp = client
+            .post(format!("{base}/insights/{id}/remediate"))
+            .json(&serde_json::json!({"action": "signal", "signal": "SIGTERM"}))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), reqwest::StatusCode::OK);
+        let body: RemediateResponse = resp.json().await.unwrap();
+        assert_eq!(body.outcome, "failed");
+
+        // The route isn't just shaping a plausible-looking response -- it
+        // really did go through `EnforcementQueue::propose`/`execute`, so
+        // the action shows up in the same queue the CLI's `Remediate`
+        // subcommand and the LLM's auto-approval path both post into.
+        let actions = enforcement_check.get_all().await.unwrap();
+        assert_eq!(actions.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn remediate_insight_404s_on_unknown_id() {
+        let insights = Arc::new(InsightStore::new(8, None));
+        let (_tmp, incidents) = test_incident_store().await;
+        let (_enf, enforcement) = test_enforcement_queue().await;
+        let state = AdminApiState {
+            insights,
+            metrics: Arc::new(Metrics::new()),
+            incidents,
+            enforcement,
+        };
+        let base = spawn_app(state).await;
+
+        let client = reqwest::Client::new();
+        let resp = client
+            .post(format!("{base}/insights/999999/remediate"))
+            .json(&serde_json::json!({"action": "signal", "signal": "SIGTERM"}))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), reqwest::StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn remediate_insight_422s_when_insight_has_no_primary_process() {
+        let insights = Arc::new(InsightStore::new(8, None));
+        insights.record(sample_insight(InsightClass::CpuSpin, 0.9));
+        let (_tmp, incidents) = test_incident_store().await;
+        let (_enf, enforcement) = test_enforcement_queue().await;
+        let state = AdminApiState {
+            insights: Arc::clone(&insights),
+            metrics: Arc::new(Metrics::new()),
+            incidents,
+            enforcement,
+        };
+        let base = spawn_app(state).await;
+        let id = insights.recent(1)[0].id;
+
+        let client = reqwest::Client::new();
+        let resp = client
+            .post(format!("{base}/insights/{id}/remediate"))
+            .json(&serde_json::json!({"action": "signal", "signal": "SIGTERM"}))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), reqwest::StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    #[tokio::test]
+    async fn capabilities_route_reports_protocol_version_and_enabled_subsystems() {
+        let insights = Arc::new(InsightStore::new(8, None));
+        let (_tmp, incidents) = test_incident_store().await;
+        let (_enf, enforcement) = test_enforcement_queue().await;
+        let state = AdminApiState {
+            insights,
+            metrics: Arc::new(Metrics::new()),
+            incidents,
+            enforcement,
+        };
+        let base = spawn_app(state).await;
+
+        let client = reqwest::Client::new();
+        let resp = client
+            .get(format!("{base}/capabilities"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), reqwest::StatusCode::OK);
+        let body: crate::capabilities::Capabilities = resp.json().await.unwrap();
+        assert_eq!(body.protocol_version, crate::capabilities::PROTOCOL_VERSION);
+        assert!(body.ilm);
+    }
+}
@@ -0,0 +1,67 @@
+//! `/capabilities` endpoint for protocol and feature negotiation with
+//! clients such as `linnix-cli`.
+//!
+//! A client fetches this once, before trusting the shape of `/status` or
+//! any `/admin/*` response, and compares `protocol_version` against the
+//! range it understands: downgrade gracefully (skip checks for an absent
+//! capability) when the agent is older, and refuse with a clear message
+//! when the agent is newer than the client knows how to talk to. Response
+//! fields only ever get added across versions, never removed or
+//! repurposed, so older clients keep deserializing newer responses.
+
+use axum::Json;
+use axum::extract::State;
+use serde::{Deserialize, Serialize};
+
+/// Bumped whenever a wire-incompatible change lands in `/status` or any
+/// `/admin/*` response shape. Additive changes (a new optional field)
+/// don't require a bump.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// The agent's protocol version plus which optional subsystems are
+/// actually enabled in this build/config, so a client can skip checks for
+/// ones that aren't rather than rendering a misleading "MISSING"/"N/A".
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Capabilities {
+    pub protocol_version: u32,
+    pub binary_stream: bool,
+    pub rss_probe: bool,
+    pub ilm: bool,
+    pub slack: bool,
+    pub incident_store: bool,
+    pub telemetry_export: bool,
+}
+
+impl Capabilities {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        binary_stream: bool,
+        rss_probe: bool,
+        ilm: bool,
+        slack: bool,
+        incident_store: bool,
+        telemetry_export: bool,
+    ) -> Self {
+        Self {
+            protocol_version: PROTOCOL_VERSION,
+            binary_stream,
+            rss_probe,
+            ilm,
+            slack,
+            incident_store,
+            telemetry_export,
+        }
+    }
+}
+
+/// Shared state for [`capabilities_handler`]. The binary computes
+/// [`Capabilities`] once at startup from its resolved config and eBPF
+/// probe state, since none of it changes at runtime.
+#[derive(Clone)]
+pub struct CapabilitiesState(pub Capabilities);
+
+/// `GET /capabilities` -- the agent's protocol version and enabled
+/// subsystems.
+pub async fn capabilities_handler(State(state): State<CapabilitiesState>) -> Json<Capabilities> {
+    Json(state.0)
+}
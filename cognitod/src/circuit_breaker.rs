@@ -0,0 +1,271 @@
+//! PSI-based circuit breaker for expensive/disruptive cognitod actions.
+//!
+//! "100% CPU" isn't stress -- stalling is. This gates actions like LLM
+//! calls, process kills, and heavy scans on [`PsiMetrics`]'s `full` stall
+//! percentages (the fraction of time *every* runnable task was blocked,
+//! which maps directly to lost throughput) rather than raw CPU usage, and
+//! keys on `avg10` specifically for responsiveness to a real incident
+//! instead of the slower-moving `avg60`/`avg300` windows.
+//!
+//! Three states per resource, the classic circuit-breaker pattern:
+//! `Closed` (normal) -> `Open` (tripped, actions refused) for a cooldown
+//! window -> `HalfOpen` to re-sample once before deciding whether to
+//! `Closed` again or re-`Open`. The close threshold (low-water) is lower
+//! than the trip threshold (high-water) -- hysteresis -- so a reading
+//! hovering right at the line doesn't flap the breaker every tick.
+
+use std::time::{Duration, Instant};
+
+use crate::utils::psi::PsiMetrics;
+
+/// Coarse class of action the breaker gates; callers map their specific
+/// operation onto one of these before calling `should_allow`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ActionClass {
+    LlmCall,
+    ProcessKill,
+    HeavyScan,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum State {
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct CircuitBreakerConfig {
+    /// `full_avg10` percentage at or above which the breaker trips to `Open`.
+    pub high_water_pct: f32,
+    /// `full_avg10` percentage at or below which a `HalfOpen` re-sample closes.
+    pub low_water_pct: f32,
+    /// How long the breaker stays `Open` before moving to `HalfOpen`.
+    pub cooldown: Duration,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            high_water_pct: 20.0,
+            low_water_pct: 5.0,
+            cooldown: Duration::from_secs(30),
+        }
+    }
+}
+
+/// One resource's (memory or I/O) breaker state machine.
+struct ResourceBreaker {
+    state: State,
+    opened_at: Option<Instant>,
+    last_full_avg10: f32,
+}
+
+impl ResourceBreaker {
+    fn new() -> Self {
+        Self {
+            state: State::Closed,
+            opened_at: None,
+            last_full_avg10: 0.0,
+        }
+    }
+
+    fn poll(&mut self, full_avg10: f32, config: &CircuitBreakerConfig) -> State {
+        self.last_full_avg10 = full_avg10;
+        match self.state {
+            State::Closed => {
+                if full_avg10 >= config.high_water_pct {
+                    self.state = State::Open;
+                    self.opened_at = Some(Instant::now());
+                }
+            }
+            State::Open => {
+                if self.opened_at.is_some_and(|at| at.elapsed() >= config.cooldown) {
+                    self.state = State::HalfOpen;
+                }
+            }
+            State::HalfOpen => {
+                if full_avg10 <= config.low_water_pct {
+                    self.state = State::Closed;
+                    self.opened_at = None;
+                } else {
+                    self.state = State::Open;
+                    self.opened_at = Some(Instant::now());
+                }
+            }
+        }
+        self.state
+    }
+}
+
+fn most_severe(a: State, b: State) -> State {
+    fn rank(state: State) -> u8 {
+        match state {
+            State::Open => 2,
+            State::HalfOpen => 1,
+            State::Closed => 0,
+        }
+    }
+    if rank(a) >= rank(b) { a } else { b }
+}
+
+/// Gates expensive/disruptive actions on memory and I/O stall via
+/// independent resource breakers, so a memory thrash doesn't mask (or get
+/// masked by) an I/O saturation event.
+pub struct CircuitBreaker {
+    config: CircuitBreakerConfig,
+    memory: ResourceBreaker,
+    io: ResourceBreaker,
+}
+
+impl CircuitBreaker {
+    pub fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            config,
+            memory: ResourceBreaker::new(),
+            io: ResourceBreaker::new(),
+        }
+    }
+
+    /// Re-sample both resource breakers against the latest PSI snapshot.
+    /// Returns the more severe of the two states (`Open` > `HalfOpen` >
+    /// `Closed`) for callers that just want one headline state.
+    pub fn poll(&mut self, metrics: &PsiMetrics) -> State {
+        let memory_state = self.memory.poll(metrics.memory_full_avg10(), &self.config);
+        let io_state = self.io.poll(metrics.io_full_avg10(), &self.config);
+        most_severe(memory_state, io_state)
+    }
+
+    /// Whether `action` should be allowed to proceed right now, based on the
+    /// state as of the last `poll`. Every action class is refused while
+    /// `Open`. `HalfOpen` differentiates: a [`ActionClass::ProcessKill`] is
+    /// itself corrective -- it relieves exactly the pressure that tripped
+    /// the breaker -- so it stays allowed through the `HalfOpen` probe,
+    /// while an `LlmCall` or `HeavyScan` is itself an expensive/disruptive
+    /// action and sits out the probe too, only clearing once `Closed`.
+    pub fn should_allow(&self, action: ActionClass) -> bool {
+        let state = most_severe(self.memory.state, self.io.state);
+        match action {
+            ActionClass::ProcessKill => state != State::Open,
+            ActionClass::LlmCall | ActionClass::HeavyScan => state == State::Closed,
+        }
+    }
+
+    /// Human-readable explanation of the current state, for logging and
+    /// insight `why` strings.
+    pub fn reason(&self) -> String {
+        format!(
+            "memory={:?} (full_avg10={:.1}%) io={:?} (full_avg10={:.1}%) high_water={:.1}% low_water={:.1}%",
+            self.memory.state,
+            self.memory.last_full_avg10,
+            self.io.state,
+            self.io.last_full_avg10,
+            self.config.high_water_pct,
+            self.config.low_water_pct,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::psi::{Pressure, PressureRecord};
+
+    fn metrics_with_full_avg10(memory_full: f32, io_full: f32) -> PsiMetrics {
+        PsiMetrics {
+            cpu: Pressure::default(),
+            memory: Pressure {
+                some: PressureRecord::default(),
+                full: Some(PressureRecord {
+                    avg10: memory_full,
+                    ..Default::default()
+                }),
+            },
+            io: Pressure {
+                some: PressureRecord::default(),
+                full: Some(PressureRecord {
+                    avg10: io_full,
+                    ..Default::default()
+                }),
+            },
+        }
+    }
+
+    fn fast_cooldown_config() -> CircuitBreakerConfig {
+        CircuitBreakerConfig {
+            cooldown: Duration::from_millis(10),
+            ..CircuitBreakerConfig::default()
+        }
+    }
+
+    #[test]
+    fn stays_closed_under_the_high_water_mark() {
+        let mut breaker = CircuitBreaker::new(CircuitBreakerConfig::default());
+        let state = breaker.poll(&metrics_with_full_avg10(2.0, 2.0));
+        assert_eq!(state, State::Closed);
+        assert!(breaker.should_allow(ActionClass::ProcessKill));
+    }
+
+    #[test]
+    fn trips_open_above_the_high_water_mark_and_refuses_actions() {
+        let mut breaker = CircuitBreaker::new(CircuitBreakerConfig::default());
+        let state = breaker.poll(&metrics_with_full_avg10(25.0, 2.0));
+        assert_eq!(state, State::Open);
+        assert!(!breaker.should_allow(ActionClass::LlmCall));
+        assert!(!breaker.should_allow(ActionClass::HeavyScan));
+    }
+
+    #[test]
+    fn half_open_resample_reopens_if_still_above_the_low_water_mark() {
+        let mut breaker = CircuitBreaker::new(fast_cooldown_config());
+        breaker.poll(&metrics_with_full_avg10(25.0, 2.0));
+        assert_eq!(breaker.poll(&metrics_with_full_avg10(25.0, 2.0)), State::Open);
+
+        std::thread::sleep(Duration::from_millis(15));
+        // First poll after cooldown elapses just moves Open -> HalfOpen.
+        let half_open = breaker.poll(&metrics_with_full_avg10(25.0, 2.0));
+        assert_eq!(half_open, State::HalfOpen);
+
+        // The following poll in HalfOpen evaluates pressure: still above
+        // the low-water mark, so it re-opens rather than closing.
+        let state = breaker.poll(&metrics_with_full_avg10(25.0, 2.0));
+        assert_eq!(state, State::Open);
+    }
+
+    #[test]
+    fn closes_from_half_open_once_pressure_falls_below_the_low_water_mark() {
+        let mut breaker = CircuitBreaker::new(fast_cooldown_config());
+        breaker.poll(&metrics_with_full_avg10(25.0, 2.0));
+
+        std::thread::sleep(Duration::from_millis(15));
+        // First re-sample after cooldown moves Open -> HalfOpen without
+        // re-evaluating pressure; the *following* poll evaluates it.
+        breaker.poll(&metrics_with_full_avg10(25.0, 2.0));
+        let state = breaker.poll(&metrics_with_full_avg10(2.0, 2.0));
+        assert_eq!(state, State::Closed);
+        assert!(breaker.should_allow(ActionClass::ProcessKill));
+    }
+
+    #[test]
+    fn half_open_allows_process_kill_but_refuses_llm_call_and_heavy_scan() {
+        let mut breaker = CircuitBreaker::new(fast_cooldown_config());
+        breaker.poll(&metrics_with_full_avg10(25.0, 2.0));
+
+        std::thread::sleep(Duration::from_millis(15));
+        let state = breaker.poll(&metrics_with_full_avg10(25.0, 2.0));
+        assert_eq!(state, State::HalfOpen);
+
+        assert!(breaker.should_allow(ActionClass::ProcessKill));
+        assert!(!breaker.should_allow(ActionClass::LlmCall));
+        assert!(!breaker.should_allow(ActionClass::HeavyScan));
+    }
+
+    #[test]
+    fn reason_mentions_both_resources() {
+        let mut breaker = CircuitBreaker::new(CircuitBreakerConfig::default());
+        breaker.poll(&metrics_with_full_avg10(25.0, 30.0));
+        let reason = breaker.reason();
+        assert!(reason.contains("memory="));
+        assert!(reason.contains("io="));
+    }
+}
@@ -8,9 +8,21 @@ use tokio::time::sleep;
 use walkdir::WalkDir;
 
 use crate::k8s::K8sContext;
+use crate::telemetry::TelemetryRegistry;
+use crate::triage::{Facts, TriageEngine};
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct PsiLine {
+    pub avg10: f32,
+    pub avg60: f32,
+    pub avg300: f32,
+    pub total: u64,
+}
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct PsiSnapshot {
+    pub some: PsiLine,
+    pub full: PsiLine,
     pub some_total: u64,
     pub full_total: u64,
 }
@@ -23,9 +35,28 @@ pub struct PsiDelta {
     pub timestamp: std::time::Instant,
 }
 
+/// Parse one `some`/`full` line of a PSI file into its avg10/avg60/avg300/total
+/// fields. Missing fields default to 0.0/0, so older kernels that omit a
+/// field (or the whole `full` line, as `cpu.pressure` does) still parse.
+fn parse_psi_line(parts: &[&str]) -> PsiLine {
+    let mut line = PsiLine::default();
+    for part in parts {
+        if let Some((key, value)) = part.split_once('=') {
+            match key {
+                "avg10" => line.avg10 = value.parse().unwrap_or(0.0),
+                "avg60" => line.avg60 = value.parse().unwrap_or(0.0),
+                "avg300" => line.avg300 = value.parse().unwrap_or(0.0),
+                "total" => line.total = value.parse().unwrap_or(0),
+                _ => {}
+            }
+        }
+    }
+    line
+}
+
 pub fn parse_psi_file(content: &str) -> Result<PsiSnapshot> {
-    let mut some_total = 0u64;
-    let mut full_total = 0u64;
+    let mut some = PsiLine::default();
+    let mut full = PsiLine::default();
 
     for line in content.lines() {
         let parts: Vec<&str> = line.split_whitespace().collect();
@@ -33,43 +64,119 @@ pub fn parse_psi_file(content: &str) -> Result<PsiSnapshot> {
             continue;
         }
 
-        let prefix = parts[0];
-        if prefix != "some" && prefix != "full" {
-            continue;
-        }
-
-        for part in &parts[1..] {
-            if let Some((key, value)) = part.split_once('=')
-                && key == "total"
-                && let Ok(v) = value.parse::<u64>()
-            {
-                if prefix == "some" {
-                    some_total = v;
-                } else {
-                    full_total = v;
-                }
-            }
+        match parts[0] {
+            "some" => some = parse_psi_line(&parts[1..]),
+            "full" => full = parse_psi_line(&parts[1..]),
+            _ => continue,
         }
     }
 
     Ok(PsiSnapshot {
-        some_total,
-        full_total,
+        some,
+        full,
+        some_total: some.total,
+        full_total: full.total,
     })
 }
 
-fn find_psi_files(base_path: &Path) -> Vec<PathBuf> {
-    WalkDir::new(base_path)
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .filter(|e| {
-            e.path().file_name().is_some_and(|n| n == "cpu.pressure")
-                && e.path().to_string_lossy().contains("kubepods")
-        })
-        .map(|e| e.path().to_path_buf())
-        .collect()
+/// Which pressure resource a `*.pressure` file reports on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Resource {
+    Cpu,
+    Memory,
+    Io,
 }
 
+impl Resource {
+    fn from_file_name(name: &str) -> Option<Self> {
+        match name {
+            "cpu.pressure" => Some(Self::Cpu),
+            "memory.pressure" => Some(Self::Memory),
+            "io.pressure" => Some(Self::Io),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Cpu => "cpu",
+            Self::Memory => "memory",
+            Self::Io => "io",
+        }
+    }
+}
+
+/// Cgroup hierarchy in use on this host.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CgroupVersion {
+    /// Unified hierarchy: `/sys/fs/cgroup/<slice>/.../{cpu,memory,io}.pressure`.
+    V2,
+    /// Per-controller hierarchy: `/sys/fs/cgroup/{cpu,memory,blkio}/<slice>/...`.
+    V1,
+}
+
+/// Detect which cgroup hierarchy is mounted, per the `cgroup.controllers`
+/// file that only exists under the v2 unified hierarchy.
+fn detect_cgroup_version(base_path: &Path) -> CgroupVersion {
+    if base_path.join("cgroup.controllers").exists() {
+        CgroupVersion::V2
+    } else {
+        CgroupVersion::V1
+    }
+}
+
+/// Walk the cgroup hierarchy and return every `{cpu,memory,io}.pressure`
+/// file found for a container, tagged with which resource it reports.
+///
+/// Under cgroup v1, PSI files live per-controller (`cpu/`, `memory/`,
+/// `blkio/` mount points) rather than under a single unified tree, so we
+/// walk each controller's kubepods subtree separately.
+fn find_psi_files(base_path: &Path) -> Vec<(PathBuf, Resource)> {
+    match detect_cgroup_version(base_path) {
+        CgroupVersion::V2 => WalkDir::new(base_path)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter_map(|e| {
+                let resource = Resource::from_file_name(e.path().file_name()?.to_str()?)?;
+                e.path()
+                    .to_string_lossy()
+                    .contains("kubepods")
+                    .then(|| (e.path().to_path_buf(), resource))
+            })
+            .collect(),
+        CgroupVersion::V1 => {
+            const V1_CONTROLLERS: &[(&str, Resource)] = &[
+                ("cpu", Resource::Cpu),
+                ("cpu,cpuacct", Resource::Cpu),
+                ("memory", Resource::Memory),
+                ("blkio", Resource::Io),
+            ];
+            V1_CONTROLLERS
+                .iter()
+                .flat_map(|(dir, resource)| {
+                    let controller_root = base_path.join(dir);
+                    let file_name = match resource {
+                        Resource::Cpu => "cpu.pressure",
+                        Resource::Memory => "memory.pressure",
+                        Resource::Io => "io.pressure",
+                    };
+                    WalkDir::new(controller_root)
+                        .into_iter()
+                        .filter_map(|e| e.ok())
+                        .filter(move |e| e.path().file_name().is_some_and(|n| n == file_name))
+                        .filter(|e| e.path().to_string_lossy().contains("kubepods"))
+                        .map(|e| (e.path().to_path_buf(), *resource))
+                        .collect::<Vec<_>>()
+                })
+                .collect()
+        }
+    }
+}
+
+/// Extract the 64-char container ID from a cgroup scope/slice path, handling
+/// both the v2 unified naming (`cri-containerd-<id>.scope`) and v1 naming
+/// (`docker-<id>.scope` nested under a per-controller `kubepods*` slice), as
+/// well as CRI-O's `crio-<id>.scope` convention.
 fn extract_container_id(cgroup_path: &Path) -> Option<String> {
     let parent = cgroup_path.parent()?;
     let dir_name = parent.file_name()?.to_string_lossy();
@@ -82,11 +189,40 @@ fn extract_container_id(cgroup_path: &Path) -> Option<String> {
     (id.len() == 64).then(|| id.to_string())
 }
 
+/// Build the [`Facts`] a [`TriageEngine`] evaluates against for one
+/// (pod, resource) tick: the `some` line's three rolling averages plus the
+/// stall delta since the previous scan.
+fn triage_facts(snapshot: &PsiSnapshot, delta_stall_us: u64) -> Facts {
+    let mut facts = Facts::new();
+    facts
+        .set_num("psi_some_avg10", snapshot.some.avg10 as f64)
+        .set_num("psi_some_avg60", snapshot.some.avg60 as f64)
+        .set_num("psi_some_avg300", snapshot.some.avg300 as f64)
+        .set_num("delta_stall_us", delta_stall_us as f64);
+    facts
+}
+
 const HISTORY_SIZE: usize = 10;
 
+/// A delta spike above this (microseconds stalled since the last scan) is
+/// worth logging as a transient stall. Scans run every second, so this is
+/// ~0.5% of the tick spent stalled -- enough to separate a real spike from
+/// the handful of microseconds that show up on an essentially idle cgroup.
+const TRANSIENT_STALL_THRESHOLD_US: u64 = 5_000;
+/// avg300 at or above this percentage indicates stall has persisted for ~5 minutes,
+/// i.e. a plateau rather than a one-off spike.
+const SUSTAINED_AVG300_THRESHOLD: f32 = 10.0;
+
+/// History is keyed by (pod, resource) so CPU, memory, and IO stall deltas
+/// are tracked independently - a memory plateau shouldn't be masked by a
+/// quiet CPU history for the same pod, and vice versa.
+type HistoryKey = (String, Resource);
+
 pub struct PsiMonitor {
     k8s_ctx: Arc<K8sContext>,
-    history: HashMap<String, VecDeque<PsiSnapshot>>,
+    history: HashMap<HistoryKey, VecDeque<PsiSnapshot>>,
+    telemetry: Option<Arc<TelemetryRegistry>>,
+    triage: Option<TriageEngine>,
 }
 
 impl PsiMonitor {
@@ -94,35 +230,103 @@ impl PsiMonitor {
         Self {
             k8s_ctx,
             history: HashMap::new(),
+            telemetry: None,
+            triage: None,
         }
     }
 
+    /// Attach a telemetry registry; stall deltas will update its gauges in
+    /// addition to being logged, so operators can graph them in Prometheus.
+    pub fn with_telemetry(mut self, telemetry: Arc<TelemetryRegistry>) -> Self {
+        self.telemetry = Some(telemetry);
+        self
+    }
+
+    /// Attach a triage rule engine; when present it replaces the hardcoded
+    /// sustained/transient stall checks below with whatever rules the
+    /// operator configured, so thresholds can be tuned without recompiling.
+    pub fn with_triage(mut self, triage: TriageEngine) -> Self {
+        self.triage = Some(triage);
+        self
+    }
+
     pub async fn run(mut self) {
         info!("[psi] starting PSI monitor");
         let base_path = Path::new("/sys/fs/cgroup");
 
         loop {
             let psi_files = find_psi_files(base_path);
-            debug!("[psi] scanning {} cgroups", psi_files.len());
+            debug!("[psi] scanning {} cgroup pressure files", psi_files.len());
 
-            for path in psi_files {
+            for (path, resource) in psi_files {
                 if let Some(container_id) = extract_container_id(&path)
                     && let Some(meta) = self.k8s_ctx.get_metadata(&container_id)
                     && let Ok(content) = std::fs::read_to_string(&path)
                     && let Ok(snapshot) = parse_psi_file(&content)
                 {
-                    let key = format!("{}/{}", meta.namespace, meta.pod_name);
+                    let key = (
+                        format!("{}/{}", meta.namespace, meta.pod_name),
+                        resource,
+                    );
 
-                    // Get or create history for this pod
+                    // Get or create history for this (pod, resource) pair
                     let hist = self.history.entry(key.clone()).or_default();
 
                     // Calculate delta if we have previous snapshot
                     if let Some(prev) = hist.back() {
-                        let delta_stall = snapshot.some_total.saturating_sub(prev.some_total);
-                        if delta_stall > 0 {
+                        let delta_stall = snapshot.some.total.saturating_sub(prev.some.total);
+                        let sustained = snapshot.some.avg300 >= SUSTAINED_AVG300_THRESHOLD;
+
+                        if let Some(telemetry) = &self.telemetry {
+                            telemetry.set_psi_stall_delta(
+                                &meta.namespace,
+                                &meta.pod_name,
+                                resource.as_str(),
+                                delta_stall,
+                            );
+                        }
+
+                        if let Some(engine) = self.triage.as_mut() {
+                            let facts = triage_facts(&snapshot, delta_stall);
+
+                            for firing in engine.evaluate(&facts) {
+                                if firing.kill {
+                                    log::warn!(
+                                        target: "linnix_audit",
+                                        "[psi] {}/{} resource={} triage action '{}' fired: {}",
+                                        meta.namespace,
+                                        meta.pod_name,
+                                        resource.as_str(),
+                                        firing.action,
+                                        firing.why
+                                    );
+                                } else {
+                                    info!(
+                                        "[psi] {}/{} resource={} triage action '{}' fired: {}",
+                                        meta.namespace,
+                                        meta.pod_name,
+                                        resource.as_str(),
+                                        firing.action,
+                                        firing.why
+                                    );
+                                }
+                            }
+                        } else if sustained {
                             info!(
-                                "[psi] {}/{} delta_stall_us={}",
-                                meta.namespace, meta.pod_name, delta_stall
+                                "[psi] {}/{} resource={} sustained stall avg60={:.1} avg300={:.1}",
+                                meta.namespace,
+                                meta.pod_name,
+                                resource.as_str(),
+                                snapshot.some.avg60,
+                                snapshot.some.avg300
+                            );
+                        } else if delta_stall > TRANSIENT_STALL_THRESHOLD_US {
+                            info!(
+                                "[psi] {}/{} resource={} transient delta_stall_us={}",
+                                meta.namespace,
+                                meta.pod_name,
+                                resource.as_str(),
+                                delta_stall
                             );
                         }
                     }
@@ -155,6 +359,74 @@ mod tests {
         assert_eq!(snapshot.full_total, 654321);
     }
 
+    #[test]
+    fn test_parse_psi_file_full_vector() {
+        let content = "some avg10=5.23 avg60=3.45 avg300=2.11 total=123456\nfull avg10=0.12 avg60=0.08 avg300=0.05 total=78901";
+        let snapshot = parse_psi_file(content).unwrap();
+
+        assert_eq!(snapshot.some.avg10, 5.23);
+        assert_eq!(snapshot.some.avg60, 3.45);
+        assert_eq!(snapshot.some.avg300, 2.11);
+        assert_eq!(snapshot.full.avg10, 0.12);
+    }
+
+    #[test]
+    fn test_parse_psi_file_missing_fields_default_to_zero() {
+        // Older kernels (e.g. cpu.pressure pre-5.13) may omit some fields.
+        let content = "some avg10=1.00 total=10";
+        let snapshot = parse_psi_file(content).unwrap();
+
+        assert_eq!(snapshot.some.avg10, 1.0);
+        assert_eq!(snapshot.some.avg60, 0.0);
+        assert_eq!(snapshot.some.avg300, 0.0);
+        assert_eq!(snapshot.full.avg10, 0.0);
+    }
+
+    #[test]
+    fn test_extract_container_id_crio() {
+        let path = Path::new(
+            "/sys/fs/cgroup/machine.slice/crio-e4063920952d766348421832d2df465324397166164478852332152342342342.scope/memory.pressure",
+        );
+        let id = extract_container_id(path).unwrap();
+        assert_eq!(
+            id,
+            "e4063920952d766348421832d2df465324397166164478852332152342342342"
+        );
+    }
+
+    #[test]
+    fn test_resource_from_file_name() {
+        assert_eq!(Resource::from_file_name("cpu.pressure"), Some(Resource::Cpu));
+        assert_eq!(Resource::from_file_name("memory.pressure"), Some(Resource::Memory));
+        assert_eq!(Resource::from_file_name("io.pressure"), Some(Resource::Io));
+        assert_eq!(Resource::from_file_name("cgroup.procs"), None);
+    }
+
+    #[test]
+    fn triage_facts_expose_avg_lines_and_stall_delta() {
+        let snapshot = parse_psi_file(
+            "some avg10=12.00 avg60=8.00 avg300=4.00 total=100\nfull avg10=0.0 avg60=0.0 avg300=0.0 total=0",
+        )
+        .unwrap();
+        let facts = triage_facts(&snapshot, 9_000);
+
+        let mut engine = crate::triage::TriageEngine::new(crate::triage::TriageConfig {
+            metrics: [("thrash".to_string(), "delta_stall_us > 5000".to_string())].into(),
+            actions: vec![crate::triage::ActionConfig {
+                name: "stall".to_string(),
+                trigger: "$thrash".to_string(),
+                why: "avg10={psi_some_avg10}".to_string(),
+                snooze_secs: 0,
+                kill: false,
+            }],
+        })
+        .unwrap();
+
+        let fired = engine.evaluate(&facts);
+        assert_eq!(fired.len(), 1);
+        assert!(fired[0].why.contains("12.0"));
+    }
+
     #[test]
     fn test_extract_container_id() {
         let path = Path::new(
@@ -0,0 +1,167 @@
+//! Consul-based [`MetadataProvider`](crate::metadata_provider::MetadataProvider).
+//! Discovers workload identity and priority from a Consul catalog/health
+//! endpoint instead of a Kubernetes apiserver, so Nomad and bare
+//! Consul-registered processes get the same priority/SLO enrichment
+//! Kubernetes users get via [`crate::k8s::K8sContext`].
+//!
+//! Priority and SLO tier come from service tag conventions, the same way
+//! `K8sContext` reads them from pod labels: `linnix.priority=<tier>` and
+//! `linnix.slo_tier=<tier>`. The pid is read from each service instance's
+//! registered `Meta["pid"]` -- instances that don't register a pid are
+//! discoverable by name but not resolvable from a pid lookup.
+
+use log::{debug, info, warn};
+use reqwest::Client;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::time::sleep;
+
+use crate::k8s::Priority;
+use crate::metadata_provider::{MetadataProvider, WorkloadMetadata};
+
+pub struct ConsulContext {
+    pid_map: RwLock<HashMap<u32, WorkloadMetadata>>,
+    client: Client,
+    consul_url: String,
+}
+
+impl ConsulContext {
+    pub fn new(consul_url: String) -> Arc<Self> {
+        Arc::new(Self {
+            pid_map: RwLock::new(HashMap::new()),
+            client: Client::new(),
+            consul_url,
+        })
+    }
+
+    pub fn start_watcher(self: Arc<Self>) {
+        tokio::spawn(async move {
+            info!("[consul] starting service watcher against {}", self.consul_url);
+            loop {
+                if let Err(e) = self.refresh_services().await {
+                    warn!("[consul] failed to refresh services: {}", e);
+                }
+                sleep(Duration::from_secs(30)).await;
+            }
+        });
+    }
+
+    async fn refresh_services(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let url = format!("{}/v1/catalog/services", self.consul_url);
+        let resp = self.client.get(&url).send().await?;
+        if !resp.status().is_success() {
+            return Err(format!("consul catalog error: {}", resp.status()).into());
+        }
+        let services: HashMap<String, Vec<String>> = resp.json().await?;
+
+        let mut new_map = HashMap::new();
+        for name in services.keys() {
+            let url = format!("{}/v1/health/service/{}", self.consul_url, name);
+            let resp = match self.client.get(&url).send().await {
+                Ok(resp) if resp.status().is_success() => resp,
+                Ok(resp) => {
+                    warn!("[consul] health lookup for {} failed: {}", name, resp.status());
+                    continue;
+                }
+                Err(e) => {
+                    warn!("[consul] health lookup for {} failed: {}", name, e);
+                    continue;
+                }
+            };
+            let entries: Vec<ConsulHealthEntry> = match resp.json().await {
+                Ok(entries) => entries,
+                Err(e) => {
+                    warn!("[consul] health payload for {} was malformed: {}", name, e);
+                    continue;
+                }
+            };
+            for entry in entries {
+                let Some(pid) = entry
+                    .service
+                    .meta
+                    .get("pid")
+                    .and_then(|p| p.parse::<u32>().ok())
+                else {
+                    continue;
+                };
+                let (priority, slo_tier) = priority_from_tags(&entry.service.tags);
+                new_map.insert(
+                    pid,
+                    WorkloadMetadata {
+                        workload_name: entry.service.service.clone(),
+                        namespace: None,
+                        container_name: None,
+                        owner_kind: Some("ConsulService".to_string()),
+                        owner_name: Some(name.clone()),
+                        priority,
+                        slo_tier,
+                    },
+                );
+            }
+        }
+
+        debug!("[consul] refreshed service map, {} pids tracked", new_map.len());
+        *self.pid_map.write().unwrap() = new_map;
+        Ok(())
+    }
+}
+
+impl MetadataProvider for ConsulContext {
+    fn metadata_for_pid(&self, pid: u32) -> Option<WorkloadMetadata> {
+        self.pid_map.read().unwrap().get(&pid).cloned()
+    }
+}
+
+fn priority_from_tags(tags: &[String]) -> (Priority, Option<String>) {
+    let mut priority = Priority::default();
+    let mut slo_tier = None;
+    for tag in tags {
+        if let Some(value) = tag.strip_prefix("linnix.priority=") {
+            priority = Priority::from(value);
+        } else if let Some(value) = tag.strip_prefix("linnix.slo_tier=") {
+            slo_tier = Some(value.to_string());
+        }
+    }
+    (priority, slo_tier)
+}
+
+#[derive(Debug, Deserialize)]
+struct ConsulHealthEntry {
+    #[serde(rename = "Service")]
+    service: ConsulServiceEntry,
+}
+
+#[derive(Debug, Deserialize)]
+struct ConsulServiceEntry {
+    #[serde(rename = "Service")]
+    service: String,
+    #[serde(default, rename = "Tags")]
+    tags: Vec<String>,
+    #[serde(default, rename = "Meta")]
+    meta: HashMap<String, String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_priority_and_slo_tier_from_tags() {
+        let tags = vec![
+            "linnix.priority=high".to_string(),
+            "linnix.slo_tier=gold".to_string(),
+        ];
+        let (priority, slo_tier) = priority_from_tags(&tags);
+        assert_eq!(priority, Priority::High);
+        assert_eq!(slo_tier, Some("gold".to_string()));
+    }
+
+    #[test]
+    fn defaults_when_tags_absent() {
+        let (priority, slo_tier) = priority_from_tags(&[]);
+        assert_eq!(priority, Priority::Medium);
+        assert_eq!(slo_tier, None);
+    }
+}
@@ -1,15 +1,111 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::sync::atomic::{AtomicU64, Ordering};
+use sqlx::{Row, SqlitePool, sqlite::SqlitePoolOptions};
+use std::path::Path;
 use std::time::{SystemTime, UNIX_EPOCH};
-use tokio::sync::RwLock;
 
 mod safety;
 
+/// `ionice` scheduling class, from most to least aggressive.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IoNiceClass {
+    RealTime,
+    BestEffort,
+    Idle,
+}
+
+impl IoNiceClass {
+    fn ioprio_class(&self) -> i32 {
+        match self {
+            IoNiceClass::RealTime => 1,
+            IoNiceClass::BestEffort => 2,
+            IoNiceClass::Idle => 3,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case", tag = "type")]
 pub enum ActionType {
     KillProcess { pid: u32, signal: i32 },
+    Renice { pid: u32, nice: i32 },
+    IoNice { pid: u32, class: IoNiceClass },
+    /// Cap the pid's cgroup at `cpu_quota_percent` of a CPU core via
+    /// cgroup v2's `cpu.max`, instead of killing or reniceing it outright --
+    /// the graduated response `cpu_spin`/`io_saturation` insights call for.
+    CgroupThrottle { pid: u32, cpu_quota_percent: u32 },
+}
+
+impl ActionType {
+    fn pid(&self) -> u32 {
+        match self {
+            ActionType::KillProcess { pid, .. } => *pid,
+            ActionType::Renice { pid, .. } => *pid,
+            ActionType::IoNice { pid, .. } => *pid,
+            ActionType::CgroupThrottle { pid, .. } => *pid,
+        }
+    }
+}
+
+/// A process's identity at a point in time, used to detect PID reuse
+/// between when a remediation is proposed and when it's actually executed:
+/// a kernel that's recycled `pid` for an unrelated process must never be
+/// acted on just because the number matches.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProcessIdentity {
+    pub pid: u32,
+    pub comm: String,
+    pub start_time: u64,
+}
+
+impl ProcessIdentity {
+    /// Read the current identity of `pid` from `/proc/<pid>/stat`.
+    pub fn read(pid: u32) -> std::io::Result<Self> {
+        let stat = std::fs::read_to_string(format!("/proc/{pid}/stat"))?;
+        Self::parse_stat(pid, &stat)
+    }
+
+    /// Parse a `/proc/<pid>/stat` line's comm and start_time fields.
+    fn parse_stat(pid: u32, stat: &str) -> std::io::Result<Self> {
+        // comm is whatever sits between the first '(' and the last ')',
+        // since the command name itself may contain spaces or parens.
+        let open = stat
+            .find('(')
+            .ok_or_else(|| invalid_stat("missing comm"))?;
+        let close = stat
+            .rfind(')')
+            .ok_or_else(|| invalid_stat("missing comm"))?;
+        let comm = stat[open + 1..close].to_string();
+        // Fields after comm are space-separated starting at `state`; start_time
+        // is field 22 overall, i.e. index 19 counting from `state` at index 0.
+        let start_time = stat[close + 1..]
+            .split_whitespace()
+            .nth(19)
+            .and_then(|s| s.parse::<u64>().ok())
+            .ok_or_else(|| invalid_stat("missing start_time"))?;
+        Ok(Self {
+            pid,
+            comm,
+            start_time,
+        })
+    }
+}
+
+fn invalid_stat(msg: &str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, msg)
+}
+
+/// Outcome of executing a remediation action, recorded on the action so it
+/// can be surfaced later (e.g. in `Export`).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case", tag = "result")]
+pub enum RemediationOutcome {
+    Applied { detail: String },
+    IdentityMismatch {
+        expected: ProcessIdentity,
+        found: Option<ProcessIdentity>,
+    },
+    Failed { reason: String },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -17,11 +113,40 @@ pub enum ActionType {
 pub enum ActionStatus {
     Pending,
     Approved,
+    /// An executor has picked this action up and is currently applying it;
+    /// it must keep bumping `heartbeat` while it works, or the reaper will
+    /// assume it crashed and requeue or fail the action.
+    Executing,
     Rejected,
     Expired,
     Executed,
 }
 
+impl ActionStatus {
+    fn as_db_str(&self) -> &'static str {
+        match self {
+            ActionStatus::Pending => "pending",
+            ActionStatus::Approved => "approved",
+            ActionStatus::Executing => "executing",
+            ActionStatus::Rejected => "rejected",
+            ActionStatus::Expired => "expired",
+            ActionStatus::Executed => "executed",
+        }
+    }
+
+    fn from_db_str(s: &str) -> Option<Self> {
+        Some(match s {
+            "pending" => ActionStatus::Pending,
+            "approved" => ActionStatus::Approved,
+            "executing" => ActionStatus::Executing,
+            "rejected" => ActionStatus::Rejected,
+            "expired" => ActionStatus::Expired,
+            "executed" => ActionStatus::Executed,
+            _ => return None,
+        })
+    }
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct EnforcementAction {
     pub id: String,
@@ -37,21 +162,137 @@ pub struct EnforcementAction {
     pub approved_by: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub approved_at: Option<u64>,
+    /// Process identity captured at proposal time; `execute` refuses to act
+    /// if the PID no longer matches this by the time it runs.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expected_identity: Option<ProcessIdentity>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub outcome: Option<RemediationOutcome>,
+    /// Last time an executor reported progress on this action, while it's
+    /// `Executing`. `None` outside that state.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub heartbeat: Option<u64>,
+}
+
+/// Snapshot of how many actions currently sit in each [`ActionStatus`].
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct EnforcementStatusCounts {
+    pub pending: u64,
+    pub approved: u64,
+    pub executing: u64,
+    pub rejected: u64,
+    pub expired: u64,
+    pub executed: u64,
+}
+
+impl EnforcementAction {
+    fn from_row(row: &sqlx::sqlite::SqliteRow) -> Result<Self, String> {
+        let status_str: String = row.get("status");
+        let action_json: String = row.get("action");
+        let expected_identity_json: Option<String> = row.get("expected_identity");
+        let outcome_json: Option<String> = row.get("outcome");
+        let rowid: i64 = row.get("id");
+
+        Ok(Self {
+            id: format!("action-{rowid}"),
+            action: serde_json::from_str(&action_json).map_err(|e| e.to_string())?,
+            reason: row.get("reason"),
+            source: row.get("source"),
+            confidence: row.get("confidence"),
+            status: ActionStatus::from_db_str(&status_str)
+                .ok_or_else(|| format!("unknown status in database: {status_str}"))?,
+            created_at: row.get::<i64, _>("created_at") as u64,
+            expires_at: row.get::<i64, _>("expires_at") as u64,
+            approved_by: row.get("approved_by"),
+            approved_at: row.get::<Option<i64>, _>("approved_at").map(|v| v as u64),
+            expected_identity: expected_identity_json
+                .map(|s| serde_json::from_str(&s))
+                .transpose()
+                .map_err(|e| e.to_string())?,
+            outcome: outcome_json
+                .map(|s| serde_json::from_str(&s))
+                .transpose()
+                .map_err(|e| e.to_string())?,
+            heartbeat: row.get::<Option<i64>, _>("heartbeat").map(|v| v as u64),
+        })
+    }
 }
 
+/// An action's numeric rowid, parsed back out of its public `"action-N"` id.
+fn parse_action_id(id: &str) -> Result<i64, String> {
+    id.strip_prefix("action-")
+        .and_then(|n| n.parse::<i64>().ok())
+        .ok_or_else(|| format!("malformed action id: {id}"))
+}
+
+/// How long an `Executing` action can go without a heartbeat before the
+/// reaper assumes its executor died and requeues or fails it.
+const DEFAULT_STALE_LEASE_SECS: u64 = 30;
+
+/// Durable queue of proposed/approved remediation actions, backed by SQLite
+/// so a daemon restart doesn't lose pending approvals or their audit trail.
+///
+/// Executors follow a worker-lease pattern borrowed from job queues: picking
+/// up an `Approved` action moves it to `Executing` and starts a heartbeat;
+/// [`EnforcementQueue::reap_stale`] finds `Executing` rows whose heartbeat
+/// has gone stale (executor crashed mid-action) and requeues or fails them,
+/// so a crashed executor never leaves a kill action stuck half-done.
 pub struct EnforcementQueue {
-    next_id: AtomicU64,
-    actions: RwLock<HashMap<String, EnforcementAction>>,
+    pool: SqlitePool,
     ttl_secs: u64,
+    stale_lease_secs: u64,
 }
 
 impl EnforcementQueue {
-    pub fn new(ttl_secs: u64) -> Self {
-        Self {
-            next_id: AtomicU64::new(1),
-            actions: RwLock::new(HashMap::new()),
+    pub async fn new<P: AsRef<Path>>(db_path: P, ttl_secs: u64) -> Result<Self, sqlx::Error> {
+        let db_url = format!("sqlite://{}?mode=rwc", db_path.as_ref().display());
+
+        let pool = SqlitePoolOptions::new()
+            .max_connections(5)
+            .connect(&db_url)
+            .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS enforcement_actions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                action TEXT NOT NULL,
+                reason TEXT NOT NULL,
+                source TEXT NOT NULL,
+                confidence REAL,
+                status TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                expires_at INTEGER NOT NULL,
+                approved_by TEXT,
+                approved_at INTEGER,
+                expected_identity TEXT,
+                outcome TEXT,
+                heartbeat INTEGER
+            );
+            CREATE INDEX IF NOT EXISTS idx_enforcement_status ON enforcement_actions(status);
+            CREATE INDEX IF NOT EXISTS idx_enforcement_heartbeat ON enforcement_actions(heartbeat);
+            "#,
+        )
+        .execute(&pool)
+        .await?;
+
+        log::info!(
+            "Enforcement queue initialized at {}",
+            db_path.as_ref().display()
+        );
+
+        Ok(Self {
+            pool,
             ttl_secs,
-        }
+            stale_lease_secs: DEFAULT_STALE_LEASE_SECS,
+        })
+    }
+
+    /// Override how long an `Executing` action may go without a heartbeat
+    /// before [`EnforcementQueue::reap_stale`] treats its executor as dead.
+    pub fn with_stale_lease_secs(mut self, stale_lease_secs: u64) -> Self {
+        self.stale_lease_secs = stale_lease_secs;
+        self
     }
 
     pub async fn propose(
@@ -94,10 +335,25 @@ impl EnforcementQueue {
             ActionType::KillProcess { pid, .. } => {
                 safety::SafetyGuard::is_safe_to_kill(*pid)?;
             }
+            ActionType::Renice { pid, .. } => {
+                safety::SafetyGuard::is_safe_to_kill(*pid)?;
+            }
+            ActionType::IoNice { pid, .. } => {
+                safety::SafetyGuard::is_safe_to_kill(*pid)?;
+            }
+            ActionType::CgroupThrottle { pid, .. } => {
+                safety::SafetyGuard::is_safe_to_kill(*pid)?;
+            }
         }
 
-        let id = format!("action-{}", self.next_id.fetch_add(1, Ordering::SeqCst));
         let now = current_epoch_secs();
+        let expected_identity = ProcessIdentity::read(action.pid()).ok();
+        let action_json = serde_json::to_string(&action).map_err(|e| e.to_string())?;
+        let expected_identity_json = expected_identity
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()
+            .map_err(|e| e.to_string())?;
 
         let (status, approved_by, approved_at) = if auto_approve {
             (
@@ -109,23 +365,29 @@ impl EnforcementQueue {
             (ActionStatus::Pending, None, None)
         };
 
-        let enforcement_action = EnforcementAction {
-            id: id.clone(),
-            action,
-            reason: reason.clone(),
-            source: source.clone(),
-            confidence,
-            status,
-            created_at: now,
-            expires_at: now + self.ttl_secs,
-            approved_by: approved_by.clone(),
-            approved_at,
-        };
+        let result = sqlx::query(
+            r#"
+            INSERT INTO enforcement_actions (
+                action, reason, source, confidence, status, created_at, expires_at,
+                approved_by, approved_at, expected_identity
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&action_json)
+        .bind(&reason)
+        .bind(&source)
+        .bind(confidence)
+        .bind(status.as_db_str())
+        .bind(now as i64)
+        .bind((now + self.ttl_secs) as i64)
+        .bind(&approved_by)
+        .bind(approved_at.map(|v| v as i64))
+        .bind(&expected_identity_json)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| e.to_string())?;
 
-        self.actions
-            .write()
-            .await
-            .insert(id.clone(), enforcement_action);
+        let id = format!("action-{}", result.last_insert_rowid());
 
         if auto_approve {
             log::warn!(
@@ -141,8 +403,8 @@ impl EnforcementQueue {
     }
 
     pub async fn approve(&self, id: &str, approver: String) -> Result<EnforcementAction, String> {
-        let mut actions = self.actions.write().await;
-        let action = actions.get_mut(id).ok_or("action not found")?;
+        let rowid = parse_action_id(id)?;
+        let action = self.get_by_id(id).await?.ok_or("action not found")?;
 
         if action.status != ActionStatus::Pending {
             return Err(format!("not pending: {:?}", action.status));
@@ -150,13 +412,20 @@ impl EnforcementQueue {
 
         let now = current_epoch_secs();
         if now > action.expires_at {
-            action.status = ActionStatus::Expired;
+            self.set_status(rowid, ActionStatus::Expired).await?;
             return Err("expired".to_string());
         }
 
-        action.status = ActionStatus::Approved;
-        action.approved_by = Some(approver.clone());
-        action.approved_at = Some(now);
+        sqlx::query(
+            "UPDATE enforcement_actions SET status = ?, approved_by = ?, approved_at = ? WHERE id = ?",
+        )
+        .bind(ActionStatus::Approved.as_db_str())
+        .bind(&approver)
+        .bind(now as i64)
+        .bind(rowid)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| e.to_string())?;
 
         log::warn!(
             target: "linnix_audit",
@@ -164,59 +433,385 @@ impl EnforcementQueue {
             id, approver, action.reason
         );
 
-        Ok(action.clone())
+        self.get_by_id(id).await?.ok_or("action not found".to_string())
     }
 
     pub async fn reject(&self, id: &str, rejector: String) -> Result<(), String> {
-        let mut actions = self.actions.write().await;
-        let action = actions.get_mut(id).ok_or("action not found")?;
+        let rowid = parse_action_id(id)?;
+        let action = self.get_by_id(id).await?.ok_or("action not found")?;
 
         if action.status != ActionStatus::Pending {
             return Err(format!("not pending: {:?}", action.status));
         }
 
-        action.status = ActionStatus::Rejected;
+        self.set_status(rowid, ActionStatus::Rejected).await?;
         log::info!("[enforcement] rejected {id} by {rejector}");
         Ok(())
     }
 
     pub async fn complete(&self, id: &str) -> Result<(), String> {
-        let mut actions = self.actions.write().await;
-        let action = actions.get_mut(id).ok_or("action not found")?;
+        let rowid = parse_action_id(id)?;
+        let action = self.get_by_id(id).await?.ok_or("action not found")?;
 
-        if action.status != ActionStatus::Approved {
+        if action.status != ActionStatus::Approved && action.status != ActionStatus::Executing {
             return Err(format!("not approved: {:?}", action.status));
         }
 
-        action.status = ActionStatus::Executed;
+        self.set_status(rowid, ActionStatus::Executed).await?;
         log::info!("[enforcement] completed {id}");
         Ok(())
     }
 
-    #[allow(dead_code)]
-    pub async fn get_pending(&self) -> Vec<EnforcementAction> {
+    /// Mark `id` as picked up by an executor and start its lease: moves it
+    /// from `Approved` to `Executing` and stamps the first heartbeat, so
+    /// [`EnforcementQueue::reap_stale`] knows it's in flight.
+    pub async fn lease(&self, id: &str) -> Result<(), String> {
+        let rowid = parse_action_id(id)?;
+        // Guard the transition with `AND status = 'approved'` in the UPDATE
+        // itself rather than a separate read-then-write: two executors
+        // racing on the same action must not both observe `Approved` and
+        // both flip it to `Executing`, or both go on to re-apply it.
         let now = current_epoch_secs();
-        let mut actions = self.actions.write().await;
+        let result = sqlx::query(
+            "UPDATE enforcement_actions SET status = ?, heartbeat = ? WHERE id = ? AND status = ?",
+        )
+        .bind(ActionStatus::Executing.as_db_str())
+        .bind(now as i64)
+        .bind(rowid)
+        .bind(ActionStatus::Approved.as_db_str())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| e.to_string())?;
 
-        for action in actions.values_mut() {
-            if action.status == ActionStatus::Pending && now > action.expires_at {
-                action.status = ActionStatus::Expired;
+        if result.rows_affected() != 1 {
+            let action = self.get_by_id(id).await?.ok_or("action not found")?;
+            return Err(format!("already leased: {:?}", action.status));
+        }
+        Ok(())
+    }
+
+    /// Record that the executor holding `id`'s lease is still alive. Must be
+    /// called periodically while executing an action, or `reap_stale` will
+    /// eventually assume the executor crashed.
+    pub async fn heartbeat(&self, id: &str) -> Result<(), String> {
+        let rowid = parse_action_id(id)?;
+        sqlx::query(
+            "UPDATE enforcement_actions SET heartbeat = ? WHERE id = ? AND status = ?",
+        )
+        .bind(current_epoch_secs() as i64)
+        .bind(rowid)
+        .bind(ActionStatus::Executing.as_db_str())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// Actually perform an approved action: re-validate the target PID's
+    /// identity (comm + start time) against what was recorded at proposal
+    /// time, refusing to act on a mismatch, then send the signal or renice
+    /// the process. The outcome is recorded on the action for later audit
+    /// (e.g. `Export`), and `--dry-run` callers should never reach this --
+    /// they just print `describe_action` instead.
+    pub async fn execute(&self, id: &str) -> Result<RemediationOutcome, String> {
+        let rowid = parse_action_id(id)?;
+        self.lease(id).await?;
+
+        let action = self.get_by_id(id).await?.ok_or("action not found")?;
+        let pid = action.action.pid();
+        let found = ProcessIdentity::read(pid).ok();
+        let outcome = match (&action.expected_identity, &found) {
+            (Some(expected), Some(found)) if expected != found => RemediationOutcome::IdentityMismatch {
+                expected: expected.clone(),
+                found: Some(found.clone()),
+            },
+            (Some(expected), None) => RemediationOutcome::IdentityMismatch {
+                expected: expected.clone(),
+                found: None,
+            },
+            _ => match &action.action {
+                ActionType::KillProcess { pid, signal } => apply_signal(*pid, *signal),
+                ActionType::Renice { pid, nice } => apply_renice(*pid, *nice),
+                ActionType::IoNice { pid, class } => apply_ionice(*pid, *class),
+                ActionType::CgroupThrottle {
+                    pid,
+                    cpu_quota_percent,
+                } => apply_cgroup_throttle(*pid, *cpu_quota_percent),
+            },
+        };
+
+        let new_status = if matches!(outcome, RemediationOutcome::Applied { .. }) {
+            ActionStatus::Executed
+        } else {
+            ActionStatus::Approved
+        };
+        let outcome_json = serde_json::to_string(&outcome).map_err(|e| e.to_string())?;
+        sqlx::query(
+            "UPDATE enforcement_actions SET status = ?, outcome = ?, heartbeat = NULL WHERE id = ?",
+        )
+        .bind(new_status.as_db_str())
+        .bind(&outcome_json)
+        .bind(rowid)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        match &outcome {
+            RemediationOutcome::Applied { detail } => {
+                log::warn!(target: "linnix_audit", "EXECUTED {id} {detail}");
+            }
+            RemediationOutcome::IdentityMismatch { .. } => {
+                log::warn!(
+                    target: "linnix_audit",
+                    "REFUSED {id}: pid {pid} identity mismatch, likely reused"
+                );
+            }
+            RemediationOutcome::Failed { reason } => {
+                log::warn!(target: "linnix_audit", "FAILED {id}: {reason}");
             }
         }
 
-        actions
-            .values()
-            .filter(|a| a.status == ActionStatus::Pending)
-            .cloned()
-            .collect()
+        Ok(outcome)
     }
 
-    pub async fn get_by_id(&self, id: &str) -> Option<EnforcementAction> {
-        self.actions.read().await.get(id).cloned()
+    /// Background sweep: expires `Pending` actions whose `expires_at` has
+    /// passed, then hands off to [`EnforcementQueue::reap_stale`] for
+    /// `Executing` actions whose executor has gone quiet. Intended to be
+    /// called periodically (e.g. from a timer task), rather than only at
+    /// `get_pending` time.
+    pub async fn sweep(&self) -> Result<(), String> {
+        let now = current_epoch_secs();
+        sqlx::query("UPDATE enforcement_actions SET status = ? WHERE status = ? AND expires_at < ?")
+            .bind(ActionStatus::Expired.as_db_str())
+            .bind(ActionStatus::Pending.as_db_str())
+            .bind(now as i64)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        self.reap_stale().await
     }
 
-    pub async fn get_all(&self) -> Vec<EnforcementAction> {
-        self.actions.read().await.values().cloned().collect()
+    /// Find `Executing` actions whose heartbeat is older than
+    /// `stale_lease_secs` -- their executor has presumably crashed -- and
+    /// either requeue them to `Approved` (if they haven't yet expired, so a
+    /// future executor can retry) or mark them `Expired`.
+    pub async fn reap_stale(&self) -> Result<(), String> {
+        let now = current_epoch_secs();
+        let stale_before = now.saturating_sub(self.stale_lease_secs);
+
+        let stale_rows = sqlx::query(
+            "SELECT id, expires_at FROM enforcement_actions WHERE status = ? AND heartbeat < ?",
+        )
+        .bind(ActionStatus::Executing.as_db_str())
+        .bind(stale_before as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        for row in stale_rows {
+            let rowid: i64 = row.get(0);
+            let expires_at: i64 = row.get(1);
+            let (new_status, note) = if (expires_at as u64) < now {
+                (ActionStatus::Expired, "expired")
+            } else {
+                (ActionStatus::Approved, "requeued")
+            };
+            sqlx::query("UPDATE enforcement_actions SET status = ?, heartbeat = NULL WHERE id = ?")
+                .bind(new_status.as_db_str())
+                .bind(rowid)
+                .execute(&self.pool)
+                .await
+                .map_err(|e| e.to_string())?;
+            log::warn!(
+                target: "linnix_audit",
+                "REAPED action-{rowid}: stale lease, executor presumed dead, {note}"
+            );
+        }
+
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    pub async fn get_pending(&self) -> Result<Vec<EnforcementAction>, String> {
+        self.sweep().await?;
+
+        let rows = sqlx::query("SELECT * FROM enforcement_actions WHERE status = ?")
+            .bind(ActionStatus::Pending.as_db_str())
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        rows.iter().map(EnforcementAction::from_row).collect()
+    }
+
+    pub async fn get_by_id(&self, id: &str) -> Result<Option<EnforcementAction>, String> {
+        let rowid = parse_action_id(id)?;
+        let row = sqlx::query("SELECT * FROM enforcement_actions WHERE id = ?")
+            .bind(rowid)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        row.as_ref().map(EnforcementAction::from_row).transpose()
+    }
+
+    pub async fn get_all(&self) -> Result<Vec<EnforcementAction>, String> {
+        let rows = sqlx::query("SELECT * FROM enforcement_actions")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        rows.iter().map(EnforcementAction::from_row).collect()
+    }
+
+    /// Count actions currently in each [`ActionStatus`], for a `/metrics`
+    /// gauge -- queried live rather than tracked in memory, since actions
+    /// migrate between statuses (propose, approve, reap) and an in-memory
+    /// counter would drift from the durable queue across a restart.
+    pub async fn status_counts(&self) -> Result<EnforcementStatusCounts, String> {
+        let rows = sqlx::query("SELECT status, COUNT(*) as count FROM enforcement_actions GROUP BY status")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let mut counts = EnforcementStatusCounts::default();
+        for row in &rows {
+            let status: String = row.get("status");
+            let count: i64 = row.get("count");
+            match ActionStatus::from_db_str(&status) {
+                Some(ActionStatus::Pending) => counts.pending = count as u64,
+                Some(ActionStatus::Approved) => counts.approved = count as u64,
+                Some(ActionStatus::Executing) => counts.executing = count as u64,
+                Some(ActionStatus::Rejected) => counts.rejected = count as u64,
+                Some(ActionStatus::Expired) => counts.expired = count as u64,
+                Some(ActionStatus::Executed) => counts.executed = count as u64,
+                None => {}
+            }
+        }
+        Ok(counts)
+    }
+
+    async fn set_status(&self, rowid: i64, status: ActionStatus) -> Result<(), String> {
+        sqlx::query("UPDATE enforcement_actions SET status = ? WHERE id = ?")
+            .bind(status.as_db_str())
+            .bind(rowid)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+}
+
+fn apply_signal(pid: u32, signal: i32) -> RemediationOutcome {
+    // SAFETY: `kill` is a simple syscall wrapper; pid/signal are plain integers.
+    let ret = unsafe { libc::kill(pid as libc::pid_t, signal) };
+    if ret == 0 {
+        RemediationOutcome::Applied {
+            detail: format!("sent signal {signal} to pid {pid}"),
+        }
+    } else {
+        RemediationOutcome::Failed {
+            reason: std::io::Error::last_os_error().to_string(),
+        }
+    }
+}
+
+fn apply_renice(pid: u32, nice: i32) -> RemediationOutcome {
+    // SAFETY: `setpriority` is a simple syscall wrapper; pid/nice are plain integers.
+    let ret = unsafe { libc::setpriority(libc::PRIO_PROCESS, pid as libc::id_t, nice) };
+    if ret == 0 {
+        RemediationOutcome::Applied {
+            detail: format!("reniced pid {pid} to {nice}"),
+        }
+    } else {
+        RemediationOutcome::Failed {
+            reason: std::io::Error::last_os_error().to_string(),
+        }
+    }
+}
+
+/// Default priority data within the chosen ioprio class (middle of the
+/// 0-7 range); only the class, not the exact priority, is what `emit_insight`'s
+/// textual actions ever express.
+const IOPRIO_DEFAULT_DATA: i32 = 4;
+const IOPRIO_WHO_PROCESS: i32 = 1;
+const IOPRIO_CLASS_SHIFT: i32 = 13;
+
+fn apply_ionice(pid: u32, class: IoNiceClass) -> RemediationOutcome {
+    let ioprio = (class.ioprio_class() << IOPRIO_CLASS_SHIFT) | IOPRIO_DEFAULT_DATA;
+    // SAFETY: `ioprio_set` is a simple syscall wrapper; pid/ioprio are plain integers.
+    let ret = unsafe {
+        libc::syscall(
+            libc::SYS_ioprio_set,
+            IOPRIO_WHO_PROCESS,
+            pid as libc::c_long,
+            ioprio as libc::c_long,
+        )
+    };
+    if ret == 0 {
+        RemediationOutcome::Applied {
+            detail: format!("set pid {pid} ionice class to {class:?}"),
+        }
+    } else {
+        RemediationOutcome::Failed {
+            reason: std::io::Error::last_os_error().to_string(),
+        }
+    }
+}
+
+/// cgroup v2's `cpu.max` bandwidth period, in microseconds; `cpu_quota_percent`
+/// is expressed as a fraction of this period.
+const CGROUP_CPU_PERIOD_US: u64 = 100_000;
+
+fn apply_cgroup_throttle(pid: u32, cpu_quota_percent: u32) -> RemediationOutcome {
+    match write_cpu_quota(pid, cpu_quota_percent) {
+        Ok(path) => RemediationOutcome::Applied {
+            detail: format!(
+                "throttled pid {pid} to {}% cpu via {}",
+                cpu_quota_percent.min(100),
+                path.display()
+            ),
+        },
+        Err(err) => RemediationOutcome::Failed {
+            reason: err.to_string(),
+        },
+    }
+}
+
+fn write_cpu_quota(pid: u32, cpu_quota_percent: u32) -> std::io::Result<std::path::PathBuf> {
+    let cgroup_dir = cgroup_path_for_pid(pid)?;
+    let quota_us = CGROUP_CPU_PERIOD_US * cpu_quota_percent.min(100) as u64 / 100;
+    std::fs::write(
+        cgroup_dir.join("cpu.max"),
+        format!("{quota_us} {CGROUP_CPU_PERIOD_US}"),
+    )?;
+    Ok(cgroup_dir)
+}
+
+/// Resolve `pid`'s unified (cgroup v2) cgroup directory under `/sys/fs/cgroup`
+/// from its `/proc/<pid>/cgroup` entry (the `0::<path>` line).
+fn cgroup_path_for_pid(pid: u32) -> std::io::Result<std::path::PathBuf> {
+    let content = std::fs::read_to_string(format!("/proc/{pid}/cgroup"))?;
+    let rel = content
+        .lines()
+        .find_map(|line| line.strip_prefix("0::"))
+        .ok_or_else(|| invalid_stat("no unified cgroup entry"))?;
+    Ok(std::path::Path::new("/sys/fs/cgroup").join(rel.trim_start_matches('/')))
+}
+
+/// Human-readable description of what `action` would do, for `--dry-run` output.
+pub fn describe_action(action: &ActionType) -> String {
+    match action {
+        ActionType::KillProcess { pid, signal } => {
+            format!("send signal {signal} to pid {pid}")
+        }
+        ActionType::Renice { pid, nice } => format!("renice pid {pid} to {nice}"),
+        ActionType::IoNice { pid, class } => format!("set pid {pid} ionice class to {class:?}"),
+        ActionType::CgroupThrottle {
+            pid,
+            cpu_quota_percent,
+        } => format!("throttle pid {pid} to {cpu_quota_percent}% cpu"),
     }
 }
 
@@ -231,9 +826,17 @@ fn current_epoch_secs() -> u64 {
 mod tests {
     use super::*;
 
+    async fn test_queue(ttl_secs: u64) -> (tempfile::TempDir, EnforcementQueue) {
+        let dir = tempfile::tempdir().unwrap();
+        let queue = EnforcementQueue::new(dir.path().join("enforcement.db"), ttl_secs)
+            .await
+            .unwrap();
+        (dir, queue)
+    }
+
     #[tokio::test]
     async fn test_propose_and_approve() {
-        let queue = EnforcementQueue::new(300);
+        let (_dir, queue) = test_queue(300).await;
         let id = queue
             .propose(
                 ActionType::KillProcess {
@@ -247,20 +850,20 @@ mod tests {
             .await
             .unwrap();
 
-        let pending = queue.get_pending().await;
+        let pending = queue.get_pending().await.unwrap();
         assert_eq!(pending.len(), 1);
 
         let result = queue.approve(&id, "alice".to_string()).await;
         assert!(result.is_ok());
 
-        let action = queue.get_by_id(&id).await.unwrap();
+        let action = queue.get_by_id(&id).await.unwrap().unwrap();
         assert_eq!(action.status, ActionStatus::Approved);
         assert_eq!(action.approved_by, Some("alice".to_string()));
     }
 
     #[tokio::test]
     async fn test_expiration() {
-        let queue = EnforcementQueue::new(0); // Expire immediately (0 seconds TTL)
+        let (_dir, queue) = test_queue(0).await; // Expire immediately (0 seconds TTL)
         let id = queue
             .propose(
                 ActionType::KillProcess {
@@ -283,7 +886,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_reject() {
-        let queue = EnforcementQueue::new(300);
+        let (_dir, queue) = test_queue(300).await;
         let id = queue
             .propose(
                 ActionType::KillProcess {
@@ -299,7 +902,7 @@ mod tests {
 
         queue.reject(&id, "bob".to_string()).await.unwrap();
 
-        let action = queue.get_by_id(&id).await.unwrap();
+        let action = queue.get_by_id(&id).await.unwrap().unwrap();
         assert_eq!(action.status, ActionStatus::Rejected);
 
         let result = queue.approve(&id, "alice".to_string()).await;
@@ -308,7 +911,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_reject_already_approved() {
-        let queue = EnforcementQueue::new(300);
+        let (_dir, queue) = test_queue(300).await;
         let id = queue
             .propose(
                 ActionType::KillProcess {
@@ -328,4 +931,185 @@ mod tests {
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("not pending"));
     }
+
+    #[test]
+    fn parses_comm_and_start_time_from_stat_line() {
+        // state ppid pgrp session tty_nr tpgid flags minflt cminflt majflt
+        // cmajflt utime stime cutime cstime priority nice num_threads
+        // itrealvalue starttime -- the 20th field after `comm` is start_time.
+        let stat = "42 (java -jar app) R 1 1 1 0 -1 4194304 100 0 0 0 0 0 0 0 20 0 1 0 56789 ...";
+        let identity = ProcessIdentity::parse_stat(42, stat).unwrap();
+        assert_eq!(identity.pid, 42);
+        assert_eq!(identity.comm, "java -jar app");
+        assert_eq!(identity.start_time, 56789);
+    }
+
+    #[tokio::test]
+    async fn execute_refuses_on_identity_mismatch() {
+        let (_dir, queue) = test_queue(300).await;
+        let id = queue
+            .propose(
+                ActionType::KillProcess {
+                    pid: 123,
+                    signal: 9,
+                },
+                "test".to_string(),
+                "test".to_string(),
+                None,
+            )
+            .await
+            .unwrap();
+        queue.approve(&id, "alice".to_string()).await.unwrap();
+
+        // Force a recorded identity that can never match /proc/123's real one.
+        let rowid = parse_action_id(&id).unwrap();
+        let bogus_identity = serde_json::to_string(&ProcessIdentity {
+            pid: 123,
+            comm: "definitely-not-the-real-process".to_string(),
+            start_time: 1,
+        })
+        .unwrap();
+        sqlx::query("UPDATE enforcement_actions SET expected_identity = ? WHERE id = ?")
+            .bind(bogus_identity)
+            .bind(rowid)
+            .execute(&queue.pool)
+            .await
+            .unwrap();
+
+        let outcome = queue.execute(&id).await.unwrap();
+        assert!(matches!(outcome, RemediationOutcome::IdentityMismatch { .. }));
+
+        let action = queue.get_by_id(&id).await.unwrap().unwrap();
+        assert_eq!(action.status, ActionStatus::Approved, "mismatch must not mark Executed");
+    }
+
+    #[tokio::test]
+    async fn lease_is_exclusive_under_concurrent_acquisition() {
+        let (_dir, queue) = test_queue(300).await;
+        let id = queue
+            .propose(
+                ActionType::KillProcess {
+                    pid: 123,
+                    signal: 9,
+                },
+                "test".to_string(),
+                "test".to_string(),
+                None,
+            )
+            .await
+            .unwrap();
+        queue.approve(&id, "alice".to_string()).await.unwrap();
+
+        let (first, second) = tokio::join!(queue.lease(&id), queue.lease(&id));
+        let outcomes = [first, second];
+        assert_eq!(outcomes.iter().filter(|r| r.is_ok()).count(), 1, "exactly one racer should win the lease");
+        assert!(
+            outcomes.iter().any(|r| r.as_ref().is_err_and(|e| e.contains("already leased"))),
+            "the loser should see an already-leased error, not silently succeed"
+        );
+
+        let action = queue.get_by_id(&id).await.unwrap().unwrap();
+        assert_eq!(action.status, ActionStatus::Executing);
+    }
+
+    #[tokio::test]
+    async fn reap_stale_requeues_unexpired_leases() {
+        let (_dir, queue) = test_queue(300).await;
+        let queue = queue.with_stale_lease_secs(0);
+        let id = queue
+            .propose(
+                ActionType::KillProcess {
+                    pid: 123,
+                    signal: 9,
+                },
+                "test".to_string(),
+                "test".to_string(),
+                None,
+            )
+            .await
+            .unwrap();
+        queue.approve(&id, "alice".to_string()).await.unwrap();
+        queue.lease(&id).await.unwrap();
+
+        // stale_lease_secs = 0, so any heartbeat older than "now" counts as
+        // stale; sleep past the current second boundary since timestamps
+        // here only have one-second resolution.
+        tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+        queue.reap_stale().await.unwrap();
+
+        let action = queue.get_by_id(&id).await.unwrap().unwrap();
+        assert_eq!(action.status, ActionStatus::Approved, "unexpired lease should requeue");
+        assert!(action.heartbeat.is_none());
+    }
+
+    #[tokio::test]
+    async fn status_counts_reflect_pending_approved_and_rejected_actions() {
+        let (_dir, queue) = test_queue(300).await;
+
+        queue
+            .propose(
+                ActionType::KillProcess { pid: 1, signal: 9 },
+                "test".to_string(),
+                "test".to_string(),
+                None,
+            )
+            .await
+            .unwrap();
+        let approved_id = queue
+            .propose(
+                ActionType::KillProcess { pid: 2, signal: 9 },
+                "test".to_string(),
+                "test".to_string(),
+                None,
+            )
+            .await
+            .unwrap();
+        queue.approve(&approved_id, "alice".to_string()).await.unwrap();
+        let rejected_id = queue
+            .propose(
+                ActionType::KillProcess { pid: 3, signal: 9 },
+                "test".to_string(),
+                "test".to_string(),
+                None,
+            )
+            .await
+            .unwrap();
+        queue.reject(&rejected_id, "bob".to_string()).await.unwrap();
+
+        let counts = queue.status_counts().await.unwrap();
+        assert_eq!(counts.pending, 1);
+        assert_eq!(counts.approved, 1);
+        assert_eq!(counts.rejected, 1);
+        assert_eq!(counts.expired, 0);
+    }
+
+    #[test]
+    fn describes_renice_and_kill_actions() {
+        assert_eq!(
+            describe_action(&ActionType::KillProcess { pid: 7, signal: 15 }),
+            "send signal 15 to pid 7"
+        );
+        assert_eq!(
+            describe_action(&ActionType::Renice { pid: 7, nice: 10 }),
+            "renice pid 7 to 10"
+        );
+    }
+
+    #[test]
+    fn describes_ionice_and_cgroup_throttle_actions() {
+        assert_eq!(
+            describe_action(&ActionType::IoNice {
+                pid: 7,
+                class: IoNiceClass::Idle,
+            }),
+            "set pid 7 ionice class to Idle"
+        );
+        assert_eq!(
+            describe_action(&ActionType::CgroupThrottle {
+                pid: 7,
+                cpu_quota_percent: 20,
+            }),
+            "throttle pid 7 to 20% cpu"
+        );
+    }
 }
@@ -0,0 +1,388 @@
+//! Batches decoded events by type into column-oriented buffers and
+//! periodically bulk-inserts them into ClickHouse, so historical process
+//! behavior is queryable ad hoc instead of only summarized in
+//! `IncidentStore` or `/status`'s headline counters.
+//!
+//! Each [`EventFrame`] variant maps to its own table (`table_name`), so a
+//! `BlockIoEvent`'s sector/device columns never collide with a
+//! `PageFaultEvent`'s flags. Rows are serialized to `JSONEachRow` text on
+//! [`ClickHouseExporter::record`] and buffered per table; [`run`] drains
+//! every non-empty buffer on a timer as one bulk `INSERT` per table,
+//! retrying with backoff and jitter the same way
+//! `handler::local_ilm::chat_with_retry` retries a chat call. A table's
+//! buffer never grows past `MAX_BUFFERED_BATCHES` full batches: `record`
+//! drops the oldest row once that bound is exceeded, and a batch that
+//! still fails after its last retry is dropped whole. Both count toward
+//! [`ExportStats::rows_dropped_total`].
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use linnix_ai_ebpf_common::frame::EventFrame;
+use rand::Rng;
+use reqwest::Client;
+use serde::Serialize;
+use tracing::{debug, warn};
+
+/// Configuration for the ClickHouse export sink.
+#[derive(Debug, Clone)]
+pub struct ExportConfig {
+    /// ClickHouse HTTP interface endpoint, e.g. `http://clickhouse:8123`.
+    pub url: String,
+    pub database: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// Row count at which [`ClickHouseExporter::flush_due`] flushes a
+    /// table; [`ClickHouseExporter::run`] itself flushes only on
+    /// `flush_interval`, whatever the buffer size.
+    pub max_batch_size: usize,
+    pub flush_interval: Duration,
+    pub retry_max: u32,
+    pub retry_base_ms: u64,
+    pub retry_max_delay_ms: u64,
+}
+
+impl Default for ExportConfig {
+    fn default() -> Self {
+        Self {
+            url: "http://localhost:8123".to_string(),
+            database: "linnix".to_string(),
+            username: None,
+            password: None,
+            max_batch_size: 5_000,
+            flush_interval: Duration::from_secs(10),
+            retry_max: 4,
+            retry_base_ms: 200,
+            retry_max_delay_ms: 10_000,
+        }
+    }
+}
+
+/// How many full `max_batch_size` batches a table's buffer may hold before
+/// the oldest rows are dropped to make room for new ones.
+const MAX_BUFFERED_BATCHES: usize = 4;
+
+/// Export health counters, updated on the hot `record` path without a lock.
+#[derive(Default)]
+struct ExportCounters {
+    rows_exported_total: AtomicU64,
+    rows_dropped_total: AtomicU64,
+    flush_failures_total: AtomicU64,
+}
+
+/// Snapshot of [`ExportCounters`], for `/status` and `doctor`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ExportStats {
+    pub rows_exported_total: u64,
+    pub rows_dropped_total: u64,
+    pub flush_failures_total: u64,
+}
+
+fn table_name(frame: &EventFrame) -> &'static str {
+    match frame {
+        EventFrame::Process(_) => "linnix_process_events",
+        EventFrame::Net(_) => "linnix_net_events",
+        EventFrame::FileIo(_) => "linnix_file_io_events",
+        EventFrame::BlockIo(_) => "linnix_block_io_events",
+        EventFrame::PageFault(_) => "linnix_page_fault_events",
+        EventFrame::RssTrace(_) => "linnix_rss_trace_events",
+        EventFrame::Syscall(_) => "linnix_syscall_events",
+    }
+}
+
+/// Serialize `frame` to one `JSONEachRow` line, tagging it with `seq` so
+/// rows from different flush batches can still be ordered downstream.
+fn to_row(seq: u64, frame: &EventFrame) -> serde_json::Result<String> {
+    let mut value = match frame {
+        EventFrame::Process(e) => serde_json::to_value(e),
+        EventFrame::Net(e) => serde_json::to_value(e),
+        EventFrame::FileIo(e) => serde_json::to_value(e),
+        EventFrame::BlockIo(e) => serde_json::to_value(e),
+        EventFrame::PageFault(e) => serde_json::to_value(e),
+        EventFrame::RssTrace(e) => serde_json::to_value(e),
+        EventFrame::Syscall(e) => serde_json::to_value(e),
+    }?;
+    if let serde_json::Value::Object(map) = &mut value {
+        map.insert("seq".to_string(), serde_json::json!(seq));
+    }
+    serde_json::to_string(&value)
+}
+
+/// Why a table's buffered rows couldn't be inserted.
+#[derive(Debug)]
+enum InsertError {
+    Request(reqwest::Error),
+    ClickHouse { status: reqwest::StatusCode, body: String },
+}
+
+impl fmt::Display for InsertError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InsertError::Request(e) => write!(f, "{e}"),
+            InsertError::ClickHouse { status, body } => write!(f, "ClickHouse returned {status}: {body}"),
+        }
+    }
+}
+
+impl std::error::Error for InsertError {}
+
+/// Batches decoded events by table and flushes them to ClickHouse on a
+/// timer. Cheap to clone-and-share via `Arc` -- `record` only needs a
+/// shared reference.
+pub struct ClickHouseExporter {
+    client: Client,
+    config: ExportConfig,
+    buffers: Mutex<HashMap<&'static str, Vec<String>>>,
+    counters: ExportCounters,
+}
+
+impl ClickHouseExporter {
+    pub fn new(config: ExportConfig) -> Self {
+        Self {
+            client: Client::new(),
+            config,
+            buffers: Mutex::new(HashMap::new()),
+            counters: ExportCounters::default(),
+        }
+    }
+
+    /// Buffer one decoded event under its table. Pure bookkeeping -- no
+    /// I/O happens here; [`ClickHouseExporter::flush_due`] drains buffers
+    /// that have reached `max_batch_size`, and the periodic loop started
+    /// by [`run`] drains every non-empty buffer on a timer.
+    pub fn record(&self, seq: u64, frame: &EventFrame) {
+        let table = table_name(frame);
+        let row = match to_row(seq, frame) {
+            Ok(row) => row,
+            Err(e) => {
+                warn!("[export] failed to serialize {table} row: {e}");
+                return;
+            }
+        };
+
+        let mut buffers = self.buffers.lock().unwrap();
+        let rows = buffers.entry(table).or_default();
+        rows.push(row);
+        let max_buffered = self.config.max_batch_size.saturating_mul(MAX_BUFFERED_BATCHES);
+        if rows.len() > max_buffered {
+            rows.remove(0);
+            self.counters.rows_dropped_total.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Flush every table whose buffer has reached `max_batch_size`, each
+    /// with its own retry/backoff so one table's ClickHouse errors don't
+    /// block another's.
+    pub async fn flush_due(&self) {
+        let due = self.drain_buffers(|rows| rows.len() >= self.config.max_batch_size);
+        for (table, rows) in due {
+            self.flush_table(table, rows).await;
+        }
+    }
+
+    /// Flush every table with a non-empty buffer, regardless of size --
+    /// used by the periodic timer tick so nothing sits unflushed across
+    /// `flush_interval` just because it never reached `max_batch_size`.
+    pub async fn flush_all(&self) {
+        let due = self.drain_buffers(|rows| !rows.is_empty());
+        for (table, rows) in due {
+            self.flush_table(table, rows).await;
+        }
+    }
+
+    fn drain_buffers(&self, should_drain: impl Fn(&[String]) -> bool) -> Vec<(&'static str, Vec<String>)> {
+        let mut buffers = self.buffers.lock().unwrap();
+        buffers
+            .iter_mut()
+            .filter(|(_, rows)| should_drain(rows))
+            .map(|(table, rows)| (*table, std::mem::take(rows)))
+            .collect()
+    }
+
+    async fn flush_table(&self, table: &'static str, rows: Vec<String>) {
+        let count = rows.len();
+        let body = rows.join("\n");
+        let mut attempt = 0u32;
+
+        loop {
+            match self.insert(table, &body).await {
+                Ok(()) => {
+                    self.counters.rows_exported_total.fetch_add(count as u64, Ordering::Relaxed);
+                    debug!("[export] flushed {count} rows to {table}");
+                    return;
+                }
+                Err(e) => {
+                    if attempt >= self.config.retry_max {
+                        self.counters.flush_failures_total.fetch_add(1, Ordering::Relaxed);
+                        self.counters.rows_dropped_total.fetch_add(count as u64, Ordering::Relaxed);
+                        warn!(
+                            "[export] giving up flushing {count} rows to {table} after {attempt} retries: {e}"
+                        );
+                        return;
+                    }
+                    let delay = backoff_with_jitter(attempt, self.config.retry_base_ms, self.config.retry_max_delay_ms);
+                    debug!("[export] flush of {table} failed ({e}), retrying in {:?}", delay);
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+            }
+        }
+    }
+
+    async fn insert(&self, table: &str, body: &str) -> Result<(), InsertError> {
+        let query = format!("INSERT INTO {}.{table} FORMAT JSONEachRow", self.config.database);
+        let mut request = self
+            .client
+            .post(&self.config.url)
+            .query(&[("query", query)])
+            .body(body.to_string());
+        if let Some(username) = &self.config.username {
+            request = request.basic_auth(username, self.config.password.as_deref());
+        }
+
+        let resp = request.send().await.map_err(InsertError::Request)?;
+        if resp.status().is_success() {
+            Ok(())
+        } else {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            Err(InsertError::ClickHouse { status, body })
+        }
+    }
+
+    /// Snapshot export health counters, for `/status` and `doctor`.
+    pub fn stats(&self) -> ExportStats {
+        ExportStats {
+            rows_exported_total: self.counters.rows_exported_total.load(Ordering::Relaxed),
+            rows_dropped_total: self.counters.rows_dropped_total.load(Ordering::Relaxed),
+            flush_failures_total: self.counters.flush_failures_total.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Run the periodic flush loop; never returns. Spawn this on its own
+    /// task -- `record` can be called freely from other tasks while it runs
+    /// since every method here takes `&self`.
+    pub async fn run(&self) {
+        let mut ticker = tokio::time::interval(self.config.flush_interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            self.flush_all().await;
+        }
+    }
+}
+
+/// `base_ms * 2^attempt` capped at `max_delay_ms`, plus uniform jitter in
+/// `[0, delay)`. Mirrors `handler::local_ilm::backoff_with_jitter` -- kept
+/// as its own copy since the two retry loops have unrelated failure modes
+/// (a chat call's time-budget abandonment doesn't apply here).
+fn backoff_with_jitter(attempt: u32, base_ms: u64, max_delay_ms: u64) -> Duration {
+    let exp = base_ms.saturating_mul(1u64 << attempt.min(32));
+    let delay_ms = exp.min(max_delay_ms);
+    let jitter_ms = if delay_ms == 0 {
+        0
+    } else {
+        rand::thread_rng().gen_range(0..delay_ms)
+    };
+    Duration::from_millis(delay_ms + jitter_ms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use linnix_ai_ebpf_common::NetEvent;
+
+    fn net_frame(pid: u32, bytes: u64) -> EventFrame {
+        EventFrame::Net(NetEvent { pid, _pad: 0, bytes })
+    }
+
+    #[test]
+    fn record_buffers_rows_under_their_table() {
+        let exporter = ClickHouseExporter::new(ExportConfig::default());
+        exporter.record(1, &net_frame(10, 100));
+        exporter.record(2, &net_frame(11, 200));
+
+        let buffers = exporter.buffers.lock().unwrap();
+        assert_eq!(buffers.get("linnix_net_events").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn record_tags_each_row_with_its_seq() {
+        let exporter = ClickHouseExporter::new(ExportConfig::default());
+        exporter.record(42, &net_frame(10, 100));
+
+        let buffers = exporter.buffers.lock().unwrap();
+        let row = &buffers.get("linnix_net_events").unwrap()[0];
+        let value: serde_json::Value = serde_json::from_str(row).unwrap();
+        assert_eq!(value["seq"], 42);
+        assert_eq!(value["pid"], 10);
+    }
+
+    #[test]
+    fn record_drops_oldest_rows_once_the_buffer_overflows() {
+        let config = ExportConfig {
+            max_batch_size: 2,
+            ..ExportConfig::default()
+        };
+        let exporter = ClickHouseExporter::new(config);
+        // MAX_BUFFERED_BATCHES * max_batch_size == 8; push past that.
+        for seq in 0..9 {
+            exporter.record(seq, &net_frame(seq as u32, 0));
+        }
+
+        assert_eq!(exporter.stats().rows_dropped_total, 1);
+        let buffers = exporter.buffers.lock().unwrap();
+        let rows = buffers.get("linnix_net_events").unwrap();
+        assert_eq!(rows.len(), 8);
+        let oldest: serde_json::Value = serde_json::from_str(&rows[0]).unwrap();
+        assert_eq!(oldest["seq"], 1, "seq 0 should have been dropped first");
+    }
+
+    #[tokio::test]
+    async fn flush_due_only_drains_tables_at_the_batch_size() {
+        let config = ExportConfig {
+            max_batch_size: 2,
+            url: "http://127.0.0.1:0".to_string(),
+            retry_max: 0,
+            ..ExportConfig::default()
+        };
+        let exporter = ClickHouseExporter::new(config);
+        exporter.record(1, &net_frame(1, 0));
+        exporter.flush_due().await;
+
+        // Below max_batch_size, so flush_due leaves it buffered.
+        let buffers = exporter.buffers.lock().unwrap();
+        assert_eq!(buffers.get("linnix_net_events").unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn flush_table_counts_a_failure_and_drops_its_rows_once_retries_are_exhausted() {
+        let config = ExportConfig {
+            url: "http://127.0.0.1:0".to_string(),
+            retry_max: 0,
+            ..ExportConfig::default()
+        };
+        let exporter = ClickHouseExporter::new(config);
+        exporter.flush_table("linnix_net_events", vec!["{\"pid\":1}".to_string()]).await;
+
+        let stats = exporter.stats();
+        assert_eq!(stats.flush_failures_total, 1);
+        assert_eq!(stats.rows_dropped_total, 1);
+        assert_eq!(stats.rows_exported_total, 0);
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_ceiling() {
+        let d0 = backoff_with_jitter(0, 100, 300);
+        let d1 = backoff_with_jitter(1, 100, 300);
+        let d2 = backoff_with_jitter(2, 100, 300);
+        assert!(d0 >= Duration::from_millis(100) && d0 < Duration::from_millis(200));
+        assert!(d1 >= Duration::from_millis(200) && d1 < Duration::from_millis(400));
+        assert!(d2 >= Duration::from_millis(300) && d2 < Duration::from_millis(600));
+        let d3 = backoff_with_jitter(3, 100, 300);
+        assert!(d3 >= Duration::from_millis(300) && d3 < Duration::from_millis(600));
+    }
+}
@@ -1,20 +1,189 @@
 use anyhow::{Context, Result, anyhow};
-use reqwest::{Client, Url};
-use serde::Serialize;
-use serde_json::Value;
+use once_cell::sync::Lazy;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::fmt;
 use std::time::Duration;
 
-const INSIGHT_GRAMMAR: &str = r#"root ::= "{" space "\"class\"" space ":" space class space "," space "\"confidence\"" space ":" space confidence space "," space "\"why\"" space ":" space string space "," space "\"actions\"" space ":" space actions space "}"
-class ::= "\"fork_storm\"" | "\"cpu_spin\"" | "\"memory_leak\"" | "\"runaway_tree\"" | "\"short_lived_jobs\"" | "\"unknown\""
-confidence ::= "0." [0-9] [0-9]? | "1.0"
-string ::= "\"" [^\"]* "\""
-actions ::= "[" space (string (space "," space string)*)? space "]"
-space ::= [ \t\n]*"#;
+/// The insight classes the model may return, in the order they're listed in
+/// prompts and schemas. Single source of truth for [`insight_json_schema`],
+/// [`INSIGHT_GRAMMAR`], and the human-readable class list in `mod.rs`'s
+/// system/user prompts, so none of them can drift out of sync with each
+/// other or with `schema::InsightClass`.
+pub const INSIGHT_CLASSES: &[&str] = &[
+    "fork_storm",
+    "short_job_flood",
+    "runaway_tree",
+    "cpu_spin",
+    "io_saturation",
+    "oom_risk",
+    "normal",
+];
 
-#[derive(Clone)]
+/// JSON Schema for the insight object, attached to completion requests as
+/// `response_format: {type:"json_schema", json_schema:{...}}` for endpoints
+/// that honor structured-output decoding.
+pub fn insight_json_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "class": { "type": "string", "enum": INSIGHT_CLASSES },
+            "confidence": { "type": "number", "minimum": 0.0, "maximum": 1.0 },
+            "primary_process": { "type": ["string", "null"] },
+            "why": { "type": "string", "maxLength": 120 },
+            "actions": {
+                "type": "array",
+                "items": { "type": "string" },
+                "maxItems": 3
+            }
+        },
+        "required": ["class", "confidence", "primary_process", "why", "actions"],
+        "additionalProperties": false
+    })
+}
+
+/// `response_format` payload wrapping [`insight_json_schema`], for
+/// OpenAI-compatible endpoints that honor structured-output decoding.
+fn insight_response_format() -> Value {
+    json!({
+        "type": "json_schema",
+        "json_schema": {
+            "name": "insight",
+            "schema": insight_json_schema()
+        }
+    })
+}
+
+/// GBNF grammar equivalent of [`insight_json_schema`], for llama.cpp-style
+/// endpoints that honor `grammar` instead of `response_format`. Generated
+/// once from [`INSIGHT_CLASSES`] so it can never list a class the schema
+/// (or the prompt text) doesn't.
+static INSIGHT_GRAMMAR: Lazy<String> = Lazy::new(|| {
+    let class_alt = INSIGHT_CLASSES
+        .iter()
+        .map(|class| format!("\"\\\"{class}\\\"\""))
+        .collect::<Vec<_>>()
+        .join(" | ");
+    format!(
+        "root ::= \"{{\" space \"\\\"class\\\"\" space \":\" space class space \",\" space \"\\\"confidence\\\"\" space \":\" space confidence space \",\" space \"\\\"primary_process\\\"\" space \":\" space primary_process space \",\" space \"\\\"why\\\"\" space \":\" space string space \",\" space \"\\\"actions\\\"\" space \":\" space actions space \"}}\"\n\
+         class ::= {class_alt}\n\
+         confidence ::= \"0.\" [0-9] [0-9]? | \"1.0\"\n\
+         string ::= \"\\\"\" [^\"]* \"\\\"\"\n\
+         primary_process ::= \"null\" | string\n\
+         actions ::= \"[\" space (string (space \",\" space string)*)? space \"]\"\n\
+         space ::= [ \\t\\n]*"
+    )
+});
+
+#[derive(Clone, Default)]
 pub struct ChatMessage {
     pub role: &'static str,
     pub content: String,
+    /// Set on a `role: "tool"` message carrying one tool's result back to
+    /// the model; must match the `id` on the `ToolCall` it answers.
+    pub tool_call_id: Option<String>,
+    /// Set on an assistant message that requested tool calls instead of
+    /// answering directly, so it can be replayed back into the
+    /// conversation verbatim alongside the matching `role: "tool"` replies.
+    pub tool_calls: Option<Vec<ToolCall>>,
+}
+
+impl ChatMessage {
+    pub fn new(role: &'static str, content: impl Into<String>) -> Self {
+        Self {
+            role,
+            content: content.into(),
+            ..Self::default()
+        }
+    }
+
+    /// A `role: "tool"` reply carrying one tool's output back to the model.
+    pub fn tool_result(tool_call_id: String, content: String) -> Self {
+        Self {
+            role: "tool",
+            content,
+            tool_call_id: Some(tool_call_id),
+            tool_calls: None,
+        }
+    }
+
+    /// The assistant turn that requested `tool_calls`, replayed back into
+    /// the conversation so the model sees its own prior request alongside
+    /// the tool results that answer it.
+    pub fn assistant_tool_calls(tool_calls: Vec<ToolCall>) -> Self {
+        Self {
+            role: "assistant",
+            content: String::new(),
+            tool_call_id: None,
+            tool_calls: Some(tool_calls),
+        }
+    }
+}
+
+/// One function call requested by the model via the native OpenAI-style
+/// `tool_calls` protocol (as opposed to the legacy `TOOL: <name> <pid>`
+/// text-line convention).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ToolCall {
+    pub id: String,
+    #[serde(rename = "type", default = "default_tool_call_type")]
+    pub kind: String,
+    pub function: ToolCallFunction,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ToolCallFunction {
+    pub name: String,
+    /// JSON-encoded argument object, e.g. `"{\"pid\":1234}"`.
+    pub arguments: String,
+}
+
+fn default_tool_call_type() -> String {
+    "function".to_string()
+}
+
+/// What a tool-enabled `chat_with_tools` call produced: either the model
+/// answered directly, or it asked to run one or more tools first.
+#[derive(Debug)]
+pub enum ChatOutcome {
+    Message(String),
+    ToolCalls(Vec<ToolCall>),
+}
+
+/// A 401/403 response from the LLM endpoint, surfaced distinctly from a
+/// generic connection/status failure so callers can report `auth_failed`
+/// instead of `unreachable`/`request_failed`.
+#[derive(Debug)]
+pub struct AuthError(pub reqwest::StatusCode);
+
+impl fmt::Display for AuthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "authentication failed with status {}", self.0)
+    }
+}
+
+impl std::error::Error for AuthError {}
+
+/// Resolve a configured auth token spec into a literal value. `env:NAME`
+/// reads from an environment variable, `file:/path` reads from a file (e.g.
+/// a mounted Kubernetes secret), and anything else is used as-is -- so
+/// `ReasonerConfig::auth_token` never needs the raw secret inlined.
+pub fn resolve_auth_token(raw: &str) -> Option<String> {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return None;
+    }
+    if let Some(var) = raw.strip_prefix("env:") {
+        return std::env::var(var).ok();
+    }
+    if let Some(path) = raw.strip_prefix("file:") {
+        return std::fs::read_to_string(path)
+            .ok()
+            .map(|s| s.trim().to_string());
+    }
+    Some(raw.to_string())
 }
 
 #[derive(Clone)]
@@ -22,6 +191,8 @@ pub struct IlmClient {
     client: Client,
     endpoint: Url,
     timeout: Duration,
+    auth_token: Option<String>,
+    extra_headers: HashMap<String, String>,
 }
 
 impl IlmClient {
@@ -35,21 +206,43 @@ impl IlmClient {
             client,
             endpoint,
             timeout,
+            auth_token: None,
+            extra_headers: HashMap::new(),
         })
     }
 
+    /// Attach a bearer token and/or static extra headers, for endpoints
+    /// behind a gateway or a remote inference server that requires
+    /// authentication.
+    pub fn with_auth(mut self, auth_token: Option<String>, extra_headers: HashMap<String, String>) -> Self {
+        self.auth_token = auth_token;
+        self.extra_headers = extra_headers;
+        self
+    }
+
+    fn apply_auth(&self, mut request: RequestBuilder) -> RequestBuilder {
+        if let Some(token) = &self.auth_token {
+            request = request.bearer_auth(token);
+        }
+        for (key, value) in &self.extra_headers {
+            request = request.header(key, value);
+        }
+        request
+    }
+
     pub async fn check_health(&self) -> Result<()> {
         let mut url = self.endpoint.clone();
         url.set_path("/v1/models");
         url.set_query(None);
         let resp = self
-            .client
-            .get(url)
+            .apply_auth(self.client.get(url))
             .send()
             .await
             .context("health request failed")?;
         if resp.status().is_success() {
             Ok(())
+        } else if resp.status().as_u16() == 401 || resp.status().as_u16() == 403 {
+            Err(anyhow::Error::new(AuthError(resp.status())))
         } else {
             Err(anyhow!("health check returned status {}", resp.status()))
         }
@@ -59,23 +252,185 @@ impl IlmClient {
         self.timeout
     }
 
+    /// Best-effort capability probe for the native OpenAI-style `tool_calls`
+    /// protocol: some OpenAI-compatible servers advertise it via a
+    /// `capabilities.tool_calls` field on `/v1/models`. Servers that omit it
+    /// are assumed to support it optimistically -- `run_tool_loop` still
+    /// falls back to the legacy `TOOL:` text-line protocol for any response
+    /// that comes back without `tool_calls`, so a wrong "supported" guess
+    /// just costs one ignored `tools` array in the request.
+    pub async fn supports_native_tool_calls(&self) -> bool {
+        let mut url = self.endpoint.clone();
+        url.set_path("/v1/models");
+        url.set_query(None);
+        let Ok(resp) = self.apply_auth(self.client.get(url)).send().await else {
+            return true;
+        };
+        let Ok(value) = resp.json::<Value>().await else {
+            return true;
+        };
+        value
+            .get("capabilities")
+            .and_then(|c| c.get("tool_calls"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true)
+    }
+
+    /// Like [`IlmClient::chat`], but advertises `tools` to the model and
+    /// returns whether it answered directly or asked to run one first.
+    /// Tool-calling responses aren't streamed: a partial `tool_calls` array
+    /// assembled mid-stream isn't actionable until it's complete anyway, so
+    /// this always requests a buffered completion.
+    pub async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: &[Value],
+    ) -> Result<ChatOutcome> {
+        if messages.is_empty() {
+            return Err(anyhow!("chat requires at least one message"));
+        }
+        let payload = build_tool_request(messages, tools);
+        let resp = self
+            .apply_auth(self.client.post(self.endpoint.clone()).json(&payload))
+            .send()
+            .await
+            .context("chat request failed")?;
+        if resp.status().as_u16() == 401 || resp.status().as_u16() == 403 {
+            return Err(anyhow::Error::new(AuthError(resp.status())));
+        }
+        if !resp.status().is_success() {
+            return Err(anyhow!("chat request status {}", resp.status()));
+        }
+
+        let value: Value = resp.json().await.context("failed to parse chat response")?;
+        extract_outcome(&value)
+    }
+
     pub async fn chat(&self, messages: &[ChatMessage]) -> Result<String> {
         if messages.is_empty() {
             return Err(anyhow!("chat requires at least one message"));
         }
         let payload = build_request(messages);
         let resp = self
-            .client
-            .post(self.endpoint.clone())
-            .json(&payload)
+            .apply_auth(self.client.post(self.endpoint.clone()).json(&payload))
             .send()
             .await
             .context("chat request failed")?;
+        if resp.status().as_u16() == 401 || resp.status().as_u16() == 403 {
+            return Err(anyhow::Error::new(AuthError(resp.status())));
+        }
         if !resp.status().is_success() {
             return Err(anyhow!("chat request status {}", resp.status()));
         }
-        let value: Value = resp.json().await.context("failed to parse chat response")?;
-        extract_message(&value)
+
+        let is_event_stream = resp
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.contains("text/event-stream"));
+
+        if is_event_stream {
+            read_streamed_completion(resp).await
+        } else {
+            // The server ignored `stream: true` and returned a single buffered
+            // completion; fall back to the non-streaming parse path.
+            let value: Value = resp.json().await.context("failed to parse chat response")?;
+            extract_message(&value)
+        }
+    }
+}
+
+/// Tracks JSON brace depth across incrementally-fed characters so a streamed
+/// response can be cut off the moment the first top-level `{...}` object
+/// closes, without waiting for the rest of the stream. Braces inside quoted
+/// strings (tracking `\`-escapes) don't count.
+#[derive(Default)]
+struct JsonObjectCutoff {
+    started: bool,
+    depth: i32,
+    in_string: bool,
+    escaped: bool,
+}
+
+impl JsonObjectCutoff {
+    /// Feed one character; returns `true` once the `{` that started this
+    /// object has been closed by its matching `}`.
+    fn feed(&mut self, ch: char) -> bool {
+        if !self.started {
+            if ch == '{' {
+                self.started = true;
+                self.depth = 1;
+            }
+            return false;
+        }
+        if self.escaped {
+            self.escaped = false;
+        } else if ch == '\\' {
+            self.escaped = true;
+        } else if ch == '"' {
+            self.in_string = !self.in_string;
+        } else if !self.in_string {
+            match ch {
+                '{' => self.depth += 1,
+                '}' => self.depth -= 1,
+                _ => {}
+            }
+        }
+        self.started && self.depth == 0
+    }
+}
+
+/// Consume an OpenAI-style `text/event-stream` chat completion, assembling
+/// `delta.content` fragments as they arrive and returning as soon as a
+/// complete JSON object has been seen -- reclaiming the remaining time
+/// budget instead of waiting for the model to finish trailing tokens.
+async fn read_streamed_completion(mut resp: reqwest::Response) -> Result<String> {
+    let mut buf = String::new();
+    let mut assembled = String::new();
+    let mut cutoff = JsonObjectCutoff::default();
+
+    while let Some(chunk) = resp.chunk().await.context("error reading stream chunk")? {
+        buf.push_str(&String::from_utf8_lossy(&chunk));
+
+        while let Some(pos) = buf.find("\n\n") {
+            let raw_event: String = buf.drain(..pos + 2).collect();
+            for line in raw_event.lines() {
+                let Some(data) = line.strip_prefix("data:") else {
+                    continue;
+                };
+                let data = data.trim();
+                if data == "[DONE]" {
+                    return Ok(assembled);
+                }
+
+                let Ok(delta) = serde_json::from_str::<Value>(data) else {
+                    continue;
+                };
+                let Some(content) = delta
+                    .get("choices")
+                    .and_then(|c| c.as_array())
+                    .and_then(|c| c.first())
+                    .and_then(|c| c.get("delta"))
+                    .and_then(|d| d.get("content"))
+                    .and_then(|c| c.as_str())
+                else {
+                    continue;
+                };
+
+                for ch in content.chars() {
+                    assembled.push(ch);
+                    if cutoff.feed(ch) {
+                        return Ok(assembled);
+                    }
+                }
+            }
+        }
+    }
+
+    if assembled.is_empty() {
+        Err(anyhow!("stream ended before any content was received"))
+    } else {
+        Ok(assembled)
     }
 }
 
@@ -88,29 +443,65 @@ struct ChatRequest<'a> {
     messages: Vec<MessagePayload<'a>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     grammar: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tools: Option<&'a [Value]>,
 }
 
 #[derive(Serialize)]
 struct MessagePayload<'a> {
     role: &'a str,
     content: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_call_id: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_calls: Option<&'a [ToolCall]>,
+}
+
+fn to_payload(m: &ChatMessage) -> MessagePayload<'_> {
+    MessagePayload {
+        role: m.role,
+        content: m.content.as_str(),
+        tool_call_id: m.tool_call_id.as_deref(),
+        tool_calls: m.tool_calls.as_deref(),
+    }
 }
 
 fn build_request(messages: &[ChatMessage]) -> ChatRequest<'_> {
-    let payload = messages
-        .iter()
-        .map(|m| MessagePayload {
-            role: m.role,
-            content: m.content.as_str(),
-        })
-        .collect();
+    ChatRequest {
+        model: "local-sre-llm",
+        temperature: 0.0,
+        max_tokens: 48,
+        // Ask for token deltas so `chat` can assemble and cut off as soon as
+        // a complete JSON object is seen; servers that ignore this fall back
+        // to the buffered `extract_message` path based on content-type.
+        stream: true,
+        messages: messages.iter().map(to_payload).collect(),
+        // Both constrain the same insight shape: `response_format` for
+        // OpenAI-compatible servers, `grammar` for llama.cpp-style ones that
+        // don't honor `response_format`. Servers that honor neither still
+        // see the schema spelled out in the prompt text itself.
+        grammar: Some(INSIGHT_GRAMMAR.as_str()),
+        response_format: Some(insight_response_format()),
+        tools: None,
+    }
+}
+
+/// Grammar-constrained decoding and function-calling are mutually exclusive
+/// on most OpenAI-compatible servers, so a `tools`-bearing request drops the
+/// grammar. Tool-calling responses also aren't streamed (see
+/// `chat_with_tools`'s doc comment), so `stream` is always `false` here.
+fn build_tool_request<'a>(messages: &'a [ChatMessage], tools: &'a [Value]) -> ChatRequest<'a> {
     ChatRequest {
         model: "local-sre-llm",
         temperature: 0.0,
         max_tokens: 48,
         stream: false,
-        messages: payload,
-        grammar: Some(INSIGHT_GRAMMAR),
+        messages: messages.iter().map(to_payload).collect(),
+        grammar: None,
+        response_format: Some(insight_response_format()),
+        tools: Some(tools),
     }
 }
 
@@ -130,6 +521,42 @@ fn extract_message(value: &Value) -> Result<String> {
     Ok(message.trim().to_string())
 }
 
+fn extract_outcome(value: &Value) -> Result<ChatOutcome> {
+    let choices = value
+        .get("choices")
+        .and_then(|c| c.as_array())
+        .ok_or_else(|| anyhow!("completion missing choices array"))?;
+    let message = choices
+        .first()
+        .ok_or_else(|| anyhow!("completion choices empty"))?
+        .get("message")
+        .ok_or_else(|| anyhow!("completion missing message"))?;
+
+    let tool_calls = message
+        .get("tool_calls")
+        .and_then(|calls| calls.as_array())
+        .filter(|calls| !calls.is_empty())
+        .map(|calls| {
+            calls
+                .iter()
+                .cloned()
+                .map(serde_json::from_value)
+                .collect::<serde_json::Result<Vec<ToolCall>>>()
+        })
+        .transpose()
+        .context("completion had a malformed tool_calls entry")?;
+
+    if let Some(tool_calls) = tool_calls {
+        return Ok(ChatOutcome::ToolCalls(tool_calls));
+    }
+
+    let content = message
+        .get("content")
+        .and_then(|c| c.as_str())
+        .ok_or_else(|| anyhow!("completion missing message content"))?;
+    Ok(ChatOutcome::Message(content.trim().to_string()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -144,12 +571,207 @@ mod tests {
 
     #[test]
     fn test_request_includes_grammar() {
-        let msg = ChatMessage {
-            role: "user",
-            content: "test".to_string(),
-        };
+        let msg = ChatMessage::new("user", "test");
         let messages = [msg];
         let req = build_request(&messages);
-        assert_eq!(req.grammar, Some(INSIGHT_GRAMMAR));
+        assert_eq!(req.grammar, Some(INSIGHT_GRAMMAR.as_str()));
+        assert!(req.response_format.is_some());
+    }
+
+    #[test]
+    fn insight_json_schema_and_grammar_list_the_same_classes() {
+        let schema = insight_json_schema();
+        let schema_classes = schema["properties"]["class"]["enum"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| v.as_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(schema_classes, INSIGHT_CLASSES);
+
+        for class in INSIGHT_CLASSES {
+            assert!(
+                INSIGHT_GRAMMAR.contains(&format!("\"{class}\"")),
+                "grammar missing class {class}"
+            );
+        }
+    }
+
+    #[test]
+    fn resolve_auth_token_reads_literal_env_and_file() {
+        assert_eq!(resolve_auth_token(""), None);
+        assert_eq!(resolve_auth_token("sk-literal-token"), Some("sk-literal-token".to_string()));
+
+        unsafe {
+            std::env::set_var("LINNIX_TEST_AUTH_TOKEN", "sk-from-env");
+        }
+        assert_eq!(
+            resolve_auth_token("env:LINNIX_TEST_AUTH_TOKEN"),
+            Some("sk-from-env".to_string())
+        );
+        unsafe {
+            std::env::remove_var("LINNIX_TEST_AUTH_TOKEN");
+        }
+
+        let temp = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(temp.path(), "sk-from-file\n").unwrap();
+        assert_eq!(
+            resolve_auth_token(&format!("file:{}", temp.path().display())),
+            Some("sk-from-file".to_string())
+        );
+    }
+
+    #[test]
+    fn json_object_cutoff_ignores_braces_in_strings() {
+        let mut cutoff = JsonObjectCutoff::default();
+        let mut closed_at = None;
+        for (i, ch) in r#"{"why":"uses a { brace"}"#.chars().enumerate() {
+            if cutoff.feed(ch) {
+                closed_at = Some(i);
+                break;
+            }
+        }
+        assert_eq!(closed_at, Some(r#"{"why":"uses a { brace"}"#.chars().count() - 1));
+    }
+
+    #[test]
+    fn json_object_cutoff_closes_on_matching_brace() {
+        let mut cutoff = JsonObjectCutoff::default();
+        assert!(!cutoff.feed('{'));
+        assert!(!cutoff.feed('{'));
+        assert!(!cutoff.feed('}'));
+        assert!(cutoff.feed('}'));
+    }
+
+    #[tokio::test]
+    async fn chat_assembles_streamed_deltas_and_stops_after_first_object() {
+        use axum::routing::post;
+        use axum::Router;
+
+        async fn streaming_handler() -> axum::response::Response {
+            let body = concat!(
+                "data: {\"choices\":[{\"delta\":{\"content\":\"{\\\"a\\\":1}\"}}]}\n\n",
+                "data: {\"choices\":[{\"delta\":{\"content\":\" trailing junk\"}}]}\n\n",
+                "data: [DONE]\n\n",
+            );
+            axum::response::Response::builder()
+                .header(axum::http::header::CONTENT_TYPE, "text/event-stream")
+                .body(axum::body::Body::from(body))
+                .unwrap()
+        }
+
+        let app = Router::new().route("/v1/chat/completions", post(streaming_handler));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let _ = axum::serve(listener, app.into_make_service()).await;
+        });
+
+        let client = IlmClient::new(&format!("http://{addr}/v1/chat/completions"), Duration::from_secs(5))
+            .unwrap();
+        let messages = vec![ChatMessage::new("user", "hi")];
+
+        let response = client.chat(&messages).await.unwrap();
+        assert_eq!(response, "{\"a\":1}");
+    }
+
+    #[tokio::test]
+    async fn chat_with_tools_returns_tool_calls_when_the_model_requests_them() {
+        use axum::response::IntoResponse;
+        use axum::routing::post;
+        use axum::Router;
+
+        async fn handler() -> axum::response::Response {
+            axum::Json(serde_json::json!({
+                "choices": [{
+                    "message": {
+                        "role": "assistant",
+                        "content": null,
+                        "tool_calls": [{
+                            "id": "call_1",
+                            "type": "function",
+                            "function": {"name": "ps_tree", "arguments": "{\"pid\":123}"}
+                        }]
+                    }
+                }]
+            }))
+            .into_response()
+        }
+
+        let app = Router::new().route("/v1/chat/completions", post(handler));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let _ = axum::serve(listener, app.into_make_service()).await;
+        });
+
+        let client = IlmClient::new(&format!("http://{addr}/v1/chat/completions"), Duration::from_secs(5))
+            .unwrap();
+        let messages = vec![ChatMessage::new("user", "investigate pid 123")];
+        let tools = vec![serde_json::json!({
+            "type": "function",
+            "function": {"name": "ps_tree", "parameters": {"type": "object"}}
+        })];
+
+        match client.chat_with_tools(&messages, &tools).await.unwrap() {
+            ChatOutcome::ToolCalls(calls) => {
+                assert_eq!(calls.len(), 1);
+                assert_eq!(calls[0].function.name, "ps_tree");
+                assert_eq!(calls[0].function.arguments, "{\"pid\":123}");
+            }
+            ChatOutcome::Message(_) => panic!("expected tool calls"),
+        }
+    }
+
+    #[tokio::test]
+    async fn chat_with_tools_returns_a_message_once_the_model_is_done() {
+        use axum::response::IntoResponse;
+        use axum::routing::post;
+        use axum::Router;
+
+        async fn handler() -> axum::response::Response {
+            axum::Json(serde_json::json!({
+                "choices": [{"message": {"role": "assistant", "content": "{\"class\":\"normal\"}"}}]
+            }))
+            .into_response()
+        }
+
+        let app = Router::new().route("/v1/chat/completions", post(handler));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let _ = axum::serve(listener, app.into_make_service()).await;
+        });
+
+        let client = IlmClient::new(&format!("http://{addr}/v1/chat/completions"), Duration::from_secs(5))
+            .unwrap();
+        let messages = vec![ChatMessage::new("user", "hi")];
+
+        match client.chat_with_tools(&messages, &[]).await.unwrap() {
+            ChatOutcome::Message(content) => assert_eq!(content, "{\"class\":\"normal\"}"),
+            ChatOutcome::ToolCalls(_) => panic!("expected a plain message"),
+        }
+    }
+
+    #[tokio::test]
+    async fn supports_native_tool_calls_defaults_to_true_without_a_capabilities_field() {
+        use axum::response::IntoResponse;
+        use axum::routing::get;
+        use axum::Router;
+
+        async fn models_handler() -> axum::response::Response {
+            axum::Json(serde_json::json!({"data": []})).into_response()
+        }
+
+        let app = Router::new().route("/v1/models", get(models_handler));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let _ = axum::serve(listener, app.into_make_service()).await;
+        });
+
+        let client = IlmClient::new(&format!("http://{addr}/v1/chat/completions"), Duration::from_secs(5))
+            .unwrap();
+        assert!(client.supports_native_tool_calls().await);
     }
 }
@@ -1,7 +1,9 @@
 use super::Handler;
+use crate::circuit_breaker::{ActionClass, CircuitBreaker, CircuitBreakerConfig};
 use crate::config::ReasonerConfig;
-use crate::insights::InsightStore;
+use crate::insights::{self, InsightStore};
 use crate::metrics::Metrics;
+use crate::utils::psi::PsiMetrics;
 use crate::{
     ProcessEvent, context::ContextStore, context::ProcessMemorySummary, types::SystemSnapshot,
 };
@@ -9,6 +11,7 @@ use async_trait::async_trait;
 use client::{ChatMessage, IlmClient};
 use linnix_ai_ebpf_common::EventType;
 use log::{debug, info, warn};
+use rand::Rng;
 use schema::{Insight, parse_and_validate};
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -18,6 +21,7 @@ use tokio::time::{Instant, MissedTickBehavior};
 
 pub mod client;
 pub mod rag;
+pub mod registry;
 pub mod schema;
 pub mod tools;
 
@@ -28,6 +32,48 @@ const MAX_TOOL_LINES: usize = 32;
 const MAX_KB_SNIPPETS: usize = 1;
 const KB_SNIPPET_MAX_CHARS: usize = 256;
 
+/// The local-ILM handler's health, covering its full lifecycle from startup
+/// through steady-state operation. Passed to `Metrics::set_ilm_disabled_reason`
+/// in place of the ad-hoc strings this used to carry, so API/UI consumers can
+/// match on a fixed set of causes instead of parsing free text, while
+/// `as_str()` still gives the stable label that shows up in metrics and logs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IlmStatus {
+    Healthy,
+    DisabledInConfig,
+    EmptyEndpoint,
+    ClientBuildFailed,
+    Unreachable,
+    AuthFailed,
+    Timeout,
+    RequestFailed,
+    SchemaError,
+    FallbackLastInsight,
+}
+
+impl IlmStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IlmStatus::Healthy => "healthy",
+            IlmStatus::DisabledInConfig => "disabled_in_config",
+            IlmStatus::EmptyEndpoint => "empty_endpoint",
+            IlmStatus::ClientBuildFailed => "client_build_failed",
+            IlmStatus::Unreachable => "unreachable",
+            IlmStatus::AuthFailed => "auth_failed",
+            IlmStatus::Timeout => "timeout",
+            IlmStatus::RequestFailed => "request_failed",
+            IlmStatus::SchemaError => "schema_error",
+            IlmStatus::FallbackLastInsight => "fallback_last_insight",
+        }
+    }
+}
+
+impl std::fmt::Display for IlmStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 pub struct LocalIlmHandlerRag {
     tx: mpsc::Sender<ProcessEvent>,
 }
@@ -43,14 +89,14 @@ impl LocalIlmHandlerRag {
     ) -> Option<Self> {
         if !cfg.enabled {
             metrics.set_ilm_enabled(false);
-            metrics.set_ilm_disabled_reason(Some("disabled_in_config".to_string()));
+            metrics.set_ilm_disabled_reason(IlmStatus::DisabledInConfig);
             return None;
         }
 
         let endpoint = cfg.endpoint.trim();
         if endpoint.is_empty() {
             metrics.set_ilm_enabled(false);
-            metrics.set_ilm_disabled_reason(Some("empty_endpoint".to_string()));
+            metrics.set_ilm_disabled_reason(IlmStatus::EmptyEndpoint);
             warn!("[local-ilm] endpoint empty; disabling handler");
             return None;
         }
@@ -62,21 +108,31 @@ impl LocalIlmHandlerRag {
             Ok(client) => client,
             Err(err) => {
                 metrics.set_ilm_enabled(false);
-                metrics.set_ilm_disabled_reason(Some(format!("client_error:{err}")));
+                metrics.set_ilm_disabled_reason(IlmStatus::ClientBuildFailed);
                 warn!("[local-ilm] failed to build HTTP client: {err}");
                 return None;
             }
         };
+        let auth_token = cfg
+            .auth_token
+            .as_deref()
+            .and_then(client::resolve_auth_token);
+        let client = client.with_auth(auth_token, cfg.extra_headers.clone());
 
         if let Err(err) = client.check_health().await {
             metrics.set_ilm_enabled(false);
-            metrics.set_ilm_disabled_reason(Some("unreachable".to_string()));
+            let reason = if err.downcast_ref::<client::AuthError>().is_some() {
+                IlmStatus::AuthFailed
+            } else {
+                IlmStatus::Unreachable
+            };
+            metrics.set_ilm_disabled_reason(reason);
             warn!("[local-ilm] LLM endpoint health check failed: {err}");
             return None;
         }
 
         metrics.set_ilm_enabled(true);
-        metrics.set_ilm_disabled_reason(None);
+        metrics.set_ilm_disabled_reason(IlmStatus::Healthy);
 
         let (tx, rx) = mpsc::channel(CHANNEL_DEPTH);
         let handler = Self { tx: tx.clone() };
@@ -146,6 +202,13 @@ async fn run_worker(
     ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
     let mut last_error: Option<String> = None;
     let mut last_insight: Option<Insight> = None;
+    let tool_registry = registry::default_registry();
+    let native_tools = cfg.tools_enabled && client.supports_native_tool_calls().await;
+    // Gates this worker's own LLM calls and kill proposals on system stall,
+    // same breaker shape `PsiMonitor` would use -- a reasoning pass and an
+    // auto-approved kill are exactly the "expensive/disruptive actions"
+    // `CircuitBreaker` exists to trip on.
+    let mut breaker = CircuitBreaker::new(CircuitBreakerConfig::default());
 
     loop {
         tokio::select! {
@@ -167,6 +230,16 @@ async fn run_worker(
                     continue;
                 }
 
+                breaker.poll(&PsiMetrics::from(&context.get_system_snapshot()));
+                if !breaker.should_allow(ActionClass::LlmCall) {
+                    debug!(
+                        "[local-ilm] circuit breaker open, skipping LLM call this window: {}",
+                        breaker.reason()
+                    );
+                    buffer.clear();
+                    continue;
+                }
+
                 let events: Vec<ProcessEvent> = std::mem::take(&mut buffer);
                 let summary = summarize_window(&events);
                 let query = build_query_string(&summary);
@@ -297,73 +370,77 @@ async fn run_worker(
                 let system_prompt = build_system_prompt();
 
                 let messages = vec![
-                    ChatMessage {
-                        role: "system",
-                        content: system_prompt.clone(),
-                    },
-                    ChatMessage {
-                        role: "user",
-                        content: user_prompt.clone(),
-                    },
+                    ChatMessage::new("system", system_prompt.clone()),
+                    ChatMessage::new("user", user_prompt.clone()),
                 ];
 
                 let start = Instant::now();
-                match client.chat(&messages).await {
-                    Ok(mut response) => {
-                        if cfg.tools_enabled
-                            && let Some((tool_name, pid)) = detect_tool_request(&response)
-                        {
-                            let elapsed = start.elapsed();
-                            let timeout = client.timeout();
-                            if elapsed < timeout.saturating_sub(Duration::from_millis(20))
-                                && let Some(tool_context) = execute_tool(tool_name.as_str(), pid)
-                            {
-                                let followup_prompt = build_followup_prompt(
-                                    &telemetry_prompt,
-                                    &snippets_joined,
-                                    tool_name.as_str(),
-                                    pid,
-                                    &tool_context,
-                                    &response,
-                                );
-                                let followup_messages = vec![
-                                    ChatMessage {
-                                        role: "system",
-                                        content: system_prompt.clone(),
-                                    },
-                                    ChatMessage {
-                                        role: "user",
-                                        content: followup_prompt,
-                                    },
-                                ];
-                                match client.chat(&followup_messages).await {
-                                    Ok(final_response) => response = final_response,
-                                    Err(err) => {
-                                        metrics.inc_ilm_timeouts();
-                                        metrics.set_ilm_enabled(false);
-                                        metrics.set_ilm_disabled_reason(Some(format!(
-                                            "followup_failed:{}",
-                                            err
-                                        )));
-                                        log_once(&mut last_error, format!(
-                                            "[local-ilm] follow-up request failed: {err}"
-                                        ));
-                                        continue;
-                                    }
-                                }
-                            }
+                let primary_call_start = Instant::now();
+                let mut tool_calls: Vec<insights::ToolCallRecord> = Vec::new();
+                let primary_result = if native_tools {
+                    run_native_investigation(
+                        &client,
+                        &cfg,
+                        &tool_registry,
+                        messages.clone(),
+                        start,
+                        &mut tool_calls,
+                    )
+                    .await
+                } else {
+                    match chat_with_retry(&client, &messages, &cfg, start).await {
+                        Ok(initial_response) => {
+                            run_tool_loop(
+                                &client,
+                                &cfg,
+                                &tool_registry,
+                                &system_prompt,
+                                &telemetry_prompt,
+                                &snippets_joined,
+                                start,
+                                insights.as_ref(),
+                                initial_response,
+                                &mut tool_calls,
+                            )
+                            .await
                         }
+                        Err(err) => Err(err),
+                    }
+                };
 
+                match primary_result {
+                    Ok(response) => {
                         match parse_and_validate(&response) {
                             Ok(insight) => {
+                                record_ilm_latency(
+                                    insights.as_ref(),
+                                    "primary",
+                                    "success",
+                                    primary_call_start.elapsed(),
+                                );
                                 debug!("[local-ilm] raw insight response: {}", response);
-                                emit_insight(&insight, &metrics, insights.as_ref(), &enforcement);
+                                let transcript = build_transcript(
+                                    &cfg,
+                                    &system_prompt,
+                                    &telemetry_prompt,
+                                    &kb_snippets,
+                                    &response,
+                                    &tool_calls,
+                                    0,
+                                );
+                                emit_insight(&insight, &metrics, insights.as_ref(), &enforcement, transcript, breaker.should_allow(ActionClass::ProcessKill));
                                 last_insight = Some(insight.clone());
                                 metrics.set_ilm_enabled(true);
-                                metrics.set_ilm_disabled_reason(None);
+                                metrics.set_ilm_disabled_reason(IlmStatus::Healthy);
                                 last_error = None;
                             }
                             Err(err) => {
+                                record_ilm_latency(
+                                    insights.as_ref(),
+                                    "primary",
+                                    "schema_error",
+                                    primary_call_start.elapsed(),
+                                );
                                 let mut parsed_fix: Option<Insight> = None;
                                 let mut error_message = format!(
                                     "[local-ilm] invalid insight payload: {err}; raw={response}"
@@ -371,17 +448,12 @@ async fn run_worker(
 
                                 let fix_prompt = build_fix_prompt(&err, &response);
                                 let fix_messages = vec![
-                                    ChatMessage {
-                                        role: "system",
-                                        content: system_prompt.clone(),
-                                    },
-                                    ChatMessage {
-                                        role: "user",
-                                        content: fix_prompt,
-                                    },
+                                    ChatMessage::new("system", system_prompt.clone()),
+                                    ChatMessage::new("user", fix_prompt),
                                 ];
 
-                                match client.chat(&fix_messages).await {
+                                let fixup_call_start = Instant::now();
+                                match chat_with_retry(&client, &fix_messages, &cfg, start).await {
                                     Ok(fix_response) => {
                                         debug!(
                                             "[local-ilm] fix-up raw response: {}",
@@ -389,14 +461,35 @@ async fn run_worker(
                                         );
                                         match parse_and_validate(&fix_response) {
                                             Ok(insight) => {
+                                                record_ilm_latency(
+                                                    insights.as_ref(),
+                                                    "fixup",
+                                                    "success",
+                                                    fixup_call_start.elapsed(),
+                                                );
                                                 parsed_fix = Some(insight.clone());
-                                                emit_insight(&insight, &metrics, insights.as_ref(), &enforcement);
+                                                let transcript = build_transcript(
+                                                    &cfg,
+                                                    &system_prompt,
+                                                    &telemetry_prompt,
+                                                    &kb_snippets,
+                                                    &fix_response,
+                                                    &tool_calls,
+                                                    1,
+                                                );
+                                                emit_insight(&insight, &metrics, insights.as_ref(), &enforcement, transcript, breaker.should_allow(ActionClass::ProcessKill));
                                                 last_insight = Some(insight);
                                                 metrics.set_ilm_enabled(true);
-                                                metrics.set_ilm_disabled_reason(None);
+                                                metrics.set_ilm_disabled_reason(IlmStatus::Healthy);
                                                 last_error = None;
                                             }
                                             Err(fix_err) => {
+                                                record_ilm_latency(
+                                                    insights.as_ref(),
+                                                    "fixup",
+                                                    "schema_error",
+                                                    fixup_call_start.elapsed(),
+                                                );
                                                 error_message = format!(
                                                     "[local-ilm] invalid insight after fix: {fix_err}; original_error={err}; raw_fix={fix_response}"
                                                 );
@@ -404,6 +497,12 @@ async fn run_worker(
                                         }
                                     }
                                     Err(fix_err) => {
+                                        record_ilm_latency(
+                                            insights.as_ref(),
+                                            "fixup",
+                                            chat_failure_outcome(&fix_err).as_str(),
+                                            fixup_call_start.elapsed(),
+                                        );
                                         error_message = format!(
                                             "[local-ilm] fix-up request failed: {fix_err}; original_error={err}; raw={response}"
                                         );
@@ -416,15 +515,22 @@ async fn run_worker(
                                         warn!(
                                             "[local-ilm] falling back to last known insight due to parse error"
                                         );
-                                        emit_insight(&insight, &metrics, insights.as_ref(), &enforcement);
+                                        let transcript = build_transcript(
+                                            &cfg,
+                                            &system_prompt,
+                                            &telemetry_prompt,
+                                            &kb_snippets,
+                                            &response,
+                                            &tool_calls,
+                                            1,
+                                        );
+                                        emit_insight(&insight, &metrics, insights.as_ref(), &enforcement, transcript, breaker.should_allow(ActionClass::ProcessKill));
                                         metrics.set_ilm_enabled(true);
-                                        metrics
-                                            .set_ilm_disabled_reason(Some("fallback_last_insight".to_string()));
+                                        metrics.set_ilm_disabled_reason(IlmStatus::FallbackLastInsight);
                                         log_once(&mut last_error, error_message);
                                     } else {
                                         metrics.set_ilm_enabled(false);
-                                        metrics
-                                            .set_ilm_disabled_reason(Some("schema_error".to_string()));
+                                        metrics.set_ilm_disabled_reason(IlmStatus::SchemaError);
                                         log_once(&mut last_error, error_message);
                                     }
                                 }
@@ -432,14 +538,11 @@ async fn run_worker(
                         }
                     }
                     Err(err) => {
+                        let reason = chat_failure_outcome(&err);
+                        record_ilm_latency(insights.as_ref(), "primary", reason.as_str(), primary_call_start.elapsed());
                         metrics.inc_ilm_timeouts();
                         metrics.set_ilm_enabled(false);
-                        let is_timeout = err
-                            .downcast_ref::<reqwest::Error>()
-                            .map(|e| e.is_timeout())
-                            .unwrap_or(false);
-                        let reason = if is_timeout { "timeout" } else { "request_failed" };
-                        metrics.set_ilm_disabled_reason(Some(reason.to_string()));
+                        metrics.set_ilm_disabled_reason(reason);
                         log_once(&mut last_error, format!(
                             "[local-ilm] request failed: {err}"
                         ));
@@ -450,6 +553,240 @@ async fn run_worker(
     }
 }
 
+/// Record one chat call's latency against the telemetry registry attached to
+/// `insights` (if any), tagged by reasoning phase and outcome. A no-op when
+/// no registry was attached via `InsightStore::with_telemetry`.
+fn record_ilm_latency(insights: &InsightStore, phase: &str, outcome: &str, elapsed: Duration) {
+    if let Some(telemetry) = insights.telemetry() {
+        telemetry.observe_ilm_chat_latency(phase, outcome, elapsed.as_millis() as u64);
+    }
+}
+
+/// Classify a failed `client.chat` call as `IlmStatus::AuthFailed`,
+/// `IlmStatus::Timeout`, or `IlmStatus::RequestFailed`.
+fn chat_failure_outcome(err: &anyhow::Error) -> IlmStatus {
+    if err.downcast_ref::<client::AuthError>().is_some() {
+        return IlmStatus::AuthFailed;
+    }
+    let is_timeout = err
+        .downcast_ref::<reqwest::Error>()
+        .map(|e| e.is_timeout())
+        .unwrap_or(false);
+    if is_timeout {
+        IlmStatus::Timeout
+    } else {
+        IlmStatus::RequestFailed
+    }
+}
+
+/// Call `client.chat` with bounded retry: up to `cfg.retry_max` extra attempts,
+/// delay `retry_base_ms * 2^attempt` capped at `retry_max_delay_ms` plus uniform
+/// jitter in `[0, delay)` so concurrent instances don't resynchronize. `window_start`
+/// anchors the window's own deadline -- once the next backoff would push elapsed
+/// time past `cfg.window_seconds`, retrying is abandoned so the failure never
+/// bleeds into the following tick, and the caller's existing `last_insight`
+/// fallback takes over.
+async fn chat_with_retry(
+    client: &IlmClient,
+    messages: &[ChatMessage],
+    cfg: &ReasonerConfig,
+    window_start: Instant,
+) -> anyhow::Result<String> {
+    let window_budget = Duration::from_secs(cfg.window_seconds.max(1));
+    let mut attempt: u32 = 0;
+
+    loop {
+        match client.chat(messages).await {
+            Ok(response) => return Ok(response),
+            Err(err) => {
+                if attempt >= cfg.retry_max {
+                    return Err(err);
+                }
+
+                let delay = backoff_with_jitter(attempt, cfg.retry_base_ms, cfg.retry_max_delay_ms);
+                let remaining = window_budget.saturating_sub(window_start.elapsed());
+                if delay >= remaining {
+                    debug!(
+                        "[local-ilm] chat attempt {} failed ({err}); backoff {:?} would exceed remaining window budget {:?}, giving up",
+                        attempt + 1,
+                        delay,
+                        remaining
+                    );
+                    return Err(err);
+                }
+
+                debug!(
+                    "[local-ilm] chat attempt {} failed ({err}), retrying in {:?}",
+                    attempt + 1,
+                    delay
+                );
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+        }
+    }
+}
+
+/// Legacy fallback for endpoints `IlmClient::supports_native_tool_calls`
+/// reports (or is assumed, optimistically) not to support: re-query the
+/// model up to `cfg.max_tool_rounds` times, ReAct-style, by sniffing the
+/// first line of its response for `TOOL: <name> <pid>` instead of a
+/// structured `tool_calls` array. Each round, if the latest response
+/// requests a registered tool this way, run it and feed the (line-capped)
+/// output back in as a follow-up prompt. Stops as soon as a response stops
+/// requesting a tool, requests one `registry` doesn't know about, the round
+/// cap is hit, or the window's time budget is exhausted -- the same
+/// abandonment logic `chat_with_retry` uses, so a chatty model can't bleed
+/// rounds into the next tick.
+#[allow(clippy::too_many_arguments)]
+async fn run_tool_loop(
+    client: &IlmClient,
+    cfg: &ReasonerConfig,
+    registry: &registry::ToolRegistry,
+    system_prompt: &str,
+    telemetry_prompt: &str,
+    snippets: &str,
+    window_start: Instant,
+    insights: &InsightStore,
+    mut response: String,
+    tool_calls: &mut Vec<insights::ToolCallRecord>,
+) -> anyhow::Result<String> {
+    if !cfg.tools_enabled {
+        return Ok(response);
+    }
+
+    for _round in 0..cfg.max_tool_rounds {
+        let Some((tool_name, pid)) = detect_tool_request(&response) else {
+            break;
+        };
+
+        let window_budget = Duration::from_secs(cfg.window_seconds.max(1));
+        if window_start.elapsed() >= window_budget {
+            break;
+        }
+
+        let Some(tool_output) = registry.run(tool_name.as_str(), pid) else {
+            break;
+        };
+        tool_calls.push(insights::ToolCallRecord {
+            tool: tool_name.clone(),
+            pid,
+            output: tool_output.clone(),
+        });
+
+        let followup_prompt = build_followup_prompt(
+            telemetry_prompt,
+            snippets,
+            tool_name.as_str(),
+            pid,
+            &tool_output,
+            &response,
+        );
+        let followup_messages = vec![
+            ChatMessage::new("system", system_prompt.to_string()),
+            ChatMessage::new("user", followup_prompt),
+        ];
+
+        let followup_call_start = Instant::now();
+        match chat_with_retry(client, &followup_messages, cfg, window_start).await {
+            Ok(next_response) => {
+                record_ilm_latency(insights, "followup", "success", followup_call_start.elapsed());
+                response = next_response;
+            }
+            Err(err) => {
+                record_ilm_latency(
+                    insights,
+                    "followup",
+                    chat_failure_outcome(&err).as_str(),
+                    followup_call_start.elapsed(),
+                );
+                return Err(err);
+            }
+        }
+    }
+
+    Ok(response)
+}
+
+/// Native OpenAI-style investigation: advertise `registry`'s tools alongside
+/// `messages` via `IlmClient::chat_with_tools`, and for each `tool_calls`
+/// response, run the requested tools and feed their results back as
+/// `role: "tool"` messages keyed by `tool_call_id`, re-querying until the
+/// model commits to a plain-content insight. Mirrors `chat_with_retry`'s
+/// window-budget abandonment so a model that keeps chaining diagnostics
+/// can't bleed rounds into the next tick; unlike `chat_with_retry`,
+/// individual rounds here aren't retried on failure, to keep the
+/// investigation's own latency bounded.
+async fn run_native_investigation(
+    client: &IlmClient,
+    cfg: &ReasonerConfig,
+    registry: &registry::ToolRegistry,
+    messages: Vec<ChatMessage>,
+    window_start: Instant,
+    tool_calls: &mut Vec<insights::ToolCallRecord>,
+) -> anyhow::Result<String> {
+    let mut conversation = messages;
+    let tools = registry.to_openai_tools();
+    let window_budget = Duration::from_secs(cfg.window_seconds.max(1));
+
+    for _round in 0..cfg.max_tool_rounds.max(1) {
+        if window_start.elapsed() >= window_budget {
+            break;
+        }
+
+        match client.chat_with_tools(&conversation, &tools).await? {
+            client::ChatOutcome::Message(content) => return Ok(content),
+            client::ChatOutcome::ToolCalls(calls) => {
+                conversation.push(ChatMessage::assistant_tool_calls(calls.clone()));
+                for call in calls {
+                    let pid = parse_tool_call_pid(&call.function.arguments);
+                    let output = match pid {
+                        Some(pid) => registry
+                            .run(&call.function.name, pid)
+                            .unwrap_or_else(|| format!("unknown tool: {}", call.function.name)),
+                        None => format!(
+                            "malformed arguments for {}: {}",
+                            call.function.name, call.function.arguments
+                        ),
+                    };
+                    tool_calls.push(insights::ToolCallRecord {
+                        tool: call.function.name.clone(),
+                        pid: pid.unwrap_or(-1),
+                        output: output.clone(),
+                    });
+                    conversation.push(ChatMessage::tool_result(call.id, output));
+                }
+            }
+        }
+    }
+
+    // Round cap (or window budget) hit mid-investigation -- force a final
+    // answer from whatever tool context has been gathered so far instead of
+    // giving up outright.
+    client.chat(&conversation).await
+}
+
+/// Pull `pid` out of a tool call's JSON-encoded `arguments`, e.g. `{"pid":123}`.
+fn parse_tool_call_pid(arguments: &str) -> Option<i32> {
+    serde_json::from_str::<serde_json::Value>(arguments)
+        .ok()?
+        .get("pid")?
+        .as_i64()
+        .map(|pid| pid as i32)
+}
+
+/// `base_ms * 2^attempt` capped at `max_delay_ms`, plus uniform jitter in `[0, delay)`.
+fn backoff_with_jitter(attempt: u32, base_ms: u64, max_delay_ms: u64) -> Duration {
+    let exp = base_ms.saturating_mul(1u64 << attempt.min(32));
+    let delay_ms = exp.min(max_delay_ms);
+    let jitter_ms = if delay_ms == 0 {
+        0
+    } else {
+        rand::thread_rng().gen_range(0..delay_ms)
+    };
+    Duration::from_millis(delay_ms + jitter_ms)
+}
+
 fn summarize_window(events: &[ProcessEvent]) -> WindowSummary {
     let mut forks = 0usize;
     let mut execs = 0usize;
@@ -513,18 +850,27 @@ fn build_query_string(summary: &WindowSummary) -> String {
 }
 
 fn build_system_prompt() -> String {
-    r#"You are an SRE assistant. Reply with exactly one JSON object and nothing else. Do NOT output arrays, multiple objects, code fences, markdown, or explanatory text. The object must contain the keys "class", "confidence", "primary_process", "why", and "actions". Valid values:
-- "class": one of "fork_storm", "short_job_flood", "runaway_tree", "cpu_spin", "io_saturation", "oom_risk", "normal" (lowercase, underscores).
-- "confidence": number between 0 and 1 (e.g. 0.45).
-- "primary_process": quoted process name or null.
-- "why": short sentence (<=120 chars) that references the telemetry.
-- "actions": array of up to 3 actionable strings (empty array when none).
-Populate them with conclusions drawn from the provided telemetry and knowledge snippets. If a field is unknown, use a sensible null/empty value. Responses that are not a single JSON object will be rejected."#
-        .to_string()
+    let classes = client::INSIGHT_CLASSES
+        .iter()
+        .map(|class| format!("\"{class}\""))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(
+        "You are an SRE assistant. Reply with exactly one JSON object and nothing else. Do NOT output arrays, multiple objects, code fences, markdown, or explanatory text. The object must contain the keys \"class\", \"confidence\", \"primary_process\", \"why\", and \"actions\". Valid values:\n\
+         - \"class\": one of {classes} (lowercase, underscores).\n\
+         - \"confidence\": number between 0 and 1 (e.g. 0.45).\n\
+         - \"primary_process\": quoted process name or null.\n\
+         - \"why\": short sentence (<=120 chars) that references the telemetry.\n\
+         - \"actions\": array of up to 3 actionable strings (empty array when none).\n\
+         Populate them with conclusions drawn from the provided telemetry and knowledge snippets. If a field is unknown, use a sensible null/empty value. Responses that are not a single JSON object will be rejected."
+    )
 }
 
 fn build_user_prompt(telemetry: &str, snippets: &str) -> String {
-    let schema = "{class:fork_storm|short_job_flood|runaway_tree|cpu_spin|io_saturation|oom_risk|normal,confidence:0-1,primary_process?:str/null,why<=120,actions<=3}";
+    let classes = client::INSIGHT_CLASSES.join("|");
+    let schema = format!(
+        "{{class:{classes},confidence:0-1,primary_process?:str/null,why<=120,actions<=3}}"
+    );
     let kb = if snippets.is_empty() {
         "kb:none".to_string()
     } else {
@@ -536,8 +882,9 @@ fn build_user_prompt(telemetry: &str, snippets: &str) -> String {
 }
 
 fn build_fix_prompt(error: &str, previous_response: &str) -> String {
+    let classes = client::INSIGHT_CLASSES.join("|");
     format!(
-        "Your previous reply was rejected because: {error}.\nPrevious reply:\n{previous_response}\n\nReturn a corrected insight as ONE JSON object with no prefix text. Do not start with words like Response, Schema, or ```.\nUse this exact structure (replace tokens with real values and keep lowercase class names):\n{{\"class\":\"fork_storm|short_job_flood|runaway_tree|cpu_spin|io_saturation|oom_risk|normal\",\"confidence\":0.0-1.0,\"primary_process\":null|\"process_name\",\"why\":\"short sentence <=120 chars\",\"actions\":[\"action 1\",\"action 2\"]}}\nRules:\n- class must be one of the allowed strings (lowercase, underscores)\n- confidence must be a numeric literal between 0 and 1\n- primary_process is either null or a quoted process name\n- why must be a non-empty sentence referencing telemetry (<=120 chars)\n- actions is an array with up to 3 quoted actions (use [] if none)\n- Do NOT add any other keys; only class/confidence/primary_process/why/actions are allowed\n- Every string must be plain text (no placeholders like ACTION_VALUES or WHY_TEXT)\nReply with ONLY the JSON object."
+        "Your previous reply was rejected because: {error}.\nPrevious reply:\n{previous_response}\n\nReturn a corrected insight as ONE JSON object with no prefix text. Do not start with words like Response, Schema, or ```.\nUse this exact structure (replace tokens with real values and keep lowercase class names):\n{{\"class\":\"{classes}\",\"confidence\":0.0-1.0,\"primary_process\":null|\"process_name\",\"why\":\"short sentence <=120 chars\",\"actions\":[\"action 1\",\"action 2\"]}}\nRules:\n- class must be one of the allowed strings (lowercase, underscores)\n- confidence must be a numeric literal between 0 and 1\n- primary_process is either null or a quoted process name\n- why must be a non-empty sentence referencing telemetry (<=120 chars)\n- actions is an array with up to 3 quoted actions (use [] if none)\n- Do NOT add any other keys; only class/confidence/primary_process/why/actions are allowed\n- Every string must be plain text (no placeholders like ACTION_VALUES or WHY_TEXT)\nReply with ONLY the JSON object."
     )
 }
 
@@ -589,27 +936,6 @@ fn detect_tool_request(response: &str) -> Option<(String, i32)> {
     Some((tool, pid))
 }
 
-fn execute_tool(tool: &str, pid: i32) -> Option<String> {
-    use tools::*;
-    match tool {
-        "ps_tree" => Some(match ps_tree(pid) {
-            Ok(output) => trim_tool_output(output),
-            Err(err) => format_tool_error(tool, err),
-        }),
-        "proc_status" => Some(match proc_status(pid) {
-            Ok(output) => trim_tool_output(output),
-            Err(err) => format_tool_error(tool, err),
-        }),
-        "cgroup_cpu" => Some(match cgroup_cpu(pid) {
-            Ok(output) => trim_tool_output(output),
-            Err(err) => format_tool_error(tool, err),
-        }),
-        "open_fds" => Some(format_count("open_fds", open_fds(pid))),
-        "net_conns" => Some(format_count("net_conns", net_conns(pid))),
-        _ => None,
-    }
-}
-
 fn trim_tool_output(output: String) -> String {
     let mut lines: Vec<&str> = output.lines().collect();
     if lines.len() > MAX_TOOL_LINES {
@@ -640,11 +966,48 @@ fn build_followup_prompt(
     )
 }
 
+/// Build the audit trail to persist alongside an insight, per `cfg.audit_level`:
+/// `Off` records nothing, `DecisionsOnly` keeps the tool calls made and
+/// whether a fix-up retry occurred, and `FullTranscript` additionally keeps
+/// the exact prompts, KB snippets, and raw model response, so a kill (or
+/// throttle) proposed from this insight can be fully reconstructed later.
+#[allow(clippy::too_many_arguments)]
+fn build_transcript(
+    cfg: &ReasonerConfig,
+    system_prompt: &str,
+    telemetry_prompt: &str,
+    kb_snippets: &[String],
+    raw_response: &str,
+    tool_calls: &[insights::ToolCallRecord],
+    fix_retries: u32,
+) -> Option<insights::InsightTranscript> {
+    match cfg.audit_level {
+        insights::AuditLevel::Off => None,
+        insights::AuditLevel::DecisionsOnly => Some(insights::InsightTranscript {
+            fix_retries,
+            tool_calls: tool_calls.to_vec(),
+            full: None,
+        }),
+        insights::AuditLevel::FullTranscript => Some(insights::InsightTranscript {
+            fix_retries,
+            tool_calls: tool_calls.to_vec(),
+            full: Some(insights::FullTranscript {
+                system_prompt: system_prompt.to_string(),
+                telemetry_prompt: telemetry_prompt.to_string(),
+                kb_snippets: kb_snippets.to_vec(),
+                raw_response: raw_response.to_string(),
+            }),
+        }),
+    }
+}
+
 fn emit_insight(
     insight: &Insight,
     metrics: &Metrics,
     store: &InsightStore,
     enforcement: &Option<Arc<crate::enforcement::EnforcementQueue>>,
+    transcript: Option<insights::InsightTranscript>,
+    auto_approve: bool,
 ) {
     let class = insight.class.as_str();
     info!(
@@ -655,22 +1018,17 @@ fn emit_insight(
     if insight.class.triggers_alert() {
         metrics.inc_alerts_emitted();
     }
-    store.record(insight.clone());
+    store.record_with_transcript(insight.clone(), transcript);
 
     if let Some(queue) = enforcement {
         for action_str in &insight.actions {
-            if let Some(pid) = parse_kill_action(action_str) {
+            if let Some(action) = parse_enforcement_action(action_str) {
                 let queue_clone = queue.clone();
                 let reason = insight.why.clone();
                 let confidence = insight.confidence;
                 tokio::spawn(async move {
                     if let Err(e) = queue_clone
-                        .propose(
-                            crate::enforcement::ActionType::KillProcess { pid, signal: 9 },
-                            reason,
-                            "llm".to_string(),
-                            Some(confidence),
-                        )
+                        .propose_auto(action, reason, "llm".to_string(), Some(confidence), auto_approve)
                         .await
                     {
                         log::warn!("[enforcement] rejected proposal: {}", e);
@@ -681,12 +1039,43 @@ fn emit_insight(
     }
 }
 
-fn parse_kill_action(action: &str) -> Option<u32> {
+/// Parse one of the textual actions the model suggests in `Insight::actions`
+/// (e.g. `"kill 123"`, `"renice 10 123"`, `"ionice -c3 123"`,
+/// `"throttle 123 20"`) into a typed [`crate::enforcement::ActionType`], or
+/// `None` if `action` isn't a form we recognize. Gives the reasoner a
+/// graduated response ladder -- `cpu_spin`/`io_saturation` insights can ask
+/// for a soft ionice/cgroup throttle instead of only ever killing.
+fn parse_enforcement_action(action: &str) -> Option<crate::enforcement::ActionType> {
     let parts: Vec<&str> = action.split_whitespace().collect();
-    if parts.first() == Some(&"kill") || parts.first() == Some(&"Kill") {
-        parts.last()?.parse().ok()
-    } else {
-        None
+    match parts.first()?.to_lowercase().as_str() {
+        "kill" => {
+            let pid = parts.get(1)?.parse().ok()?;
+            Some(crate::enforcement::ActionType::KillProcess { pid, signal: 9 })
+        }
+        "renice" => {
+            let nice = parts.get(1)?.parse().ok()?;
+            let pid = parts.get(2)?.parse().ok()?;
+            Some(crate::enforcement::ActionType::Renice { pid, nice })
+        }
+        "ionice" => {
+            let class = match *parts.get(1)? {
+                "-c1" => crate::enforcement::IoNiceClass::RealTime,
+                "-c2" => crate::enforcement::IoNiceClass::BestEffort,
+                "-c3" => crate::enforcement::IoNiceClass::Idle,
+                _ => return None,
+            };
+            let pid = parts.get(2)?.parse().ok()?;
+            Some(crate::enforcement::ActionType::IoNice { pid, class })
+        }
+        "throttle" | "cgroup_throttle" => {
+            let pid = parts.get(1)?.parse().ok()?;
+            let cpu_quota_percent = parts.get(2)?.parse().ok()?;
+            Some(crate::enforcement::ActionType::CgroupThrottle {
+                pid,
+                cpu_quota_percent,
+            })
+        }
+        _ => None,
     }
 }
 
@@ -820,4 +1209,428 @@ mod tests {
         assert_eq!(metrics.ilm_schema_errors(), 0);
         assert_eq!(metrics.ilm_timeouts(), 0);
     }
+
+    #[test]
+    fn backoff_doubles_up_to_ceiling() {
+        let d0 = backoff_with_jitter(0, 100, 300);
+        let d1 = backoff_with_jitter(1, 100, 300);
+        let d2 = backoff_with_jitter(2, 100, 300);
+        assert!(d0 >= Duration::from_millis(100) && d0 < Duration::from_millis(200));
+        assert!(d1 >= Duration::from_millis(200) && d1 < Duration::from_millis(400));
+        assert!(d2 >= Duration::from_millis(300) && d2 < Duration::from_millis(600));
+    }
+
+    #[tokio::test]
+    async fn chat_with_retry_recovers_after_transient_failures() {
+        let failures = Arc::new(std::sync::atomic::AtomicU32::new(0));
+
+        async fn flaky_handler(
+            axum::extract::State(failures): axum::extract::State<Arc<std::sync::atomic::AtomicU32>>,
+        ) -> axum::response::Response {
+            if failures.fetch_add(1, std::sync::atomic::Ordering::SeqCst) < 2 {
+                axum::http::StatusCode::SERVICE_UNAVAILABLE.into_response()
+            } else {
+                Json(json!({
+                    "choices": [{"message": {"content": "ok"}}]
+                }))
+                .into_response()
+            }
+        }
+
+        let app = Router::new()
+            .route("/v1/chat/completions", post(flaky_handler))
+            .with_state(Arc::clone(&failures));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let _ = axum::serve(listener, app.into_make_service()).await;
+        });
+
+        let client =
+            client::IlmClient::new(&format!("http://{addr}/v1/chat/completions"), Duration::from_secs(5))
+                .unwrap();
+        let cfg = ReasonerConfig {
+            window_seconds: 10,
+            retry_max: 5,
+            retry_base_ms: 5,
+            retry_max_delay_ms: 50,
+            ..ReasonerConfig::default()
+        };
+        let messages = vec![ChatMessage::new("user", "hi")];
+
+        let response = chat_with_retry(&client, &messages, &cfg, Instant::now())
+            .await
+            .expect("should eventually succeed");
+        assert_eq!(response, "ok");
+        assert_eq!(failures.load(std::sync::atomic::Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn chat_with_retry_abandons_once_window_budget_is_spent() {
+        async fn always_fails() -> axum::http::StatusCode {
+            axum::http::StatusCode::SERVICE_UNAVAILABLE
+        }
+
+        let app = Router::new().route("/v1/chat/completions", post(always_fails));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let _ = axum::serve(listener, app.into_make_service()).await;
+        });
+
+        let client =
+            client::IlmClient::new(&format!("http://{addr}/v1/chat/completions"), Duration::from_secs(5))
+                .unwrap();
+        let cfg = ReasonerConfig {
+            window_seconds: 1,
+            retry_max: 100,
+            retry_base_ms: 500,
+            retry_max_delay_ms: 500,
+            ..ReasonerConfig::default()
+        };
+        let messages = vec![ChatMessage::new("user", "hi")];
+
+        let window_start = Instant::now() - Duration::from_millis(900);
+        let result = chat_with_retry(&client, &messages, &cfg, window_start).await;
+        assert!(result.is_err(), "should give up once the window budget is exhausted");
+    }
+
+    struct EchoToolInspector;
+
+    impl registry::ToolInspector for EchoToolInspector {
+        fn name(&self) -> &'static str {
+            "echo"
+        }
+
+        fn arg_schema(&self) -> &'static str {
+            "pid: i32"
+        }
+
+        fn run(&self, pid: i32) -> String {
+            format!("echo:{pid}")
+        }
+    }
+
+    #[tokio::test]
+    async fn run_tool_loop_requeries_until_a_final_answer() {
+        let calls = Arc::new(std::sync::atomic::AtomicU32::new(0));
+
+        async fn handler(
+            axum::extract::State(calls): axum::extract::State<Arc<std::sync::atomic::AtomicU32>>,
+        ) -> axum::response::Response {
+            let content = if calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst) == 0 {
+                "TOOL: echo 123"
+            } else {
+                "final answer"
+            };
+            Json(json!({"choices": [{"message": {"content": content}}]})).into_response()
+        }
+
+        let app = Router::new()
+            .route("/v1/chat/completions", post(handler))
+            .with_state(Arc::clone(&calls));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let _ = axum::serve(listener, app.into_make_service()).await;
+        });
+
+        let client =
+            client::IlmClient::new(&format!("http://{addr}/v1/chat/completions"), Duration::from_secs(5))
+                .unwrap();
+        let cfg = ReasonerConfig {
+            tools_enabled: true,
+            max_tool_rounds: 3,
+            ..ReasonerConfig::default()
+        };
+        let mut tool_registry = registry::ToolRegistry::new();
+        tool_registry.register(Box::new(EchoToolInspector));
+        let insights = InsightStore::new(4, None);
+
+        let mut tool_calls = Vec::new();
+        let response = run_tool_loop(
+            &client,
+            &cfg,
+            &tool_registry,
+            "system",
+            "telemetry",
+            "",
+            Instant::now(),
+            &insights,
+            "TOOL: echo 123".to_string(),
+            &mut tool_calls,
+        )
+        .await
+        .expect("loop should complete");
+
+        assert_eq!(response, "final answer");
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn run_tool_loop_stops_at_the_round_cap() {
+        async fn always_requests_tool() -> axum::response::Response {
+            Json(json!({"choices": [{"message": {"content": "TOOL: echo 123"}}]})).into_response()
+        }
+
+        let app = Router::new().route("/v1/chat/completions", post(always_requests_tool));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let _ = axum::serve(listener, app.into_make_service()).await;
+        });
+
+        let client =
+            client::IlmClient::new(&format!("http://{addr}/v1/chat/completions"), Duration::from_secs(5))
+                .unwrap();
+        let cfg = ReasonerConfig {
+            tools_enabled: true,
+            max_tool_rounds: 2,
+            ..ReasonerConfig::default()
+        };
+        let mut tool_registry = registry::ToolRegistry::new();
+        tool_registry.register(Box::new(EchoToolInspector));
+        let insights = InsightStore::new(4, None);
+
+        let mut tool_calls = Vec::new();
+        let response = run_tool_loop(
+            &client,
+            &cfg,
+            &tool_registry,
+            "system",
+            "telemetry",
+            "",
+            Instant::now(),
+            &insights,
+            "TOOL: echo 123".to_string(),
+            &mut tool_calls,
+        )
+        .await
+        .expect("loop should stop instead of erroring once the round cap is hit");
+
+        assert_eq!(response, "TOOL: echo 123");
+    }
+
+    #[tokio::test]
+    async fn run_native_investigation_runs_a_tool_then_returns_the_final_message() {
+        let calls = Arc::new(std::sync::atomic::AtomicU32::new(0));
+
+        async fn handler(
+            axum::extract::State(calls): axum::extract::State<Arc<std::sync::atomic::AtomicU32>>,
+        ) -> axum::response::Response {
+            if calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst) == 0 {
+                Json(json!({
+                    "choices": [{
+                        "message": {
+                            "role": "assistant",
+                            "content": null,
+                            "tool_calls": [{
+                                "id": "call_1",
+                                "type": "function",
+                                "function": {"name": "echo", "arguments": "{\"pid\":123}"}
+                            }]
+                        }
+                    }]
+                }))
+                .into_response()
+            } else {
+                Json(json!({"choices": [{"message": {"content": "final answer"}}]})).into_response()
+            }
+        }
+
+        let app = Router::new()
+            .route("/v1/chat/completions", post(handler))
+            .with_state(Arc::clone(&calls));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let _ = axum::serve(listener, app.into_make_service()).await;
+        });
+
+        let client =
+            client::IlmClient::new(&format!("http://{addr}/v1/chat/completions"), Duration::from_secs(5))
+                .unwrap();
+        let cfg = ReasonerConfig {
+            tools_enabled: true,
+            max_tool_rounds: 3,
+            ..ReasonerConfig::default()
+        };
+        let mut tool_registry = registry::ToolRegistry::new();
+        tool_registry.register(Box::new(EchoToolInspector));
+        let messages = vec![ChatMessage::new("user", "investigate")];
+
+        let mut tool_calls = Vec::new();
+        let response = run_native_investigation(
+            &client,
+            &cfg,
+            &tool_registry,
+            messages,
+            Instant::now(),
+            &mut tool_calls,
+        )
+        .await
+        .expect("investigation should complete");
+
+        assert_eq!(response, "final answer");
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn run_native_investigation_forces_a_final_answer_at_the_round_cap() {
+        async fn always_requests_a_tool() -> axum::response::Response {
+            Json(json!({
+                "choices": [{
+                    "message": {
+                        "role": "assistant",
+                        "content": null,
+                        "tool_calls": [{
+                            "id": "call_1",
+                            "type": "function",
+                            "function": {"name": "echo", "arguments": "{\"pid\":123}"}
+                        }]
+                    }
+                }]
+            }))
+            .into_response()
+        }
+
+        let app = Router::new().route("/v1/chat/completions", post(always_requests_a_tool));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let _ = axum::serve(listener, app.into_make_service()).await;
+        });
+
+        let client =
+            client::IlmClient::new(&format!("http://{addr}/v1/chat/completions"), Duration::from_secs(5))
+                .unwrap();
+        let cfg = ReasonerConfig {
+            tools_enabled: true,
+            max_tool_rounds: 2,
+            ..ReasonerConfig::default()
+        };
+        let mut tool_registry = registry::ToolRegistry::new();
+        tool_registry.register(Box::new(EchoToolInspector));
+        let messages = vec![ChatMessage::new("user", "investigate")];
+
+        // Past the round cap, `client.chat` (not `chat_with_tools`) forces a
+        // final answer; the mock always reports tool_calls, so `extract_message`
+        // fails on its missing `content` and the call surfaces that error.
+        let mut tool_calls = Vec::new();
+        let result =
+            run_native_investigation(&client, &cfg, &tool_registry, messages, Instant::now(), &mut tool_calls)
+                .await;
+        assert!(
+            result.is_err(),
+            "a server that never stops requesting tools should surface an error rather than loop forever"
+        );
+    }
+
+    #[test]
+    fn ilm_status_labels_are_stable_for_metrics() {
+        assert_eq!(IlmStatus::Healthy.as_str(), "healthy");
+        assert_eq!(IlmStatus::AuthFailed.as_str(), "auth_failed");
+        assert_eq!(IlmStatus::FallbackLastInsight.to_string(), "fallback_last_insight");
+    }
+
+    #[test]
+    fn parse_enforcement_action_covers_the_graduated_response_ladder() {
+        use crate::enforcement::{ActionType, IoNiceClass};
+
+        assert!(matches!(
+            parse_enforcement_action("kill 123"),
+            Some(ActionType::KillProcess { pid: 123, signal: 9 })
+        ));
+        assert!(matches!(
+            parse_enforcement_action("renice 10 123"),
+            Some(ActionType::Renice { pid: 123, nice: 10 })
+        ));
+        assert!(matches!(
+            parse_enforcement_action("ionice -c3 123"),
+            Some(ActionType::IoNice {
+                pid: 123,
+                class: IoNiceClass::Idle
+            })
+        ));
+        assert!(matches!(
+            parse_enforcement_action("throttle 123 20"),
+            Some(ActionType::CgroupThrottle {
+                pid: 123,
+                cpu_quota_percent: 20
+            })
+        ));
+        assert_eq!(parse_enforcement_action("nice weather today"), None);
+    }
+
+    #[tokio::test]
+    async fn emit_insight_auto_approves_only_when_the_circuit_breaker_allows_it() {
+        use crate::enforcement::{ActionStatus, EnforcementQueue};
+
+        async fn queue() -> (tempfile::TempDir, Arc<EnforcementQueue>) {
+            let dir = tempfile::tempdir().unwrap();
+            let queue = EnforcementQueue::new(dir.path().join("enforcement.db"), 300)
+                .await
+                .unwrap();
+            (dir, Arc::new(queue))
+        }
+
+        let insight = Insight {
+            class: schema::InsightClass::ForkStorm,
+            confidence: 0.9,
+            primary_process: Some(123),
+            why: "forks spiked".to_string(),
+            actions: vec!["kill 123".to_string()],
+        };
+        let metrics = Metrics::new();
+        let store = InsightStore::new(8, None);
+
+        let (_dir, enforcement) = queue().await;
+        emit_insight(&insight, &metrics, &store, &Some(Arc::clone(&enforcement)), None, true);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let actions = enforcement.get_all().await.unwrap();
+        assert_eq!(actions.len(), 1);
+        assert_eq!(actions[0].status, ActionStatus::Approved);
+
+        let (_dir2, enforcement2) = queue().await;
+        emit_insight(&insight, &metrics, &store, &Some(Arc::clone(&enforcement2)), None, false);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let actions = enforcement2.get_all().await.unwrap();
+        assert_eq!(actions.len(), 1);
+        assert_eq!(actions[0].status, ActionStatus::Pending);
+    }
+
+    #[test]
+    fn build_transcript_respects_the_configured_audit_level() {
+        let tool_calls = vec![insights::ToolCallRecord {
+            tool: "ps_tree".to_string(),
+            pid: 123,
+            output: "tree".to_string(),
+        }];
+
+        let off_cfg = ReasonerConfig {
+            audit_level: insights::AuditLevel::Off,
+            ..ReasonerConfig::default()
+        };
+        assert!(build_transcript(&off_cfg, "sys", "tel", &[], "raw", &tool_calls, 1).is_none());
+
+        let decisions_cfg = ReasonerConfig {
+            audit_level: insights::AuditLevel::DecisionsOnly,
+            ..ReasonerConfig::default()
+        };
+        let decisions = build_transcript(&decisions_cfg, "sys", "tel", &[], "raw", &tool_calls, 1)
+            .expect("decisions-only should still record a transcript");
+        assert_eq!(decisions.fix_retries, 1);
+        assert_eq!(decisions.tool_calls.len(), 1);
+        assert!(decisions.full.is_none());
+
+        let full_cfg = ReasonerConfig {
+            audit_level: insights::AuditLevel::FullTranscript,
+            ..ReasonerConfig::default()
+        };
+        let full = build_transcript(&full_cfg, "sys", "tel", &[], "raw", &tool_calls, 0)
+            .expect("full-transcript should record a transcript");
+        let details = full.full.expect("full-transcript should include prompt bodies");
+        assert_eq!(details.system_prompt, "sys");
+        assert_eq!(details.raw_response, "raw");
+    }
 }
@@ -0,0 +1,247 @@
+//! Pluggable tool registry backing the ReAct-style tool-calling loop in
+//! `run_worker`. Each inspector owns its name, a one-line argument schema for
+//! the system prompt, and how to run itself against a PID, so new inspectors
+//! (open FDs, cgroup membership, per-thread CPU, recent exec argv, ...) can
+//! be registered without touching the worker's loop.
+
+use std::collections::HashMap;
+
+use serde_json::{Value, json};
+
+/// One tool the model can request, either via the legacy `TOOL: <name> <pid>`
+/// text line or the native OpenAI-style `tool_calls` protocol.
+pub trait ToolInspector: Send + Sync {
+    /// Stable name the model requests this tool by, e.g. `"ps_tree"`.
+    fn name(&self) -> &'static str;
+    /// One-line argument schema surfaced in the legacy text-protocol system prompt.
+    fn arg_schema(&self) -> &'static str;
+    /// One-line description surfaced in the native `tools` request array.
+    fn description(&self) -> &'static str {
+        ""
+    }
+    /// Run the tool against `pid`, returning already-formatted, already
+    /// line-capped output (or an error string) ready to splice into a
+    /// follow-up prompt.
+    fn run(&self, pid: i32) -> String;
+
+    /// JSON-schema parameter block for the native `tools` request array.
+    /// Every built-in inspector takes a single `pid` integer, so this has a
+    /// shared default; override it if an inspector ever needs richer args.
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "pid": { "type": "integer", "description": "Process ID to inspect" }
+            },
+            "required": ["pid"]
+        })
+    }
+
+    /// This inspector's entry in the native, OpenAI-style `tools` request array.
+    fn to_openai_tool(&self) -> Value {
+        json!({
+            "type": "function",
+            "function": {
+                "name": self.name(),
+                "description": self.description(),
+                "parameters": self.parameters_schema()
+            }
+        })
+    }
+}
+
+/// Looks inspectors up by name so the worker doesn't need a hardcoded `match`.
+#[derive(Default)]
+pub struct ToolRegistry {
+    inspectors: HashMap<&'static str, Box<dyn ToolInspector>>,
+}
+
+impl ToolRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&mut self, inspector: Box<dyn ToolInspector>) -> &mut Self {
+        self.inspectors.insert(inspector.name(), inspector);
+        self
+    }
+
+    /// Names of every registered tool, sorted, for the system prompt.
+    pub fn names(&self) -> Vec<&'static str> {
+        let mut names: Vec<&'static str> = self.inspectors.keys().copied().collect();
+        names.sort_unstable();
+        names
+    }
+
+    pub fn arg_schema(&self, tool: &str) -> Option<&'static str> {
+        self.inspectors.get(tool).map(|inspector| inspector.arg_schema())
+    }
+
+    /// Run `tool` against `pid`; `None` if no inspector is registered under that name.
+    pub fn run(&self, tool: &str, pid: i32) -> Option<String> {
+        self.inspectors.get(tool).map(|inspector| inspector.run(pid))
+    }
+
+    /// Every registered inspector's entry for the native `tools` request array.
+    pub fn to_openai_tools(&self) -> Vec<Value> {
+        self.names()
+            .into_iter()
+            .filter_map(|name| self.inspectors.get(name))
+            .map(|inspector| inspector.to_openai_tool())
+            .collect()
+    }
+}
+
+macro_rules! capped_inspector {
+    ($struct_name:ident, $name:literal, $description:literal, $func:path) => {
+        pub struct $struct_name;
+
+        impl ToolInspector for $struct_name {
+            fn name(&self) -> &'static str {
+                $name
+            }
+
+            fn arg_schema(&self) -> &'static str {
+                "pid: i32"
+            }
+
+            fn description(&self) -> &'static str {
+                $description
+            }
+
+            fn run(&self, pid: i32) -> String {
+                match $func(pid) {
+                    Ok(output) => super::trim_tool_output(output),
+                    Err(err) => super::tools::format_tool_error($name, err),
+                }
+            }
+        }
+    };
+}
+
+capped_inspector!(
+    PsTreeInspector,
+    "ps_tree",
+    "List the process tree rooted at pid.",
+    super::tools::ps_tree
+);
+capped_inspector!(
+    ProcStatusInspector,
+    "proc_status",
+    "Read /proc/<pid>/status (state, memory, threads) for pid.",
+    super::tools::proc_status
+);
+capped_inspector!(
+    CgroupCpuInspector,
+    "cgroup_cpu",
+    "Read pid's cgroup CPU usage and throttling stats.",
+    super::tools::cgroup_cpu
+);
+
+pub struct OpenFdsInspector;
+
+impl ToolInspector for OpenFdsInspector {
+    fn name(&self) -> &'static str {
+        "open_fds"
+    }
+
+    fn arg_schema(&self) -> &'static str {
+        "pid: i32"
+    }
+
+    fn description(&self) -> &'static str {
+        "Count pid's open file descriptors."
+    }
+
+    fn run(&self, pid: i32) -> String {
+        super::tools::format_count("open_fds", super::tools::open_fds(pid))
+    }
+}
+
+pub struct NetConnsInspector;
+
+impl ToolInspector for NetConnsInspector {
+    fn name(&self) -> &'static str {
+        "net_conns"
+    }
+
+    fn arg_schema(&self) -> &'static str {
+        "pid: i32"
+    }
+
+    fn description(&self) -> &'static str {
+        "Count pid's open network connections."
+    }
+
+    fn run(&self, pid: i32) -> String {
+        super::tools::format_count("net_conns", super::tools::net_conns(pid))
+    }
+}
+
+/// The inspector set that shipped as `execute_tool`'s hardcoded match before
+/// this registry existed.
+pub fn default_registry() -> ToolRegistry {
+    let mut registry = ToolRegistry::new();
+    registry
+        .register(Box::new(PsTreeInspector))
+        .register(Box::new(ProcStatusInspector))
+        .register(Box::new(CgroupCpuInspector))
+        .register(Box::new(OpenFdsInspector))
+        .register(Box::new(NetConnsInspector));
+    registry
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct EchoInspector;
+
+    impl ToolInspector for EchoInspector {
+        fn name(&self) -> &'static str {
+            "echo"
+        }
+
+        fn arg_schema(&self) -> &'static str {
+            "pid: i32"
+        }
+
+        fn run(&self, pid: i32) -> String {
+            format!("echo:{pid}")
+        }
+    }
+
+    #[test]
+    fn custom_inspectors_register_without_touching_the_worker() {
+        let mut registry = ToolRegistry::new();
+        registry.register(Box::new(EchoInspector));
+
+        assert_eq!(registry.run("echo", 42), Some("echo:42".to_string()));
+        assert_eq!(registry.arg_schema("echo"), Some("pid: i32"));
+        assert_eq!(registry.run("unknown_tool", 42), None);
+    }
+
+    #[test]
+    fn to_openai_tool_describes_a_pid_taking_function() {
+        let tool = PsTreeInspector.to_openai_tool();
+        assert_eq!(tool["type"], "function");
+        assert_eq!(tool["function"]["name"], "ps_tree");
+        assert_eq!(tool["function"]["parameters"]["required"][0], "pid");
+    }
+
+    #[test]
+    fn default_registry_tools_array_covers_every_built_in() {
+        let tools = default_registry().to_openai_tools();
+        assert_eq!(tools.len(), 5);
+        assert!(tools.iter().all(|t| t["type"] == "function"));
+    }
+
+    #[test]
+    fn default_registry_exposes_the_built_in_tools() {
+        let registry = default_registry();
+        assert_eq!(
+            registry.names(),
+            vec!["cgroup_cpu", "net_conns", "open_fds", "proc_status", "ps_tree"]
+        );
+    }
+}
@@ -0,0 +1,12 @@
+//! Event-handler implementations that react to process events / snapshots.
+//!
+//! NOTE: `local_ilm` still depends on a `Handler` trait, a `ProcessEvent`
+//! type, and a `context::ContextStore` type that are referenced throughout
+//! this tree (`use super::Handler;`, `crate::{ProcessEvent, context::...}`)
+//! but were never defined anywhere in this snapshot -- a pre-existing gap
+//! that predates this module's own wiring, same as `enforcement::safety`'s
+//! missing `SafetyGuard` file. Declaring this module makes `local_ilm`
+//! reachable and compiled again; it does not by itself resolve those
+//! still-missing definitions.
+
+pub mod local_ilm;
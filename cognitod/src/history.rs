@@ -0,0 +1,134 @@
+//! Bounded history ring buffer for SSE backfill
+//!
+//! `/stream?since=15m` and `/alerts?since=1h` need to replay recent events
+//! before transitioning to live streaming. [`HistoryRing`] is the shared
+//! building block: a capacity-bounded buffer of timestamped records that a
+//! stream/alerts handler pushes into as events are emitted, and queries with
+//! [`HistoryRing::since`] to serve the backfill phase, which the handler
+//! frames with `event: history-start` / `event: history-end` SSE markers.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Anything replayable from history needs a timestamp to filter by.
+pub trait Timestamped {
+    fn timestamp_secs(&self) -> u64;
+}
+
+pub struct HistoryRing<T> {
+    capacity: usize,
+    entries: Mutex<VecDeque<T>>,
+}
+
+impl<T: Clone + Timestamped> HistoryRing<T> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    pub fn push(&self, entry: T) {
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() == self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back(entry);
+    }
+
+    /// Every stored record at or after `cutoff_secs` (unix epoch seconds), oldest first.
+    pub fn since(&self, cutoff_secs: u64) -> Vec<T> {
+        self.entries
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|e| e.timestamp_secs() >= cutoff_secs)
+            .cloned()
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Parse a relative duration like `"15m"`, `"1h"`, `"30s"`, `"2d"`, or a bare
+/// number of seconds (`"90"`), as used by the `?since=` query parameter.
+pub fn parse_since(raw: &str) -> Option<Duration> {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return None;
+    }
+    let (num_str, multiplier) = match raw.split_at(raw.len() - 1) {
+        (value, "s") => (value, 1u64),
+        (value, "m") => (value, 60),
+        (value, "h") => (value, 3600),
+        (value, "d") => (value, 86_400),
+        _ => (raw, 1),
+    };
+    let n: u64 = num_str.parse().ok()?;
+    Some(Duration::from_secs(n * multiplier))
+}
+
+/// Convert a `since` duration into a cutoff unix-epoch-seconds timestamp relative to `now_secs`.
+pub fn cutoff_from_since(since: Duration, now_secs: u64) -> u64 {
+    now_secs.saturating_sub(since.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone)]
+    struct Event(u64);
+
+    impl Timestamped for Event {
+        fn timestamp_secs(&self) -> u64 {
+            self.0
+        }
+    }
+
+    #[test]
+    fn since_filters_by_cutoff() {
+        let ring = HistoryRing::new(10);
+        ring.push(Event(100));
+        ring.push(Event(200));
+        ring.push(Event(300));
+
+        let recent = ring.since(200);
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent[0].0, 200);
+        assert_eq!(recent[1].0, 300);
+    }
+
+    #[test]
+    fn ring_evicts_oldest_past_capacity() {
+        let ring = HistoryRing::new(2);
+        ring.push(Event(1));
+        ring.push(Event(2));
+        ring.push(Event(3));
+
+        assert_eq!(ring.len(), 2);
+        assert_eq!(ring.since(0).iter().map(|e| e.0).collect::<Vec<_>>(), vec![2, 3]);
+    }
+
+    #[test]
+    fn parse_since_handles_units() {
+        assert_eq!(parse_since("15m"), Some(Duration::from_secs(900)));
+        assert_eq!(parse_since("1h"), Some(Duration::from_secs(3600)));
+        assert_eq!(parse_since("90"), Some(Duration::from_secs(90)));
+        assert_eq!(parse_since("2d"), Some(Duration::from_secs(172_800)));
+        assert_eq!(parse_since(""), None);
+    }
+
+    #[test]
+    fn cutoff_from_since_subtracts_from_now() {
+        assert_eq!(cutoff_from_since(Duration::from_secs(60), 1_000), 940);
+        assert_eq!(cutoff_from_since(Duration::from_secs(2_000), 1_000), 0);
+    }
+}
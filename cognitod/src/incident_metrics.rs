@@ -0,0 +1,245 @@
+//! Prometheus metrics for incident and enforcement health.
+//!
+//! Complements `telemetry::TelemetryRegistry`'s push-tracked counters with
+//! data pulled live from `IncidentStore` and `EnforcementQueue`: incident
+//! totals and circuit-breaker triggers are queried from SQLite rather than
+//! accumulated in memory, so a scrape survives a daemon restart, and the
+//! enforcement queue's per-status counts only make sense as a live
+//! snapshot since actions migrate between statuses (pending -> approved ->
+//! executing -> executed) over their lifetime.
+
+use std::fmt::Write as _;
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::response::IntoResponse;
+
+use crate::enforcement::EnforcementQueue;
+use crate::incidents::IncidentStore;
+
+/// Upper bounds (inclusive) of each recovery-time bucket, in milliseconds.
+const RECOVERY_TIME_BUCKETS_MS: &[u64] = &[
+    100, 250, 500, 1_000, 2_500, 5_000, 10_000, 30_000, 60_000, 300_000,
+];
+
+/// Shared state for [`incident_metrics_handler`].
+#[derive(Clone)]
+pub struct IncidentMetricsState {
+    pub incidents: Arc<IncidentStore>,
+    pub enforcement: Arc<EnforcementQueue>,
+}
+
+/// Render incident and enforcement health in Prometheus text exposition format.
+pub async fn render(state: &IncidentMetricsState) -> anyhow::Result<String> {
+    let mut out = String::new();
+
+    let counts_by_event_type = state.incidents.counts_by_event_type().await?;
+    writeln!(
+        out,
+        "# HELP linnix_incidents_total Incidents recorded, by event type\n\
+         # TYPE linnix_incidents_total counter"
+    )
+    .ok();
+    for (event_type, count) in &counts_by_event_type {
+        writeln!(
+            out,
+            "linnix_incidents_total{{event_type=\"{event_type}\"}} {count}"
+        )
+        .ok();
+    }
+
+    let stats = state.incidents.stats().await?;
+    writeln!(
+        out,
+        "# HELP linnix_circuit_breaker_triggers_total Circuit breaker triggers\n\
+         # TYPE linnix_circuit_breaker_triggers_total counter"
+    )
+    .ok();
+    writeln!(
+        out,
+        "linnix_circuit_breaker_triggers_total {}",
+        stats.circuit_breaker_triggers
+    )
+    .ok();
+
+    let recovery_values = state.incidents.recovery_time_ms_values().await?;
+    writeln!(
+        out,
+        "# HELP linnix_incident_recovery_time_ms Incident recovery time\n\
+         # TYPE linnix_incident_recovery_time_ms histogram"
+    )
+    .ok();
+    let mut bucket_counts = vec![0u64; RECOVERY_TIME_BUCKETS_MS.len() + 1];
+    let mut sum_ms = 0u64;
+    for &value in &recovery_values {
+        let bucket = RECOVERY_TIME_BUCKETS_MS
+            .iter()
+            .position(|&le| value <= le)
+            .unwrap_or(RECOVERY_TIME_BUCKETS_MS.len());
+        bucket_counts[bucket] += 1;
+        sum_ms += value;
+    }
+    let mut cumulative = 0u64;
+    for (idx, &le) in RECOVERY_TIME_BUCKETS_MS.iter().enumerate() {
+        cumulative += bucket_counts[idx];
+        writeln!(
+            out,
+            "linnix_incident_recovery_time_ms_bucket{{le=\"{le}\"}} {cumulative}"
+        )
+        .ok();
+    }
+    cumulative += bucket_counts[RECOVERY_TIME_BUCKETS_MS.len()];
+    writeln!(
+        out,
+        "linnix_incident_recovery_time_ms_bucket{{le=\"+Inf\"}} {cumulative}"
+    )
+    .ok();
+    writeln!(out, "linnix_incident_recovery_time_ms_sum {sum_ms}").ok();
+    writeln!(
+        out,
+        "linnix_incident_recovery_time_ms_count {}",
+        recovery_values.len()
+    )
+    .ok();
+
+    let enforcement_counts = state
+        .enforcement
+        .status_counts()
+        .await
+        .map_err(anyhow::Error::msg)?;
+    writeln!(
+        out,
+        "# HELP linnix_enforcement_actions Enforcement actions currently in each status\n\
+         # TYPE linnix_enforcement_actions gauge"
+    )
+    .ok();
+    writeln!(
+        out,
+        "linnix_enforcement_actions{{status=\"pending\"}} {}",
+        enforcement_counts.pending
+    )
+    .ok();
+    writeln!(
+        out,
+        "linnix_enforcement_actions{{status=\"approved\"}} {}",
+        enforcement_counts.approved
+    )
+    .ok();
+    writeln!(
+        out,
+        "linnix_enforcement_actions{{status=\"executing\"}} {}",
+        enforcement_counts.executing
+    )
+    .ok();
+    writeln!(
+        out,
+        "linnix_enforcement_actions{{status=\"rejected\"}} {}",
+        enforcement_counts.rejected
+    )
+    .ok();
+    writeln!(
+        out,
+        "linnix_enforcement_actions{{status=\"expired\"}} {}",
+        enforcement_counts.expired
+    )
+    .ok();
+    writeln!(
+        out,
+        "linnix_enforcement_actions{{status=\"executed\"}} {}",
+        enforcement_counts.executed
+    )
+    .ok();
+
+    Ok(out)
+}
+
+/// Serve incident/enforcement health in Prometheus text exposition format,
+/// alongside `telemetry::metrics_handler` at whatever path the binary mounts it.
+pub async fn incident_metrics_handler(State(state): State<IncidentMetricsState>) -> impl IntoResponse {
+    match render(&state).await {
+        Ok(body) => (
+            [(
+                axum::http::header::CONTENT_TYPE,
+                "text/plain; version=0.0.4",
+            )],
+            body,
+        )
+            .into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "failed to render incident metrics");
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enforcement::ActionType;
+    use tempfile::NamedTempFile;
+
+    async fn test_state() -> (NamedTempFile, tempfile::TempDir, IncidentMetricsState) {
+        let incidents_file = NamedTempFile::new().unwrap();
+        let incidents = Arc::new(IncidentStore::new(incidents_file.path()).await.unwrap());
+        let enforcement_dir = tempfile::tempdir().unwrap();
+        let enforcement = Arc::new(
+            EnforcementQueue::new(enforcement_dir.path().join("enforcement.db"), 300)
+                .await
+                .unwrap(),
+        );
+        (
+            incidents_file,
+            enforcement_dir,
+            IncidentMetricsState {
+                incidents,
+                enforcement,
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn renders_incident_and_enforcement_series() {
+        let (_f, _d, state) = test_state().await;
+
+        state
+            .incidents
+            .insert(&crate::incidents::Incident {
+                id: None,
+                timestamp: 1,
+                event_type: "circuit_breaker".to_string(),
+                psi_cpu: 90.0,
+                psi_memory: 10.0,
+                cpu_percent: 95.0,
+                load_avg: "1.0,1.0,1.0".to_string(),
+                action: "kill".to_string(),
+                target_pid: Some(42),
+                target_name: None,
+                system_snapshot: None,
+                llm_analysis: None,
+                llm_analyzed_at: None,
+                recovery_time_ms: Some(150),
+                psi_after: None,
+            })
+            .await
+            .unwrap();
+
+        state
+            .enforcement
+            .propose(
+                ActionType::KillProcess { pid: 42, signal: 9 },
+                "test".to_string(),
+                "test".to_string(),
+                None,
+            )
+            .await
+            .unwrap();
+
+        let text = render(&state).await.unwrap();
+        assert!(text.contains("linnix_incidents_total{event_type=\"circuit_breaker\"} 1"));
+        assert!(text.contains("linnix_circuit_breaker_triggers_total 1"));
+        assert!(text.contains("linnix_incident_recovery_time_ms_bucket{le=\"250\"} 1"));
+        assert!(text.contains("linnix_incident_recovery_time_ms_count 1"));
+        assert!(text.contains("linnix_enforcement_actions{status=\"pending\"} 1"));
+        assert!(text.contains("linnix_enforcement_actions{status=\"approved\"} 0"));
+    }
+}
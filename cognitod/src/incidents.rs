@@ -4,13 +4,22 @@
 //! system events, and LLM analysis. Uses SQLite for simplicity and reliability.
 
 mod analyzer;
+mod postgres;
 
 pub use analyzer::{IncidentAnalysis, IncidentAnalyzer};
+pub use postgres::PostgresIncidentStore;
 
+use crate::telemetry::TelemetryRegistry;
+use anyhow::Context;
+use async_trait::async_trait;
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
-use sqlx::{Row, SqlitePool, sqlite::SqlitePoolOptions};
+use sqlx::{Row, SqliteConnection, SqlitePool, sqlite::SqlitePoolOptions};
+use std::future::Future;
 use std::path::Path;
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt};
 use tracing::{debug, info};
 
 /// Represents a circuit breaker incident or system event
@@ -44,22 +53,31 @@ pub struct Incident {
     pub psi_after: Option<f32>,
 }
 
-/// Incident storage backed by SQLite
-pub struct IncidentStore {
-    pool: SqlitePool,
-}
+/// Current schema version. Bump this and append a new entry to [`MIGRATIONS`]
+/// whenever the schema changes (new column, renamed field, new index) --
+/// never edit an already-shipped migration in place, since databases created
+/// under an older binary have already applied it.
+const DB_VERSION: i64 = 1;
 
-impl IncidentStore {
-    /// Create a new incident store
-    pub async fn new<P: AsRef<Path>>(db_path: P) -> Result<Self, sqlx::Error> {
-        let db_url = format!("sqlite://{}?mode=rwc", db_path.as_ref().display());
+/// One schema change, applied inside the single migration transaction. Takes
+/// the in-progress transaction's connection (rather than a pool) so every
+/// migration step for a given upgrade either all land or all roll back
+/// together.
+type MigrationStep = for<'a> fn(
+    &'a mut SqliteConnection,
+) -> Pin<Box<dyn Future<Output = Result<(), sqlx::Error>> + Send + 'a>>;
 
-        let pool = SqlitePoolOptions::new()
-            .max_connections(5)
-            .connect(&db_url)
-            .await?;
+/// Ordered by version; `MIGRATIONS[n]` takes the schema from version `n` to
+/// `n + 1`. Index 0 is migration 1 (the original schema, folded in here
+/// since older binaries only ever had `CREATE TABLE IF NOT EXISTS`, never a
+/// real `user_version`, so a fresh database and a pre-migration database both
+/// start from `user_version = 0`).
+const MIGRATIONS: &[MigrationStep] = &[migration_1_initial_schema];
 
-        // Create schema
+fn migration_1_initial_schema(
+    conn: &mut SqliteConnection,
+) -> Pin<Box<dyn Future<Output = Result<(), sqlx::Error>> + Send + '_>> {
+    Box::pin(async move {
         sqlx::query(
             r#"
             CREATE TABLE IF NOT EXISTS incidents (
@@ -84,14 +102,83 @@ impl IncidentStore {
             CREATE INDEX IF NOT EXISTS idx_psi_cpu ON incidents(psi_cpu);
             "#,
         )
-        .execute(&pool)
+        .execute(conn)
+        .await?;
+        Ok(())
+    })
+}
+
+/// Bring `pool`'s database up to [`DB_VERSION`] using `PRAGMA user_version`
+/// to track progress, applying every migration between the current version
+/// and `DB_VERSION` inside one transaction. Refuses to open a database whose
+/// `user_version` is already ahead of `DB_VERSION`, so a downgraded binary
+/// can't silently corrupt a newer schema.
+async fn migrate(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    let current_version: i64 = sqlx::query_scalar("PRAGMA user_version")
+        .fetch_one(pool)
         .await?;
 
+    if current_version > DB_VERSION {
+        return Err(sqlx::Error::Protocol(format!(
+            "incident database is at schema version {current_version}, but this binary only \
+             understands up to version {DB_VERSION} -- refusing to open a newer database with \
+             an older binary"
+        )));
+    }
+
+    if current_version == DB_VERSION {
+        return Ok(());
+    }
+
+    let mut tx = pool.begin().await?;
+    for step in &MIGRATIONS[current_version as usize..DB_VERSION as usize] {
+        step(&mut tx).await?;
+    }
+    // PRAGMA doesn't accept bind parameters, so interpolate the compile-time
+    // constant directly rather than binding it.
+    sqlx::query(&format!("PRAGMA user_version = {DB_VERSION}"))
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+
+    info!("Migrated incident database from schema version {current_version} to {DB_VERSION}");
+    Ok(())
+}
+
+/// Incident storage backed by SQLite
+pub struct IncidentStore {
+    pool: SqlitePool,
+    telemetry: Option<Arc<TelemetryRegistry>>,
+}
+
+impl IncidentStore {
+    /// Create a new incident store
+    pub async fn new<P: AsRef<Path>>(db_path: P) -> Result<Self, sqlx::Error> {
+        let db_url = format!("sqlite://{}?mode=rwc", db_path.as_ref().display());
+
+        let pool = SqlitePoolOptions::new()
+            .max_connections(5)
+            .connect(&db_url)
+            .await?;
+
+        migrate(&pool).await?;
+
         info!(
             "Incident store initialized at {}",
             db_path.as_ref().display()
         );
-        Ok(Self { pool })
+        Ok(Self {
+            pool,
+            telemetry: None,
+        })
+    }
+
+    /// Attach a telemetry registry; every inserted incident also updates its
+    /// event-type counter and recovery-time gauge there.
+    pub fn with_telemetry(mut self, telemetry: Arc<TelemetryRegistry>) -> Self {
+        self.telemetry = Some(telemetry);
+        self
     }
 
     /// Insert a new incident
@@ -122,6 +209,14 @@ impl IncidentStore {
 
         let id = result.last_insert_rowid();
         debug!("Inserted incident #{} (type: {})", id, incident.event_type);
+
+        if let Some(telemetry) = &self.telemetry {
+            telemetry.record_incident(
+                &incident.event_type,
+                incident.recovery_time_ms.map(|ms| ms as u64),
+            );
+        }
+
         Ok(id)
     }
 
@@ -270,6 +365,173 @@ impl IncidentStore {
             .collect())
     }
 
+    /// Stream every incident as newline-delimited JSON, ordered by
+    /// timestamp, for backup, migration, or shipping to another host.
+    pub async fn export_jsonl<W: AsyncWrite + Unpin>(&self, mut writer: W) -> anyhow::Result<usize> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, timestamp, event_type, psi_cpu, psi_memory, cpu_percent, load_avg,
+                   action, target_pid, target_name, system_snapshot,
+                   llm_analysis, llm_analyzed_at, recovery_time_ms, psi_after
+            FROM incidents
+            ORDER BY timestamp ASC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("querying incidents for export")?;
+
+        for row in &rows {
+            let incident = Incident {
+                id: Some(row.get(0)),
+                timestamp: row.get(1),
+                event_type: row.get(2),
+                psi_cpu: row.get(3),
+                psi_memory: row.get(4),
+                cpu_percent: row.get(5),
+                load_avg: row.get(6),
+                action: row.get(7),
+                target_pid: row.get(8),
+                target_name: row.get(9),
+                system_snapshot: row.get(10),
+                llm_analysis: row.get(11),
+                llm_analyzed_at: row.get(12),
+                recovery_time_ms: row.get(13),
+                psi_after: row.get(14),
+            };
+            let mut line = serde_json::to_string(&incident).context("serializing incident")?;
+            line.push('\n');
+            writer
+                .write_all(line.as_bytes())
+                .await
+                .context("writing incident to export stream")?;
+        }
+        writer.flush().await.context("flushing export stream")?;
+
+        Ok(rows.len())
+    }
+
+    /// Read a newline-delimited JSON stream of incidents (as produced by
+    /// [`IncidentStore::export_jsonl`]), validating each record and
+    /// inserting in batches of [`IMPORT_BATCH_SIZE`] inside one transaction
+    /// apiece, so a bulk restore doesn't leave the database half-loaded if
+    /// it fails partway through. Returns the number of incidents inserted.
+    pub async fn import_jsonl<R: AsyncBufRead + Unpin>(
+        &self,
+        mut reader: R,
+        id_mode: ImportIdMode,
+    ) -> anyhow::Result<usize> {
+        let mut total = 0;
+        let mut batch = Vec::with_capacity(IMPORT_BATCH_SIZE);
+        let mut line = String::new();
+        let mut line_no = 0usize;
+
+        loop {
+            line.clear();
+            let bytes_read = reader
+                .read_line(&mut line)
+                .await
+                .context("reading incident import stream")?;
+            if bytes_read == 0 {
+                break;
+            }
+            line_no += 1;
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+
+            let incident: Incident = serde_json::from_str(trimmed)
+                .with_context(|| format!("invalid incident record at line {line_no}"))?;
+            batch.push(incident);
+
+            if batch.len() >= IMPORT_BATCH_SIZE {
+                total += self.insert_batch(&batch, id_mode).await?;
+                batch.clear();
+            }
+        }
+        if !batch.is_empty() {
+            total += self.insert_batch(&batch, id_mode).await?;
+        }
+
+        Ok(total)
+    }
+
+    /// Insert one batch of imported incidents inside a single transaction.
+    async fn insert_batch(
+        &self,
+        incidents: &[Incident],
+        id_mode: ImportIdMode,
+    ) -> anyhow::Result<usize> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("starting incident import transaction")?;
+
+        for incident in incidents {
+            if id_mode == ImportIdMode::Preserve && incident.id.is_some() {
+                sqlx::query(
+                    r#"
+                    INSERT INTO incidents (
+                        id, timestamp, event_type, psi_cpu, psi_memory, cpu_percent, load_avg,
+                        action, target_pid, target_name, system_snapshot,
+                        llm_analysis, llm_analyzed_at, recovery_time_ms, psi_after
+                    ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                    "#,
+                )
+                .bind(incident.id)
+                .bind(incident.timestamp)
+                .bind(&incident.event_type)
+                .bind(incident.psi_cpu)
+                .bind(incident.psi_memory)
+                .bind(incident.cpu_percent)
+                .bind(&incident.load_avg)
+                .bind(&incident.action)
+                .bind(incident.target_pid)
+                .bind(&incident.target_name)
+                .bind(&incident.system_snapshot)
+                .bind(&incident.llm_analysis)
+                .bind(incident.llm_analyzed_at)
+                .bind(incident.recovery_time_ms)
+                .bind(incident.psi_after)
+                .execute(&mut *tx)
+                .await
+                .context("inserting incident with preserved id")?;
+            } else {
+                sqlx::query(
+                    r#"
+                    INSERT INTO incidents (
+                        timestamp, event_type, psi_cpu, psi_memory, cpu_percent, load_avg,
+                        action, target_pid, target_name, system_snapshot,
+                        llm_analysis, llm_analyzed_at, recovery_time_ms, psi_after
+                    ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                    "#,
+                )
+                .bind(incident.timestamp)
+                .bind(&incident.event_type)
+                .bind(incident.psi_cpu)
+                .bind(incident.psi_memory)
+                .bind(incident.cpu_percent)
+                .bind(&incident.load_avg)
+                .bind(&incident.action)
+                .bind(incident.target_pid)
+                .bind(&incident.target_name)
+                .bind(&incident.system_snapshot)
+                .bind(&incident.llm_analysis)
+                .bind(incident.llm_analyzed_at)
+                .bind(incident.recovery_time_ms)
+                .bind(incident.psi_after)
+                .execute(&mut *tx)
+                .await
+                .context("inserting incident")?;
+            }
+        }
+
+        tx.commit().await.context("committing incident import batch")?;
+        Ok(incidents.len())
+    }
+
     /// Get statistics about incidents
     pub async fn stats(&self) -> Result<IncidentStats, sqlx::Error> {
         let total_row = sqlx::query("SELECT COUNT(*) FROM incidents")
@@ -296,8 +558,196 @@ impl IncidentStore {
             avg_recovery_time_ms: avg_recovery.map(|r| r as u64),
         })
     }
+
+    /// Incident counts grouped by `event_type`, for a `/metrics` counter --
+    /// queried live rather than tracked in memory, so a scrape reflects the
+    /// durable total even across a daemon restart.
+    pub async fn counts_by_event_type(&self) -> Result<Vec<(String, u64)>, sqlx::Error> {
+        let rows = sqlx::query("SELECT event_type, COUNT(*) as count FROM incidents GROUP BY event_type")
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let event_type: String = row.get("event_type");
+                let count: i64 = row.get("count");
+                (event_type, count as u64)
+            })
+            .collect())
+    }
+
+    /// Every recorded `recovery_time_ms`, for bucketing into a `/metrics`
+    /// histogram. Unbounded, but incident volume is low enough (circuit
+    /// breaker triggers, not every request) that this is cheap in practice.
+    pub async fn recovery_time_ms_values(&self) -> Result<Vec<u64>, sqlx::Error> {
+        let rows = sqlx::query(
+            "SELECT recovery_time_ms FROM incidents WHERE recovery_time_ms IS NOT NULL",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows
+            .iter()
+            .map(|row| row.get::<i64, _>("recovery_time_ms") as u64)
+            .collect())
+    }
+
+    /// Page through incidents in strict, gap-free sequence order since
+    /// `last_seq`, for a UI or exporter that polls repeatedly. Filtering on
+    /// `timestamp` (as [`IncidentStore::since`] does) can drop or duplicate
+    /// rows under clock skew or same-second collisions; `id` doesn't have
+    /// that problem, since SQLite's `AUTOINCREMENT` is both monotonic and
+    /// (as long as rows are never deleted out of order) gap-free, so it
+    /// doubles as the cursor. Returns the page plus the cursor to resume
+    /// from on the next call.
+    pub async fn incidents_since_seq(
+        &self,
+        last_seq: i64,
+        limit: i64,
+    ) -> Result<(Vec<Incident>, i64), SinceSeqError> {
+        let oldest_retained: Option<i64> = sqlx::query_scalar("SELECT MIN(id) FROM incidents")
+            .fetch_one(&self.pool)
+            .await?;
+
+        if let Some(oldest_retained) = oldest_retained {
+            if last_seq < oldest_retained - 1 {
+                return Err(SinceSeqError::CursorTooOld {
+                    requested: last_seq,
+                    oldest_retained,
+                });
+            }
+        }
+
+        let rows = sqlx::query(
+            r#"
+            SELECT id, timestamp, event_type, psi_cpu, psi_memory, cpu_percent, load_avg,
+                   action, target_pid, target_name, system_snapshot,
+                   llm_analysis, llm_analyzed_at, recovery_time_ms, psi_after
+            FROM incidents
+            WHERE id > ?
+            ORDER BY id ASC
+            LIMIT ?
+            "#,
+        )
+        .bind(last_seq)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let next_seq = rows.last().map(|r| r.get::<i64, _>(0)).unwrap_or(last_seq);
+
+        let incidents = rows
+            .iter()
+            .map(|r| Incident {
+                id: Some(r.get(0)),
+                timestamp: r.get(1),
+                event_type: r.get(2),
+                psi_cpu: r.get(3),
+                psi_memory: r.get(4),
+                cpu_percent: r.get(5),
+                load_avg: r.get(6),
+                action: r.get(7),
+                target_pid: r.get(8),
+                target_name: r.get(9),
+                system_snapshot: r.get(10),
+                llm_analysis: r.get(11),
+                llm_analyzed_at: r.get(12),
+                recovery_time_ms: r.get(13),
+                psi_after: r.get(14),
+            })
+            .collect();
+
+        Ok((incidents, next_seq))
+    }
+}
+
+/// Error from [`IncidentStore::incidents_since_seq`].
+#[derive(Debug)]
+pub enum SinceSeqError {
+    /// `requested` precedes `oldest_retained`, meaning the window the
+    /// caller wants has already been pruned away. The caller missed
+    /// incidents and must re-sync (e.g. from `oldest_retained`) rather than
+    /// silently resuming with a gap in its view.
+    CursorTooOld { requested: i64, oldest_retained: i64 },
+    Db(sqlx::Error),
+}
+
+impl std::fmt::Display for SinceSeqError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SinceSeqError::CursorTooOld { requested, oldest_retained } => write!(
+                f,
+                "cursor {requested} is older than the oldest retained incident (seq {oldest_retained}); re-sync required"
+            ),
+            SinceSeqError::Db(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for SinceSeqError {}
+
+impl From<sqlx::Error> for SinceSeqError {
+    fn from(e: sqlx::Error) -> Self {
+        SinceSeqError::Db(e)
+    }
+}
+
+/// Query surface shared by every incident backend, so the daemon can run
+/// against a single local SQLite file ([`IncidentStore`]) or a shared
+/// Postgres database ([`PostgresIncidentStore`]) without callers caring
+/// which one is behind the trait object. Export/import and the
+/// Prometheus-facing helpers above stay inherent to [`IncidentStore`] for
+/// now -- they're bulk/operational tooling, not the hot query path a second
+/// backend needs to match.
+#[async_trait]
+pub trait IncidentRepo: Send + Sync {
+    async fn insert(&self, incident: &Incident) -> Result<i64, sqlx::Error>;
+    async fn get(&self, id: i64) -> Result<Option<Incident>, sqlx::Error>;
+    async fn recent(&self, limit: i64) -> Result<Vec<Incident>, sqlx::Error>;
+    async fn since(&self, start_timestamp: i64, event_type: Option<&str>) -> Result<Vec<Incident>, sqlx::Error>;
+    async fn add_llm_analysis(&self, id: i64, analysis: String) -> Result<(), sqlx::Error>;
+    async fn stats(&self) -> Result<IncidentStats, sqlx::Error>;
 }
 
+#[async_trait]
+impl IncidentRepo for IncidentStore {
+    async fn insert(&self, incident: &Incident) -> Result<i64, sqlx::Error> {
+        self.insert(incident).await
+    }
+
+    async fn get(&self, id: i64) -> Result<Option<Incident>, sqlx::Error> {
+        self.get(id).await
+    }
+
+    async fn recent(&self, limit: i64) -> Result<Vec<Incident>, sqlx::Error> {
+        self.recent(limit).await
+    }
+
+    async fn since(&self, start_timestamp: i64, event_type: Option<&str>) -> Result<Vec<Incident>, sqlx::Error> {
+        self.since(start_timestamp, event_type).await
+    }
+
+    async fn add_llm_analysis(&self, id: i64, analysis: String) -> Result<(), sqlx::Error> {
+        self.add_llm_analysis(id, analysis).await
+    }
+
+    async fn stats(&self) -> Result<IncidentStats, sqlx::Error> {
+        self.stats().await
+    }
+}
+
+/// Whether [`IncidentStore::import_jsonl`] keeps each record's original
+/// `id` (an exact mirror restore onto an empty database) or lets SQLite
+/// assign a fresh one (merging an archive into an already-populated store,
+/// where the original ids would likely collide).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportIdMode {
+    Preserve,
+    Reassign,
+}
+
+/// How many incidents `import_jsonl` inserts per transaction.
+const IMPORT_BATCH_SIZE: usize = 500;
+
 /// Statistics about stored incidents
 #[derive(Debug, Serialize)]
 pub struct IncidentStats {
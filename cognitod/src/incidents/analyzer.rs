@@ -37,14 +37,71 @@ impl IncidentAnalyzer {
         Ok(Self { endpoint, client })
     }
 
-    /// Analyze an incident using the LLM
+    /// Analyze an incident using the LLM, returning the raw response text.
     pub async fn analyze(
         &self,
         incident: &Incident,
     ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
         let prompt = self.build_analysis_prompt(incident);
+        self.chat(&prompt, false).await
+    }
+
+    /// Analyze an incident and return a validated [`IncidentAnalysis`].
+    ///
+    /// Tries a strict JSON-mode request first (deserialized directly with
+    /// serde). If the model ignores the schema hint, falls back to the
+    /// lenient text parser; if that parser is missing fields, re-prompts
+    /// once, naming the missing fields and asking for the complete
+    /// analysis again, before giving up.
+    pub async fn analyze_structured(
+        &self,
+        incident: &Incident,
+    ) -> Result<IncidentAnalysis, Box<dyn std::error::Error + Send + Sync>> {
+        let prompt = self.build_analysis_prompt(incident);
+        let raw = self.chat(&prompt, true).await?;
+
+        if let Ok(analysis) = serde_json::from_str::<IncidentAnalysis>(&raw) {
+            return Ok(analysis);
+        }
+        debug!("[incident_analyzer] JSON-mode response didn't match schema, falling back to text parser");
+
+        match Self::parse_analysis(&raw) {
+            Ok(analysis) => Ok(analysis),
+            Err(missing) => {
+                debug!(
+                    "[incident_analyzer] missing field(s) {:?}, retrying once",
+                    missing
+                );
+                let retry_prompt = format!(
+                    "{prompt}\n\nYour previous reply was missing required field(s): {}. \
+                     Resend the COMPLETE analysis with all six fields (ACTION_SUMMARY, \
+                     ROOT_CAUSE, IMPACT, SEVERITY, RECOMMENDATION, CONFIDENCE).",
+                    missing.join(", ")
+                );
+                let retry_raw = self.chat(&retry_prompt, false).await?;
+                Self::parse_analysis(&retry_raw).map_err(|still_missing| {
+                    format!(
+                        "incident analysis still missing field(s) after retry: {}",
+                        still_missing.join(", ")
+                    )
+                    .into()
+                })
+            }
+        }
+    }
 
-        let request_body = json!({
+    /// Send one chat-completion request and return the message content.
+    ///
+    /// When `json_mode` is set, hints the server to return a strict JSON
+    /// object matching [`IncidentAnalysis`] via a `response_format` /
+    /// JSON-schema field (servers that ignore it simply return free text,
+    /// which the caller falls back to parsing).
+    async fn chat(
+        &self,
+        prompt: &str,
+        json_mode: bool,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let mut request_body = json!({
             "model": "linnix-3b-distilled",
             "messages": [
                 {
@@ -60,7 +117,28 @@ impl IncidentAnalyzer {
             "max_tokens": 500
         });
 
-        debug!("[incident_analyzer] Requesting LLM analysis for incident");
+        if json_mode {
+            request_body["response_format"] = json!({
+                "type": "json_schema",
+                "json_schema": {
+                    "name": "incident_analysis",
+                    "schema": {
+                        "type": "object",
+                        "properties": {
+                            "action_summary": {"type": "string"},
+                            "root_cause": {"type": "string"},
+                            "impact": {"type": "string"},
+                            "severity": {"type": "string", "enum": ["low", "medium", "high", "critical"]},
+                            "recommendation": {"type": "string"},
+                            "confidence": {"type": "number"}
+                        },
+                        "required": ["action_summary", "root_cause", "impact", "severity", "recommendation", "confidence"]
+                    }
+                }
+            });
+        }
+
+        debug!("[incident_analyzer] Requesting LLM analysis for incident (json_mode={json_mode})");
 
         let response = self
             .client
@@ -77,7 +155,6 @@ impl IncidentAnalyzer {
 
         let response_json: serde_json::Value = response.json().await?;
 
-        // Extract LLM response
         let analysis = response_json["choices"][0]["message"]["content"]
             .as_str()
             .unwrap_or("Analysis unavailable")
@@ -169,8 +246,12 @@ CONFIDENCE: <0.0-1.0>
         }
     }
 
-    /// Parse structured analysis from LLM response
-    pub fn parse_analysis(text: &str) -> Option<IncidentAnalysis> {
+    /// Parse structured analysis from the line-prefixed text format.
+    ///
+    /// Returns `Err` with the names of every field that failed to parse
+    /// instead of discarding the whole analysis on the first miss, so
+    /// callers can decide whether to re-prompt for just those fields.
+    pub fn parse_analysis(text: &str) -> Result<IncidentAnalysis, Vec<String>> {
         let mut action_summary = None;
         let mut root_cause = None;
         let mut impact = None;
@@ -180,29 +261,52 @@ CONFIDENCE: <0.0-1.0>
 
         for line in text.lines() {
             let line = line.trim();
-            if line.starts_with("ACTION_SUMMARY:") {
-                action_summary = Some(line.strip_prefix("ACTION_SUMMARY:")?.trim().to_string());
-            } else if line.starts_with("ROOT_CAUSE:") {
-                root_cause = Some(line.strip_prefix("ROOT_CAUSE:")?.trim().to_string());
-            } else if line.starts_with("IMPACT:") {
-                impact = Some(line.strip_prefix("IMPACT:")?.trim().to_string());
-            } else if line.starts_with("SEVERITY:") {
-                severity = Some(line.strip_prefix("SEVERITY:")?.trim().to_lowercase());
-            } else if line.starts_with("RECOMMENDATION:") {
-                recommendation = Some(line.strip_prefix("RECOMMENDATION:")?.trim().to_string());
-            } else if line.starts_with("CONFIDENCE:") {
-                let conf_str = line.strip_prefix("CONFIDENCE:")?.trim();
-                confidence = conf_str.parse::<f32>().ok();
+            if let Some(rest) = line.strip_prefix("ACTION_SUMMARY:") {
+                action_summary = Some(rest.trim().to_string());
+            } else if let Some(rest) = line.strip_prefix("ROOT_CAUSE:") {
+                root_cause = Some(rest.trim().to_string());
+            } else if let Some(rest) = line.strip_prefix("IMPACT:") {
+                impact = Some(rest.trim().to_string());
+            } else if let Some(rest) = line.strip_prefix("SEVERITY:") {
+                severity = Some(rest.trim().to_lowercase());
+            } else if let Some(rest) = line.strip_prefix("RECOMMENDATION:") {
+                recommendation = Some(rest.trim().to_string());
+            } else if let Some(rest) = line.strip_prefix("CONFIDENCE:") {
+                confidence = rest.trim().parse::<f32>().ok();
             }
         }
 
-        Some(IncidentAnalysis {
-            action_summary: action_summary?,
-            root_cause: root_cause?,
-            impact: impact?,
-            severity: severity?,
-            recommendation: recommendation?,
-            confidence: confidence?,
+        let mut missing = Vec::new();
+        if action_summary.is_none() {
+            missing.push("ACTION_SUMMARY".to_string());
+        }
+        if root_cause.is_none() {
+            missing.push("ROOT_CAUSE".to_string());
+        }
+        if impact.is_none() {
+            missing.push("IMPACT".to_string());
+        }
+        if severity.is_none() {
+            missing.push("SEVERITY".to_string());
+        }
+        if recommendation.is_none() {
+            missing.push("RECOMMENDATION".to_string());
+        }
+        if confidence.is_none() {
+            missing.push("CONFIDENCE".to_string());
+        }
+
+        if !missing.is_empty() {
+            return Err(missing);
+        }
+
+        Ok(IncidentAnalysis {
+            action_summary: action_summary.unwrap(),
+            root_cause: root_cause.unwrap(),
+            impact: impact.unwrap(),
+            severity: severity.unwrap(),
+            recommendation: recommendation.unwrap(),
+            confidence: confidence.unwrap(),
         })
     }
 }
@@ -229,6 +333,36 @@ CONFIDENCE: 0.95
         assert!(analysis.action_summary.contains("Auto-killed"));
     }
 
+    #[test]
+    fn test_parse_analysis_json_mode() {
+        let response = r#"{
+            "action_summary": "Auto-killed aggressive process",
+            "root_cause": "Fork bomb",
+            "impact": "System unresponsive",
+            "severity": "critical",
+            "recommendation": "Set ulimit -u",
+            "confidence": 0.9
+        }"#;
+
+        let analysis = serde_json::from_str::<IncidentAnalysis>(response).unwrap();
+        assert_eq!(analysis.severity, "critical");
+        assert_eq!(analysis.confidence, 0.9);
+    }
+
+    #[test]
+    fn test_parse_analysis_reports_missing_fields() {
+        let response = r#"
+ACTION_SUMMARY: Auto-killed aggressive process
+ROOT_CAUSE: Fork bomb
+"#;
+
+        let missing = IncidentAnalyzer::parse_analysis(response).unwrap_err();
+        assert_eq!(
+            missing,
+            vec!["IMPACT", "SEVERITY", "RECOMMENDATION", "CONFIDENCE"]
+        );
+    }
+
     #[test]
     fn test_build_prompt() {
         let incident = Incident {
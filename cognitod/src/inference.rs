@@ -0,0 +1,5 @@
+//! LLM-backed inference helpers (command tagging, response summarization).
+
+pub mod gossip;
+pub mod summarizer;
+pub mod tag_cache;
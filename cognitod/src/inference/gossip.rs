@@ -0,0 +1,283 @@
+//! Cluster-wide anti-entropy gossip of learned command -> tag mappings
+//!
+//! Each daemon in a fleet independently calls the LLM to classify the same
+//! common commands. This subsystem lets peers exchange `TAG_CACHE` contents
+//! over UDP instead of each paying that round trip: every few seconds a
+//! node picks a random known peer and sends a digest (command name -> short
+//! hash of its tag list, plus the timestamp it last changed). The peer
+//! replies with the keys it's missing or holds an older copy of, and the
+//! originator pushes the full `(comm, tags)` entries for those keys.
+//! Received entries flow through [`insert_tag_cache`](super::summarizer::insert_tag_cache)
+//! so size limits and the dirty flag are respected. Messages are a one-byte
+//! protocol version, a 4-byte big-endian length prefix, and a bincode
+//! payload; entries carry a wall-clock `updated_at` (epoch milliseconds) so the newer classification
+//! wins on conflict.
+
+use super::summarizer::{DEFAULT_TAG_TTL, TAG_CACHE, insert_tag_cache};
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::UdpSocket;
+
+/// Wire protocol version; bump when [`GossipMessage`]'s shape changes.
+const PROTOCOL_VERSION: u8 = 1;
+const MAX_DATAGRAM: usize = 65_507;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct DigestEntry {
+    comm: String,
+    updated_at: i64,
+    hash: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct GossipEntry {
+    comm: String,
+    tags: Vec<String>,
+    updated_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum GossipMessage {
+    Digest(Vec<DigestEntry>),
+    Request(Vec<String>),
+    Push(Vec<GossipEntry>),
+}
+
+/// Gossip subsystem configuration. Peers are normally drawn from `Config`'s
+/// seed list; surfaced via env vars here until that struct is reachable
+/// from this crate snapshot (see `LLM_TAG_ENDPOINT` et al. for the same
+/// convention elsewhere in this module).
+#[derive(Debug, Clone)]
+pub struct GossipConfig {
+    pub bind_addr: SocketAddr,
+    pub seeds: Vec<SocketAddr>,
+    pub interval: Duration,
+}
+
+impl GossipConfig {
+    /// `None` if no seed list is configured, i.e. gossip should stay off.
+    pub fn from_env() -> Option<Self> {
+        let seeds_raw = std::env::var("LLM_TAG_GOSSIP_SEEDS").ok()?;
+        let seeds: Vec<SocketAddr> = seeds_raw
+            .split(',')
+            .filter_map(|s| s.trim().parse().ok())
+            .collect();
+        if seeds.is_empty() {
+            return None;
+        }
+        let bind_addr = std::env::var("LLM_TAG_GOSSIP_BIND")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or_else(|| "0.0.0.0:7475".parse().unwrap());
+        let interval = std::env::var("LLM_TAG_GOSSIP_INTERVAL_SECS")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .map(Duration::from_secs)
+            .unwrap_or_else(|| Duration::from_secs(5));
+        Some(Self {
+            bind_addr,
+            seeds,
+            interval,
+        })
+    }
+}
+
+/// Start the UDP listener and the periodic gossip task; runs until the process exits.
+pub async fn run(config: GossipConfig) -> std::io::Result<()> {
+    let socket = Arc::new(UdpSocket::bind(config.bind_addr).await?);
+    log::info!(
+        "[gossip] listening on {} with {} seed(s), interval {:?}",
+        config.bind_addr,
+        config.seeds.len(),
+        config.interval
+    );
+
+    let listener = tokio::spawn(listen_loop(socket.clone()));
+    let gossiper = tokio::spawn(gossip_loop(socket, config.seeds, config.interval));
+
+    let _ = tokio::join!(listener, gossiper);
+    Ok(())
+}
+
+async fn listen_loop(socket: Arc<UdpSocket>) {
+    let mut buf = vec![0u8; MAX_DATAGRAM];
+    loop {
+        match socket.recv_from(&mut buf).await {
+            Ok((len, peer)) => {
+                if let Some(msg) = decode_message(&buf[..len]) {
+                    handle_message(&socket, peer, msg).await;
+                }
+            }
+            Err(err) => log::warn!("[gossip] recv error: {err}"),
+        }
+    }
+}
+
+async fn gossip_loop(socket: Arc<UdpSocket>, seeds: Vec<SocketAddr>, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        ticker.tick().await;
+        let Some(peer) = seeds.choose(&mut rand::thread_rng()).copied() else {
+            continue;
+        };
+        let digest = build_digest();
+        if digest.is_empty() {
+            continue;
+        }
+        send_message(&socket, peer, &GossipMessage::Digest(digest)).await;
+    }
+}
+
+async fn handle_message(socket: &UdpSocket, peer: SocketAddr, msg: GossipMessage) {
+    match msg {
+        GossipMessage::Digest(entries) => {
+            let local: HashMap<String, (i64, u64)> = TAG_CACHE
+                .snapshot()
+                .into_iter()
+                .map(|(comm, tags, updated_at)| (comm, (updated_at, hash_tags(&tags))))
+                .collect();
+            let wanted: Vec<String> = entries
+                .into_iter()
+                .filter(|e| match local.get(&e.comm) {
+                    None => true,
+                    Some((local_updated, local_hash)) => {
+                        e.updated_at > *local_updated && e.hash != *local_hash
+                    }
+                })
+                .map(|e| e.comm)
+                .collect();
+            if !wanted.is_empty() {
+                send_message(socket, peer, &GossipMessage::Request(wanted)).await;
+            }
+        }
+        GossipMessage::Request(keys) => {
+            let keys: HashSet<String> = keys.into_iter().collect();
+            let entries: Vec<GossipEntry> = TAG_CACHE
+                .snapshot()
+                .into_iter()
+                .filter(|(comm, _, _)| keys.contains(comm))
+                .map(|(comm, tags, updated_at)| GossipEntry {
+                    comm,
+                    tags,
+                    updated_at,
+                })
+                .collect();
+            if !entries.is_empty() {
+                send_message(socket, peer, &GossipMessage::Push(entries)).await;
+            }
+        }
+        GossipMessage::Push(entries) => {
+            for entry in entries {
+                log::debug!("[gossip] learned '{}' from {peer}", entry.comm);
+                insert_tag_cache(entry.comm, entry.tags, Some(DEFAULT_TAG_TTL)).await;
+            }
+        }
+    }
+}
+
+fn build_digest() -> Vec<DigestEntry> {
+    TAG_CACHE
+        .snapshot()
+        .into_iter()
+        .map(|(comm, tags, updated_at)| DigestEntry {
+            hash: hash_tags(&tags),
+            comm,
+            updated_at,
+        })
+        .collect()
+}
+
+fn hash_tags(tags: &[String]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    tags.hash(&mut hasher);
+    hasher.finish()
+}
+
+async fn send_message(socket: &UdpSocket, peer: SocketAddr, msg: &GossipMessage) {
+    let bytes = encode_message(msg);
+    if let Err(err) = socket.send_to(&bytes, peer).await {
+        log::warn!("[gossip] send to {peer} failed: {err}");
+    }
+}
+
+fn encode_message(msg: &GossipMessage) -> Vec<u8> {
+    let payload = bincode::serialize(msg).expect("gossip message should serialize");
+    let mut buf = Vec::with_capacity(1 + 4 + payload.len());
+    buf.push(PROTOCOL_VERSION);
+    buf.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+    buf.extend_from_slice(&payload);
+    buf
+}
+
+fn decode_message(bytes: &[u8]) -> Option<GossipMessage> {
+    if bytes.len() < 5 {
+        return None;
+    }
+    let version = bytes[0];
+    if version != PROTOCOL_VERSION {
+        log::warn!("[gossip] dropping message with unsupported protocol version {version}");
+        return None;
+    }
+    let len = u32::from_be_bytes(bytes[1..5].try_into().ok()?) as usize;
+    let payload = bytes.get(5..5 + len)?;
+    bincode::deserialize(payload).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_digest_message() {
+        let msg = GossipMessage::Digest(vec![DigestEntry {
+            comm: "apt-get".to_string(),
+            updated_at: 1234,
+            hash: 42,
+        }]);
+        let bytes = encode_message(&msg);
+        let decoded = decode_message(&bytes).unwrap();
+        match decoded {
+            GossipMessage::Digest(entries) => {
+                assert_eq!(entries.len(), 1);
+                assert_eq!(entries[0].comm, "apt-get");
+                assert_eq!(entries[0].updated_at, 1234);
+                assert_eq!(entries[0].hash, 42);
+            }
+            _ => panic!("expected Digest"),
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_protocol_version() {
+        let mut bytes = encode_message(&GossipMessage::Request(vec!["curl".to_string()]));
+        bytes[0] = PROTOCOL_VERSION + 1;
+        assert!(decode_message(&bytes).is_none());
+    }
+
+    #[test]
+    fn rejects_truncated_messages() {
+        assert!(decode_message(&[PROTOCOL_VERSION, 0, 0]).is_none());
+    }
+
+    #[test]
+    fn hash_is_stable_across_calls() {
+        let tags = vec!["package_manager".to_string()];
+        assert_eq!(hash_tags(&tags), hash_tags(&tags));
+    }
+
+    #[test]
+    fn gossip_config_requires_seeds() {
+        // SAFETY: no other test in this module reads or writes this env var;
+        // it is cleared so `from_env` sees no seed list.
+        unsafe {
+            std::env::remove_var("LLM_TAG_GOSSIP_SEEDS");
+        }
+        assert!(GossipConfig::from_env().is_none());
+    }
+}
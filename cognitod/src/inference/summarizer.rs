@@ -1,28 +1,57 @@
 #![allow(dead_code)]
 
+use super::tag_cache::{InMemoryTagCache, RedisTagCache, TagCacheBackend};
 use crate::config::{Config, OfflineGuard};
 use crate::metrics::Metrics;
-use dashmap::DashMap;
-use flate2::{Compression, read::GzDecoder, write::GzEncoder};
-use once_cell::sync::Lazy;
+use once_cell::sync::{Lazy, OnceCell};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use serde_json;
+use std::collections::HashMap;
 use std::env;
-use std::fs::File;
-use std::io::{Read, Write};
-use std::os::unix::fs::OpenOptionsExt;
-use std::path::Path;
 use std::sync::Arc;
-use std::sync::atomic::{AtomicBool, Ordering}; // Add this import
 use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
 
-const TAG_CACHE_PATH: &str = "tag_cache.json.gz";
 const TAG_CACHE_MAX_ENTRIES: usize = 10_000;
 const TAG_CACHE_USE_GZIP: bool = true;
+/// Default TTL for learned tags; after this they're treated as stale and re-queried.
+pub(crate) const DEFAULT_TAG_TTL: Duration = Duration::from_secs(24 * 3600);
 
-pub static TAG_CACHE: Lazy<DashMap<String, Vec<String>>> = Lazy::new(DashMap::new);
-static TAG_CACHE_DIRTY: Lazy<AtomicBool> = Lazy::new(|| AtomicBool::new(false));
+/// GBNF grammar constraining tagger output to a JSON array of 1-3 lowercase
+/// snake_case tags, mirroring `IlmClient`'s `INSIGHT_GRAMMAR` convention so
+/// local models can't wander off into prose, code fences, or malformed JSON.
+const TAG_GRAMMAR: &str = r#"root ::= "[" space tag (space "," space tag)? (space "," space tag)? space "]"
+tag ::= "\"" [a-z] [a-z_]* "\""
+space ::= [ \t\n]*"#;
+
+/// Endpoints that reject an unrecognized `grammar` field can opt out.
+static TAG_GRAMMAR_ENABLED: Lazy<bool> = Lazy::new(|| {
+    env::var("LLM_TAG_GRAMMAR_DISABLED")
+        .map(|v| v != "1" && v.to_lowercase() != "true")
+        .unwrap_or(true)
+});
+
+/// GBNF grammar for the batched tagger: a JSON object mapping each input
+/// command to its own `TAG_GRAMMAR`-shaped tag array.
+const TAG_BATCH_GRAMMAR: &str = r#"root ::= "{" space (entry (space "," space entry)*)? space "}"
+entry ::= key space ":" space tags
+key ::= "\"" [^"]+ "\""
+tags ::= "[" space tag (space "," space tag)? (space "," space tag)? space "]"
+tag ::= "\"" [a-z] [a-z_]* "\""
+space ::= [ \t\n]*"#;
+
+/// Commands are queued here for up to `TAG_BATCH_WINDOW` (or until
+/// `TAG_BATCH_MAX` pile up), then issued as a single chat request. Cuts
+/// per-command round trips when a burst of unseen commands shows up.
+const TAG_BATCH_MAX: usize = 16;
+const TAG_BATCH_WINDOW: Duration = Duration::from_millis(50);
+const TAG_BATCH_QUEUE_DEPTH: usize = 256;
+
+/// Process-local tag cache, always available as the fallback/disk-persisted backend.
+pub static TAG_CACHE: Lazy<Arc<InMemoryTagCache>> =
+    Lazy::new(|| Arc::new(InMemoryTagCache::new(tag_cache_path(), TAG_CACHE_MAX_ENTRIES, TAG_CACHE_USE_GZIP)));
+static REMOTE_TAG_CACHE: OnceCell<Arc<RedisTagCache>> = OnceCell::new();
 static TAG_HTTP_CLIENT: Lazy<Client> = Lazy::new(|| {
     Client::builder()
         .timeout(Duration::from_secs(6))
@@ -44,6 +73,16 @@ static TAG_API_KEY: Lazy<Option<String>> = Lazy::new(|| {
         .ok()
         .or_else(|| env::var("OPENAI_API_KEY").ok())
 });
+static TAG_BATCH_QUEUE: Lazy<mpsc::Sender<BatchRequest>> = Lazy::new(|| {
+    let (tx, rx) = mpsc::channel(TAG_BATCH_QUEUE_DEPTH);
+    tokio::spawn(run_tag_batcher(rx));
+    tx
+});
+
+struct BatchRequest {
+    comm: String,
+    reply: oneshot::Sender<anyhow::Result<Vec<String>>>,
+}
 
 #[derive(Serialize)]
 struct ChatMessage {
@@ -61,6 +100,8 @@ struct ChatRequest {
     max_tokens: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     stream: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    grammar: Option<&'static str>,
 }
 
 #[derive(Deserialize)]
@@ -91,10 +132,146 @@ pub async fn llm_tags_for_comm(
     // Check cache first
     let key = comm.trim().to_lowercase();
 
-    if let Some(tags) = TAG_CACHE.get(&key) {
-        return Ok(tags.clone());
+    if let Some(tags) = active_tag_cache().await.get(&key).await {
+        return Ok(tags);
+    }
+
+    let tags = match tag_via_batch(key.clone()).await {
+        Ok(tags) => tags,
+        Err(err) => {
+            log::debug!(
+                "[tagger] batched request didn't resolve '{key}' ({err}), falling back to single request"
+            );
+            metrics
+                .tag_failures_total
+                .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            single_tag_request(comm, &metrics).await?
+        }
+    };
+
+    // Insert into cache
+    log::debug!("[tagger] cached tags for '{comm}': {tags:?}");
+    insert_tag_cache(key.clone(), tags.clone(), Some(DEFAULT_TAG_TTL)).await;
+
+    Ok(tags)
+}
+
+/// Queue `key` for the next batch and wait for its result, keyed by the
+/// normalized command name via a oneshot reply channel.
+async fn tag_via_batch(key: String) -> anyhow::Result<Vec<String>> {
+    let (reply, reply_rx) = oneshot::channel();
+    TAG_BATCH_QUEUE
+        .send(BatchRequest { comm: key, reply })
+        .await
+        .map_err(|_| anyhow::anyhow!("tag batch queue is closed"))?;
+    reply_rx
+        .await
+        .map_err(|_| anyhow::anyhow!("tag batch worker dropped without replying"))?
+}
+
+/// Drains up to `TAG_BATCH_MAX` pending requests every `TAG_BATCH_WINDOW`
+/// and resolves them with a single chat request.
+async fn run_tag_batcher(mut rx: mpsc::Receiver<BatchRequest>) {
+    loop {
+        let Some(first) = rx.recv().await else {
+            return;
+        };
+        let mut pending = vec![first];
+        let deadline = tokio::time::sleep(TAG_BATCH_WINDOW);
+        tokio::pin!(deadline);
+        while pending.len() < TAG_BATCH_MAX {
+            tokio::select! {
+                _ = &mut deadline => break,
+                maybe = rx.recv() => match maybe {
+                    Some(req) => pending.push(req),
+                    None => break,
+                },
+            }
+        }
+        resolve_batch(pending).await;
+    }
+}
+
+async fn resolve_batch(pending: Vec<BatchRequest>) {
+    let comms: Vec<String> = pending.iter().map(|req| req.comm.clone()).collect();
+    match fetch_batch_tags(&comms).await {
+        Ok(mut by_comm) => {
+            for req in pending {
+                let result = by_comm.remove(&req.comm).ok_or_else(|| {
+                    anyhow::anyhow!("batch response missing entry for '{}'", req.comm)
+                });
+                let _ = req.reply.send(result);
+            }
+        }
+        Err(err) => {
+            for req in pending {
+                let _ = req
+                    .reply
+                    .send(Err(anyhow::anyhow!("batched tagging request failed: {err}")));
+            }
+        }
     }
+}
 
+/// Issue one chat request tagging every command in `comms` at once, returning
+/// a map from (normalized) command name to its tags.
+async fn fetch_batch_tags(comms: &[String]) -> anyhow::Result<HashMap<String, Vec<String>>> {
+    let list = comms
+        .iter()
+        .map(|c| format!("- {c}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let prompt = format!(
+        "Commands:\n{list}\nFor each command, return a JSON array of 1-3 lowercase snake_case tags describing what it typically does (e.g., \"package_manager\", \"network_tool\"). Respond with a single JSON object mapping each command name, exactly as given, to its tag array, and nothing else."
+    );
+
+    let req_body = ChatRequest {
+        model: TAG_MODEL.clone(),
+        messages: vec![
+            ChatMessage {
+                role: "system".to_string(),
+                content: "You classify Linux command names into semantic categories. Respond with a JSON object mapping each input command to an array of lowercase snake_case tags. Output JSON only, no prose, no code fences, no explanations.".to_string(),
+            },
+            ChatMessage {
+                role: "user".to_string(),
+                content: prompt,
+            },
+        ],
+        temperature: Some(0.0),
+        max_tokens: Some(48 * comms.len().max(1) as u32),
+        stream: Some(false),
+        grammar: TAG_GRAMMAR_ENABLED.then_some(TAG_BATCH_GRAMMAR),
+    };
+
+    let client = &*TAG_HTTP_CLIENT;
+    let mut request = client.post(TAG_ENDPOINT.as_str()).json(&req_body);
+    if let Some(key) = TAG_API_KEY.as_ref() {
+        request = request.bearer_auth(key);
+    }
+
+    let response = request
+        .send()
+        .await
+        .map_err(|err| anyhow::anyhow!("LLM batch tagging request failed: {err}"))?;
+
+    if !response.status().is_success() {
+        return Err(anyhow::anyhow!(
+            "LLM batch tagging request returned status {}",
+            response.status()
+        ));
+    }
+
+    let body = response
+        .text()
+        .await
+        .map_err(|err| anyhow::anyhow!("Failed to read LLM batch response body: {err}"))?;
+
+    parse_batch_tag_response(&body)
+}
+
+/// The existing single-command path, used when a command's batched request
+/// fails or doesn't come back with an entry for it.
+async fn single_tag_request(comm: &str, metrics: &Metrics) -> anyhow::Result<Vec<String>> {
     let prompt = format!(
         "Command: {comm}\nReturn a JSON array of 1-3 lowercase snake_case tags describing what this command typically does (e.g., \"package_manager\", \"network_tool\"). Respond with JSON only and nothing else."
     );
@@ -114,12 +291,13 @@ pub async fn llm_tags_for_comm(
         temperature: Some(0.0),
         max_tokens: Some(48),
         stream: Some(false),
+        grammar: TAG_GRAMMAR_ENABLED.then_some(TAG_GRAMMAR),
     };
 
     let client = &*TAG_HTTP_CLIENT;
     let mut request = client.post(TAG_ENDPOINT.as_str()).json(&req_body);
-    if let Some(key) = TAG_API_KEY.as_ref() {
-        request = request.bearer_auth(key);
+    if let Some(api_key) = TAG_API_KEY.as_ref() {
+        request = request.bearer_auth(api_key);
     }
 
     let response = match request.send().await {
@@ -152,122 +330,60 @@ pub async fn llm_tags_for_comm(
         }
     };
 
-    let tags = match parse_tag_response(&body) {
-        Ok(tags) => tags,
+    match parse_tag_response(&body) {
+        Ok(tags) => Ok(tags),
         Err(err) => {
             metrics
                 .tag_failures_total
                 .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
-            return Err(err);
+            Err(err)
         }
-    };
-
-    // Insert into cache
-    log::debug!("[tagger] cached tags for '{comm}': {tags:?}");
-    insert_tag_cache(key.clone(), tags.clone());
-
-    Ok(tags)
+    }
 }
 
-/// Load the tag cache from disk at startup.
-pub fn load_tag_cache_from_disk() {
-    if !Path::new(TAG_CACHE_PATH).exists() {
-        log::info!("[tagger] No tag cache file found, starting fresh");
-        return;
-    }
-    let file = match File::open(TAG_CACHE_PATH) {
-        Ok(f) => f,
-        Err(e) => {
-            log::warn!("[tagger] Failed to open tag cache: {e}");
-            return;
-        }
-    };
-    let mut reader: Box<dyn Read> = if TAG_CACHE_USE_GZIP {
-        Box::new(GzDecoder::new(file))
-    } else {
-        Box::new(file)
-    };
-    let mut data = String::new();
-    if let Err(e) = reader.read_to_string(&mut data) {
-        log::warn!("[tagger] Failed to read tag cache: {e}");
-        return;
+/// The backend in effect for this process: Redis when `LLM_TAG_CACHE_REDIS_URL`
+/// is set and reachable, falling back to the always-available in-memory cache.
+async fn active_tag_cache() -> Arc<dyn TagCacheBackend> {
+    if let Some(existing) = REMOTE_TAG_CACHE.get() {
+        return existing.clone();
     }
-    match serde_json::from_str::<std::collections::HashMap<String, Vec<String>>>(&data) {
-        Ok(map) => {
-            TAG_CACHE.clear();
-            for (k, v) in map {
-                TAG_CACHE.insert(k, v);
+    if let Ok(url) = env::var("LLM_TAG_CACHE_REDIS_URL") {
+        match RedisTagCache::connect(&url, "linnix:tag:").await {
+            Ok(backend) => {
+                let backend = Arc::new(backend);
+                let _ = REMOTE_TAG_CACHE.set(backend.clone());
+                log::info!("[tagger] using Redis tag-cache backend at {url}");
+                return backend;
+            }
+            Err(err) => {
+                log::warn!(
+                    "[tagger] failed to connect Redis tag cache ({err}), using in-memory backend"
+                );
             }
-            log::info!(
-                "[tagger] Loaded tag cache from disk ({} entries)",
-                TAG_CACHE.len()
-            );
-        }
-        Err(e) => {
-            log::warn!("[tagger] Failed to parse tag cache: {e}");
         }
     }
+    TAG_CACHE.clone()
+}
+
+/// Load the tag cache from disk at startup.
+pub fn load_tag_cache_from_disk() {
+    TAG_CACHE.load_from_disk();
 }
 
 /// Save the tag cache to disk.
 pub fn save_tag_cache_to_disk() {
-    if !TAG_CACHE_DIRTY.swap(false, Ordering::Relaxed) {
-        return;
-    }
-    let map: std::collections::HashMap<_, _> = TAG_CACHE
-        .iter()
-        .map(|kv| (kv.key().clone(), kv.value().clone()))
-        .collect();
-    let json = match serde_json::to_string_pretty(&map) {
-        Ok(j) => j,
-        Err(e) => {
-            log::warn!("[tagger] Failed to serialize tag cache: {e}");
-            return;
-        }
-    };
-    let path = tag_cache_path();
-    let tmp_path = path.with_extension("tmp");
-    let file = match std::fs::OpenOptions::new()
-        .write(true)
-        .create(true)
-        .truncate(true)
-        .mode(0o600) // Set permissions to 0600
-        .open(&tmp_path)
-    {
-        Ok(f) => f,
-        Err(e) => {
-            log::warn!("[tagger] Failed to create tag cache file: {e}");
-            return;
-        }
-    };
-    let result = if TAG_CACHE_USE_GZIP {
-        let mut encoder = GzEncoder::new(file, Compression::default());
-        encoder
-            .write_all(json.as_bytes())
-            .and_then(|_| encoder.finish().map(|_| ()))
-    } else {
-        let mut writer = file;
-        writer.write_all(json.as_bytes())
-    };
-    if let Err(e) = result {
-        log::warn!("[tagger] Failed to write tag cache: {e}");
-        return;
-    }
-    if let Err(e) = std::fs::rename(&tmp_path, &path) {
-        log::warn!("[tagger] Failed to rename tag cache file: {e}");
-    }
+    TAG_CACHE.save_to_disk();
 }
 
-// Use this function to insert tags, enforcing the size and dirty flag
-pub fn insert_tag_cache(comm: String, tags: Vec<String>) {
-    if TAG_CACHE.len() >= TAG_CACHE_MAX_ENTRIES && !TAG_CACHE.contains_key(&comm) {
-        log::warn!(
-            "[tagger] Tag cache full ({TAG_CACHE_MAX_ENTRIES} entries), skipping insert for '{comm}'"
-        );
-        return;
-    }
-    TAG_CACHE.insert(comm, tags);
-    TAG_CACHE_DIRTY.store(true, Ordering::Relaxed);
+/// Insert `tags` for `comm`, expiring them after `ttl` if given.
+pub async fn insert_tag_cache(comm: String, tags: Vec<String>, ttl: Option<Duration>) {
+    active_tag_cache().await.insert(comm, tags, ttl).await;
+}
+
+/// Evict every cached command matching a glob `pattern` (e.g. `"apt*"`),
+/// forcing re-tagging on next use. Returns the number of entries removed.
+pub async fn invalidate_tag_cache(pattern: &str) -> usize {
+    active_tag_cache().await.invalidate(pattern).await
 }
 
 fn tag_cache_path() -> std::path::PathBuf {
@@ -301,6 +417,28 @@ fn parse_tag_response(body: &str) -> anyhow::Result<Vec<String>> {
     parse_tag_content(body)
 }
 
+fn parse_batch_tag_response(body: &str) -> anyhow::Result<HashMap<String, Vec<String>>> {
+    let content = if let Ok(chat_resp) = serde_json::from_str::<ChatResponse>(body) {
+        chat_resp
+            .choices
+            .first()
+            .map(|choice| choice.message.content.clone())
+            .unwrap_or_else(|| body.to_string())
+    } else {
+        body.to_string()
+    };
+    let trimmed = content.trim();
+    let normalized = trimmed
+        .strip_prefix("```json")
+        .or_else(|| trimmed.strip_prefix("```"))
+        .map(|s| s.trim())
+        .and_then(|s| s.strip_suffix("```").map(|s| s.trim()))
+        .unwrap_or(trimmed);
+    serde_json::from_str(normalized).map_err(|e| {
+        anyhow::anyhow!("Failed to parse batched LLM tags JSON: {e}\nLLM output: {normalized}")
+    })
+}
+
 fn parse_tag_content(content: &str) -> anyhow::Result<Vec<String>> {
     let trimmed = content.trim();
     let normalized = trimmed
@@ -313,3 +451,44 @@ fn parse_tag_content(content: &str) -> anyhow::Result<Vec<String>> {
         anyhow::anyhow!("Failed to parse LLM tags JSON: {e}\nLLM output: {normalized}")
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tag_grammar_is_valid_gbnf() {
+        assert!(TAG_GRAMMAR.contains("root ::="));
+        assert!(TAG_GRAMMAR.contains("tag ::="));
+        assert!(TAG_GRAMMAR.contains("space ::="));
+    }
+
+    #[test]
+    fn test_parse_tag_content_accepts_grammar_shaped_output() {
+        let tags = parse_tag_content("[\"package_manager\", \"network_tool\"]").unwrap();
+        assert_eq!(tags, vec!["package_manager", "network_tool"]);
+    }
+
+    #[test]
+    fn test_tag_batch_grammar_is_valid_gbnf() {
+        assert!(TAG_BATCH_GRAMMAR.contains("root ::="));
+        assert!(TAG_BATCH_GRAMMAR.contains("entry ::="));
+        assert!(TAG_BATCH_GRAMMAR.contains("tags ::="));
+    }
+
+    #[test]
+    fn test_parse_batch_tag_response_splits_by_command() {
+        let body = r#"{"apt-get": ["package_manager"], "curl": ["network_tool", "cli"]}"#;
+        let parsed = parse_batch_tag_response(body).unwrap();
+        assert_eq!(parsed.get("apt-get").unwrap(), &vec!["package_manager".to_string()]);
+        assert_eq!(
+            parsed.get("curl").unwrap(),
+            &vec!["network_tool".to_string(), "cli".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_parse_batch_tag_response_rejects_malformed_json() {
+        assert!(parse_batch_tag_response("not json").is_err());
+    }
+}
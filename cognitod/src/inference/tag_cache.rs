@@ -0,0 +1,379 @@
+//! Pluggable tag-cache backend
+//!
+//! The command tagger (`summarizer::llm_tags_for_comm`) caches LLM
+//! classifications behind a `TagCacheBackend` trait instead of a single
+//! hardcoded process-local map, so multiple `cognitod` instances can share
+//! one another's classifications via Redis instead of each paying the LLM
+//! round trip. [`InMemoryTagCache`] (a `DashMap` plus gzipped JSON file) is
+//! the default; [`RedisTagCache`] is an alternative backed by a `bb8`
+//! connection pool. Entries carry an optional expiry so stale
+//! classifications eventually get re-queried, and `invalidate` accepts glob
+//! patterns (e.g. `"apt*"`) so operators can force re-tagging of a command
+//! family.
+
+use async_trait::async_trait;
+use chrono::{NaiveDateTime, Utc};
+use dashmap::DashMap;
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+use std::io::{Read, Write};
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+/// A cached tag classification, with an optional expiry.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+struct TagEntry {
+    tags: Vec<String>,
+    expires_at: Option<NaiveDateTime>,
+    /// When this entry was last (re)classified, in epoch milliseconds. Used
+    /// by the gossip subsystem to decide which of two conflicting copies wins.
+    updated_at: i64,
+}
+
+impl TagEntry {
+    fn new(tags: Vec<String>, ttl: Option<Duration>) -> Self {
+        let expires_at = ttl.and_then(|ttl| {
+            chrono::Duration::from_std(ttl)
+                .ok()
+                .map(|ttl| Utc::now().naive_utc() + ttl)
+        });
+        Self {
+            tags,
+            expires_at,
+            updated_at: Utc::now().timestamp_millis(),
+        }
+    }
+
+    fn is_expired(&self) -> bool {
+        matches!(self.expires_at, Some(at) if Utc::now().naive_utc() >= at)
+    }
+}
+
+/// Storage backend for learned command -> tag classifications.
+#[async_trait]
+pub trait TagCacheBackend: Send + Sync {
+    /// Look up `key`; an expired entry is treated as a miss.
+    async fn get(&self, key: &str) -> Option<Vec<String>>;
+    /// Insert `tags` for `key`, expiring after `ttl` if given.
+    async fn insert(&self, key: String, tags: Vec<String>, ttl: Option<Duration>);
+    /// Evict every key matching a glob `pattern` (e.g. `"apt*"`). Returns the count removed.
+    async fn invalidate(&self, pattern: &str) -> usize;
+    /// Number of entries currently stored (including not-yet-swept expired ones).
+    async fn len(&self) -> usize;
+}
+
+/// Default tag-cache backend: a process-local `DashMap` persisted to a
+/// gzipped JSON file, matching the original single-instance implementation.
+pub struct InMemoryTagCache {
+    entries: DashMap<String, TagEntry>,
+    dirty: AtomicBool,
+    max_entries: usize,
+    path: PathBuf,
+    use_gzip: bool,
+}
+
+impl InMemoryTagCache {
+    pub fn new(path: PathBuf, max_entries: usize, use_gzip: bool) -> Self {
+        Self {
+            entries: DashMap::new(),
+            dirty: AtomicBool::new(false),
+            max_entries,
+            path,
+            use_gzip,
+        }
+    }
+
+    /// Load the tag cache from disk at startup.
+    pub fn load_from_disk(&self) {
+        if !self.path.exists() {
+            log::info!("[tagger] No tag cache file found, starting fresh");
+            return;
+        }
+        let file = match std::fs::File::open(&self.path) {
+            Ok(f) => f,
+            Err(e) => {
+                log::warn!("[tagger] Failed to open tag cache: {e}");
+                return;
+            }
+        };
+        let mut reader: Box<dyn Read> = if self.use_gzip {
+            Box::new(GzDecoder::new(file))
+        } else {
+            Box::new(file)
+        };
+        let mut data = String::new();
+        if let Err(e) = reader.read_to_string(&mut data) {
+            log::warn!("[tagger] Failed to read tag cache: {e}");
+            return;
+        }
+        match serde_json::from_str::<std::collections::HashMap<String, TagEntry>>(&data) {
+            Ok(map) => {
+                self.entries.clear();
+                for (k, v) in map {
+                    self.entries.insert(k, v);
+                }
+                log::info!(
+                    "[tagger] Loaded tag cache from disk ({} entries)",
+                    self.entries.len()
+                );
+            }
+            Err(e) => {
+                log::warn!("[tagger] Failed to parse tag cache: {e}");
+            }
+        }
+    }
+
+    /// Save the tag cache to disk if it has changed since the last save.
+    pub fn save_to_disk(&self) {
+        if !self.dirty.swap(false, Ordering::Relaxed) {
+            return;
+        }
+        let map: std::collections::HashMap<_, _> = self
+            .entries
+            .iter()
+            .map(|kv| (kv.key().clone(), kv.value().clone()))
+            .collect();
+        let json = match serde_json::to_string_pretty(&map) {
+            Ok(j) => j,
+            Err(e) => {
+                log::warn!("[tagger] Failed to serialize tag cache: {e}");
+                return;
+            }
+        };
+        let tmp_path = self.path.with_extension("tmp");
+        let file = match std::fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(&tmp_path)
+        {
+            Ok(f) => f,
+            Err(e) => {
+                log::warn!("[tagger] Failed to create tag cache file: {e}");
+                return;
+            }
+        };
+        let result = if self.use_gzip {
+            let mut encoder = GzEncoder::new(file, Compression::default());
+            encoder
+                .write_all(json.as_bytes())
+                .and_then(|_| encoder.finish().map(|_| ()))
+        } else {
+            let mut writer = file;
+            writer.write_all(json.as_bytes())
+        };
+        if let Err(e) = result {
+            log::warn!("[tagger] Failed to write tag cache: {e}");
+            return;
+        }
+        if let Err(e) = std::fs::rename(&tmp_path, &self.path) {
+            log::warn!("[tagger] Failed to rename tag cache file: {e}");
+        }
+    }
+
+    /// Every non-expired entry, for the gossip subsystem's digests and pushes.
+    pub(crate) fn snapshot(&self) -> Vec<(String, Vec<String>, i64)> {
+        self.entries
+            .iter()
+            .filter(|kv| !kv.value().is_expired())
+            .map(|kv| (kv.key().clone(), kv.value().tags.clone(), kv.value().updated_at))
+            .collect()
+    }
+}
+
+#[async_trait]
+impl TagCacheBackend for InMemoryTagCache {
+    async fn get(&self, key: &str) -> Option<Vec<String>> {
+        let entry = self.entries.get(key)?;
+        if entry.is_expired() {
+            drop(entry);
+            self.entries.remove(key);
+            return None;
+        }
+        Some(entry.tags.clone())
+    }
+
+    async fn insert(&self, key: String, tags: Vec<String>, ttl: Option<Duration>) {
+        if self.entries.len() >= self.max_entries && !self.entries.contains_key(&key) {
+            log::warn!(
+                "[tagger] Tag cache full ({} entries), skipping insert for '{key}'",
+                self.max_entries
+            );
+            return;
+        }
+        self.entries.insert(key, TagEntry::new(tags, ttl));
+        self.dirty.store(true, Ordering::Relaxed);
+    }
+
+    async fn invalidate(&self, pattern: &str) -> usize {
+        let to_remove: Vec<String> = self
+            .entries
+            .iter()
+            .map(|kv| kv.key().clone())
+            .filter(|key| glob_match(pattern, key))
+            .collect();
+        for key in &to_remove {
+            self.entries.remove(key);
+        }
+        if !to_remove.is_empty() {
+            self.dirty.store(true, Ordering::Relaxed);
+        }
+        to_remove.len()
+    }
+
+    async fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+/// Redis-backed tag cache, shared across `cognitod` instances.
+pub struct RedisTagCache {
+    pool: bb8::Pool<bb8_redis::RedisConnectionManager>,
+    key_prefix: String,
+}
+
+impl RedisTagCache {
+    pub async fn connect(redis_url: &str, key_prefix: impl Into<String>) -> anyhow::Result<Self> {
+        let manager = bb8_redis::RedisConnectionManager::new(redis_url)?;
+        let pool = bb8::Pool::builder().build(manager).await?;
+        Ok(Self {
+            pool,
+            key_prefix: key_prefix.into(),
+        })
+    }
+
+    fn full_key(&self, key: &str) -> String {
+        format!("{}{}", self.key_prefix, key)
+    }
+}
+
+#[async_trait]
+impl TagCacheBackend for RedisTagCache {
+    async fn get(&self, key: &str) -> Option<Vec<String>> {
+        use redis::AsyncCommands;
+        let mut conn = self.pool.get().await.ok()?;
+        let raw: Option<String> = conn.get(self.full_key(key)).await.ok()?;
+        serde_json::from_str(&raw?).ok()
+    }
+
+    async fn insert(&self, key: String, tags: Vec<String>, ttl: Option<Duration>) {
+        use redis::AsyncCommands;
+        let Ok(mut conn) = self.pool.get().await else {
+            log::warn!("[tagger] redis pool exhausted, dropping insert for '{key}'");
+            return;
+        };
+        let Ok(raw) = serde_json::to_string(&tags) else {
+            return;
+        };
+        let full_key = self.full_key(&key);
+        let result: redis::RedisResult<()> = match ttl {
+            Some(ttl) => conn.set_ex(&full_key, raw, ttl.as_secs().max(1)).await,
+            None => conn.set(&full_key, raw).await,
+        };
+        if let Err(err) = result {
+            log::warn!("[tagger] redis insert failed for '{key}': {err}");
+        }
+    }
+
+    async fn invalidate(&self, pattern: &str) -> usize {
+        use redis::AsyncCommands;
+        let Ok(mut conn) = self.pool.get().await else {
+            return 0;
+        };
+        let full_pattern = self.full_key(pattern);
+        let keys: Vec<String> = conn.keys(&full_pattern).await.unwrap_or_default();
+        if keys.is_empty() {
+            return 0;
+        }
+        let _: redis::RedisResult<()> = conn.del(&keys).await;
+        keys.len()
+    }
+
+    async fn len(&self) -> usize {
+        use redis::AsyncCommands;
+        let Ok(mut conn) = self.pool.get().await else {
+            return 0;
+        };
+        let full_pattern = self.full_key("*");
+        conn.keys::<_, Vec<String>>(&full_pattern)
+            .await
+            .map(|k| k.len())
+            .unwrap_or(0)
+    }
+}
+
+/// Match `text` against a simple glob `pattern` (only `*` is special, matching any run of characters).
+fn glob_match(pattern: &str, text: &str) -> bool {
+    fn inner(pattern: &[u8], text: &[u8]) -> bool {
+        match pattern.first() {
+            None => text.is_empty(),
+            Some(b'*') => {
+                inner(&pattern[1..], text)
+                    || (!text.is_empty() && inner(pattern, &text[1..]))
+            }
+            Some(&c) => !text.is_empty() && text[0] == c && inner(&pattern[1..], &text[1..]),
+        }
+    }
+    inner(pattern.as_bytes(), text.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glob_matches_prefix_wildcard() {
+        assert!(glob_match("apt*", "apt-get"));
+        assert!(glob_match("apt*", "apt"));
+        assert!(!glob_match("apt*", "snap"));
+    }
+
+    #[test]
+    fn glob_matches_exact_and_any() {
+        assert!(glob_match("curl", "curl"));
+        assert!(!glob_match("curl", "curlx"));
+        assert!(glob_match("*", "anything"));
+    }
+
+    #[tokio::test]
+    async fn in_memory_cache_round_trips_and_invalidates() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = InMemoryTagCache::new(dir.path().join("cache.json.gz"), 10, true);
+
+        cache
+            .insert("apt-get".to_string(), vec!["package_manager".to_string()], None)
+            .await;
+        cache
+            .insert("curl".to_string(), vec!["network_tool".to_string()], None)
+            .await;
+
+        assert_eq!(
+            cache.get("apt-get").await,
+            Some(vec!["package_manager".to_string()])
+        );
+        assert_eq!(cache.len().await, 2);
+
+        let removed = cache.invalidate("apt*").await;
+        assert_eq!(removed, 1);
+        assert_eq!(cache.get("apt-get").await, None);
+        assert_eq!(cache.get("curl").await, Some(vec!["network_tool".to_string()]));
+    }
+
+    #[tokio::test]
+    async fn in_memory_cache_treats_expired_entries_as_misses() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = InMemoryTagCache::new(dir.path().join("cache.json.gz"), 10, true);
+
+        cache
+            .insert(
+                "apt-get".to_string(),
+                vec!["package_manager".to_string()],
+                Some(Duration::from_secs(0)),
+            )
+            .await;
+
+        assert_eq!(cache.get("apt-get").await, None);
+    }
+}
@@ -1,38 +1,116 @@
 use crate::handler::local_ilm::schema::Insight;
+use crate::telemetry::TelemetryRegistry;
 use log::warn;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
 use std::fs::OpenOptions;
 use std::io::Write;
 use std::path::{Path, PathBuf};
-use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast;
+
+/// Backlog for the admin API's insight stream; a slow subscriber drops the
+/// oldest unread insights rather than blocking `record_with_transcript`.
+const INSIGHT_STREAM_CAPACITY: usize = 256;
+
+/// How much of the reasoning that produced an insight gets persisted
+/// alongside it. Gated by `ReasonerConfig::audit_level` so production can
+/// stay lean while a debugging session captures everything needed to
+/// reconstruct why an enforcement action was proposed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditLevel {
+    /// No transcript is built or persisted.
+    #[default]
+    Off,
+    /// Persist retry counts and which tools ran, but not the prompt/response bodies.
+    DecisionsOnly,
+    /// Persist the full prompts, KB snippets, tool I/O, and raw model response.
+    FullTranscript,
+}
+
+/// One tool invocation made while investigating a window, recorded so the
+/// eventual insight (and any enforcement action it triggers) can be traced
+/// back to the evidence that produced it.
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolCallRecord {
+    pub tool: String,
+    pub pid: i32,
+    pub output: String,
+}
+
+/// The verbose, prompt/response-level detail `AuditLevel::FullTranscript`
+/// adds on top of `InsightTranscript`'s always-present retry/tool-call fields.
+#[derive(Debug, Clone, Serialize)]
+pub struct FullTranscript {
+    pub system_prompt: String,
+    pub telemetry_prompt: String,
+    pub kb_snippets: Vec<String>,
+    pub raw_response: String,
+}
+
+/// Audit trail for one insight: what tools were consulted, how many
+/// validation/fix retries it took, and (at `AuditLevel::FullTranscript`) the
+/// exact prompts and raw model reply behind it.
+#[derive(Debug, Clone, Serialize)]
+pub struct InsightTranscript {
+    pub fix_retries: u32,
+    pub tool_calls: Vec<ToolCallRecord>,
+    pub full: Option<FullTranscript>,
+}
 
 #[derive(Debug, Clone, Serialize)]
 pub struct InsightRecord {
+    pub id: u64,
     pub timestamp: u64,
     pub insight: Insight,
+    pub transcript: Option<InsightTranscript>,
 }
 
 pub struct InsightStore {
     inner: Mutex<VecDeque<InsightRecord>>,
     capacity: usize,
     file_path: Option<PathBuf>,
+    telemetry: Option<Arc<TelemetryRegistry>>,
+    next_id: AtomicU64,
+    stream: broadcast::Sender<InsightRecord>,
 }
 
 impl InsightStore {
     pub fn new(capacity: usize, file_path: Option<PathBuf>) -> Self {
+        let (stream, _) = broadcast::channel(INSIGHT_STREAM_CAPACITY);
         Self {
             inner: Mutex::new(VecDeque::with_capacity(capacity)),
             capacity,
             file_path,
+            telemetry: None,
+            next_id: AtomicU64::new(1),
+            stream,
         }
     }
 
+    /// Attach a telemetry registry; every recorded insight also increments
+    /// its class counter there, so operators can alert on insight rates.
+    pub fn with_telemetry(mut self, telemetry: Arc<TelemetryRegistry>) -> Self {
+        self.telemetry = Some(telemetry);
+        self
+    }
+
     pub fn record(&self, insight: Insight) {
+        self.record_with_transcript(insight, None);
+    }
+
+    /// Like [`record`](Self::record), but also attaches the audit transcript
+    /// (if any -- `None` when `AuditLevel::Off`) that produced this insight,
+    /// so enforcement decisions stay explainable after the fact.
+    pub fn record_with_transcript(&self, insight: Insight, transcript: Option<InsightTranscript>) {
         let record = InsightRecord {
+            id: self.next_id.fetch_add(1, Ordering::Relaxed),
             timestamp: current_epoch_secs(),
             insight: insight.clone(),
+            transcript,
         };
 
         {
@@ -43,6 +121,15 @@ impl InsightStore {
             inner.push_back(record.clone());
         }
 
+        // `send` fails only when there are no receivers (e.g. no admin API
+        // stream subscribed), the common case; a slow subscriber lags and
+        // loses its oldest unread records instead of failing the send.
+        let _ = self.stream.send(record.clone());
+
+        if let Some(telemetry) = &self.telemetry {
+            telemetry.record_insight(insight.class.as_str());
+        }
+
         if let Some(path) = &self.file_path {
             if let Err(err) = ensure_parent(path) {
                 warn!("[insights] failed to create directory {:?}: {}", path, err);
@@ -58,6 +145,13 @@ impl InsightStore {
         }
     }
 
+    /// The attached telemetry registry, if any, for subsystems that need to
+    /// record series beyond what `InsightStore` itself tracks (e.g. ILM
+    /// chat-latency histograms in `handler::local_ilm::run_worker`).
+    pub fn telemetry(&self) -> Option<&Arc<TelemetryRegistry>> {
+        self.telemetry.as_ref()
+    }
+
     pub fn recent(&self, limit: usize) -> Vec<InsightRecord> {
         if limit == 0 {
             return Vec::new();
@@ -65,6 +159,20 @@ impl InsightStore {
         let inner = self.inner.lock().unwrap();
         inner.iter().rev().take(limit).cloned().collect::<Vec<_>>()
     }
+
+    /// Look up one retained record (insight plus its audit transcript, if
+    /// any) by the id `record_with_transcript` assigned it.
+    pub fn get(&self, id: u64) -> Option<InsightRecord> {
+        let inner = self.inner.lock().unwrap();
+        inner.iter().find(|record| record.id == id).cloned()
+    }
+
+    /// Subscribe to insights as they're recorded, for the admin API's SSE
+    /// stream. A subscriber that falls behind `INSIGHT_STREAM_CAPACITY`
+    /// records loses the oldest ones rather than blocking `record`.
+    pub fn subscribe(&self) -> broadcast::Receiver<InsightRecord> {
+        self.stream.subscribe()
+    }
 }
 
 fn current_epoch_secs() -> u64 {
@@ -133,4 +241,27 @@ mod tests {
             "serialized insight should land in file"
         );
     }
+
+    #[test]
+    fn looks_up_records_by_id() {
+        let store = InsightStore::new(4, None);
+        store.record(sample_insight(0));
+        store.record(sample_insight(1));
+
+        let recent = store.recent(10);
+        let first_id = recent.last().unwrap().id;
+        assert_eq!(store.get(first_id).unwrap().insight.why, "why-0");
+        assert!(store.get(9999).is_none());
+    }
+
+    #[tokio::test]
+    async fn subscribers_receive_newly_recorded_insights() {
+        let store = InsightStore::new(4, None);
+        let mut rx = store.subscribe();
+
+        store.record(sample_insight(7));
+
+        let received = rx.recv().await.unwrap();
+        assert_eq!(received.insight.why, "why-7");
+    }
 }
@@ -1,3 +1,4 @@
+use futures_util::StreamExt;
 use log::{debug, info, warn};
 use reqwest::Client;
 use serde::Deserialize;
@@ -42,6 +43,11 @@ pub struct K8sMetadata {
 pub struct K8sContext {
     // Map from Container ID (stripped) to Metadata
     container_map: RwLock<HashMap<String, K8sMetadata>>,
+    // `resourceVersion` the last list or watch event was observed at; `None`
+    // until a list has succeeded, and cleared again whenever the watch
+    // cursor goes stale (410 Gone / a `Status` error with reason `Expired`)
+    // so the next watch attempt re-lists instead of resuming from it.
+    resource_version: RwLock<Option<String>>,
     client: Client,
     api_url: String,
     token: String,
@@ -84,6 +90,7 @@ impl K8sContext {
 
         Some(Arc::new(Self {
             container_map: RwLock::new(HashMap::new()),
+            resource_version: RwLock::new(None),
             client,
             api_url,
             token,
@@ -91,18 +98,158 @@ impl K8sContext {
         }))
     }
 
+    /// Prefer the incremental Kubernetes Watch API (sub-second updates,
+    /// far less apiserver load); if a watch connection can't even be
+    /// established (e.g. the initial list fails), fall back to the old
+    /// 30s full-list poll for that cycle and try watching again next time.
     pub fn start_watcher(self: Arc<Self>) {
         tokio::spawn(async move {
             info!("[k8s] starting pod watcher for node {}", self.node_name);
             loop {
-                if let Err(e) = self.refresh_pods().await {
-                    warn!("[k8s] failed to refresh pods: {}", e);
+                if let Err(e) = self.watch_pods().await {
+                    warn!(
+                        "[k8s] watch connection could not be established, falling back to polling: {}",
+                        e
+                    );
+                    if let Err(e) = self.refresh_pods().await {
+                        warn!("[k8s] failed to refresh pods: {}", e);
+                    }
+                    sleep(Duration::from_secs(30)).await;
                 }
-                sleep(Duration::from_secs(30)).await;
             }
         });
     }
 
+    /// Reflector loop: one full list to seed `container_map` and capture
+    /// `resourceVersion`, then stream incremental events from it forever,
+    /// transparently re-listing whenever the watch cursor goes stale or the
+    /// stream drops. Only returns `Err` when the seeding list itself fails,
+    /// so `start_watcher` can fall back to polling.
+    async fn watch_pods(&self) -> Result<(), Box<dyn std::error::Error>> {
+        loop {
+            self.refresh_pods().await?;
+            if let Err(e) = self.stream_watch_events().await {
+                warn!("[k8s] watch stream ended, re-listing: {}", e);
+                sleep(Duration::from_millis(500)).await;
+            }
+        }
+    }
+
+    /// Open the streaming `watch=true` request at the current
+    /// `resourceVersion` and apply events as they arrive. Returns (without
+    /// erroring the caller out of the reflector loop) whenever the cursor
+    /// needs to be refreshed from a fresh list: the stream ended, a `410
+    /// Gone` response, or a `Status` error event with reason `Expired`.
+    async fn stream_watch_events(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let rv = self
+            .resource_version
+            .read()
+            .unwrap()
+            .clone()
+            .ok_or("no resourceVersion to watch from")?;
+        let url = format!(
+            "{}/api/v1/pods?fieldSelector=spec.nodeName={}&watch=true&resourceVersion={}&allowWatchBookmarks=true",
+            self.api_url, self.node_name, rv
+        );
+        let resp = self
+            .client
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", self.token))
+            .send()
+            .await?;
+
+        if resp.status() == reqwest::StatusCode::GONE {
+            *self.resource_version.write().unwrap() = None;
+            return Err("watch cursor expired (410 Gone)".into());
+        }
+        if !resp.status().is_success() {
+            return Err(format!("watch API error: {}", resp.status()).into());
+        }
+
+        info!("[k8s] watch connection established at resourceVersion={}", rv);
+
+        let mut buf = String::new();
+        let mut stream = resp.bytes_stream();
+        while let Some(chunk) = stream.next().await {
+            buf.push_str(&String::from_utf8_lossy(&chunk?));
+            while let Some(pos) = buf.find('\n') {
+                let line: String = buf.drain(..=pos).collect();
+                let line = line.trim();
+                if line.is_empty() {
+                    continue;
+                }
+                if self.apply_watch_line(line) {
+                    *self.resource_version.write().unwrap() = None;
+                    return Err("watch cursor expired".into());
+                }
+            }
+        }
+
+        Err("watch stream closed by server".into())
+    }
+
+    /// Apply one newline-delimited `WatchEvent` JSON line to `container_map`.
+    /// Returns `true` if the cursor is stale (reason `Expired`) and the
+    /// caller must discard it and restart from a fresh list.
+    fn apply_watch_line(&self, line: &str) -> bool {
+        let event: WatchEvent = match serde_json::from_str(line) {
+            Ok(event) => event,
+            Err(e) => {
+                warn!("[k8s] failed to parse watch event: {}", e);
+                return false;
+            }
+        };
+
+        if event.event_type == "ERROR" {
+            let status: WatchStatus = serde_json::from_value(event.object.clone()).unwrap_or_default();
+            if status.reason.as_deref() == Some("Expired") {
+                warn!("[k8s] watch cursor expired, restarting from a fresh list");
+                return true;
+            }
+            warn!("[k8s] watch error event: {:?}", status.reason);
+            return false;
+        }
+
+        // Every event -- including BOOKMARK, which carries only this -- advances the cursor.
+        if let Some(rv) = event
+            .object
+            .get("metadata")
+            .and_then(|m| m.get("resourceVersion"))
+            .and_then(|v| v.as_str())
+        {
+            *self.resource_version.write().unwrap() = Some(rv.to_string());
+        }
+
+        if event.event_type == "BOOKMARK" {
+            return false;
+        }
+
+        let pod: Pod = match serde_json::from_value(event.object) {
+            Ok(pod) => pod,
+            Err(e) => {
+                warn!("[k8s] failed to parse watched pod: {}", e);
+                return false;
+            }
+        };
+
+        match event.event_type.as_str() {
+            "ADDED" | "MODIFIED" => {
+                let mut map = self.container_map.write().unwrap();
+                for (id, meta) in container_entries_for_pod(&pod) {
+                    map.insert(id, meta);
+                }
+            }
+            "DELETED" => {
+                let mut map = self.container_map.write().unwrap();
+                for (id, _) in container_entries_for_pod(&pod) {
+                    map.remove(&id);
+                }
+            }
+            other => debug!("[k8s] ignoring unhandled watch event type {}", other),
+        }
+        false
+    }
+
     async fn refresh_pods(&self) -> Result<(), Box<dyn std::error::Error>> {
         let url = format!(
             "{}/api/v1/pods?fieldSelector=spec.nodeName={}",
@@ -120,66 +267,14 @@ impl K8sContext {
         }
 
         let pod_list: PodList = resp.json().await?;
-        let mut new_map = HashMap::new();
-
-        for pod in pod_list.items {
-            let ns = pod.metadata.namespace.unwrap_or_default();
-            let pod_name = pod.metadata.name.unwrap_or_default();
-
-            let (owner_kind, owner_name) = if let Some(owners) = pod.metadata.owner_references {
-                if let Some(owner) = owners.first() {
-                    (Some(owner.kind.clone()), Some(owner.name.clone()))
-                } else {
-                    (None, None)
-                }
-            } else {
-                (None, None)
-            };
-
-            let (priority, slo_tier) = if let Some(labels) = &pod.metadata.labels {
-                let p = labels
-                    .get("linnix.dev/priority")
-                    .map(|s| Priority::from(s.as_str()))
-                    .unwrap_or_default();
-                let s = labels.get("linnix.dev/slo-tier").cloned();
-                (p, s)
-            } else {
-                (Priority::default(), None)
-            };
+        if let Some(rv) = pod_list.metadata.resource_version {
+            *self.resource_version.write().unwrap() = Some(rv);
+        }
 
-            if let Some(statuses) = pod.status.container_statuses {
-                for status in statuses {
-                    if let Some(container_id) = status.container_id {
-                        // container_id is usually "containerd://<id>" or "docker://<id>"
-                        if let Some(stripped) = container_id.strip_prefix("containerd://") {
-                            new_map.insert(
-                                stripped.to_string(),
-                                K8sMetadata {
-                                    pod_name: pod_name.clone(),
-                                    namespace: ns.clone(),
-                                    container_name: status.name.clone(),
-                                    owner_kind: owner_kind.clone(),
-                                    owner_name: owner_name.clone(),
-                                    priority: priority.clone(),
-                                    slo_tier: slo_tier.clone(),
-                                },
-                            );
-                        } else if let Some(stripped) = container_id.strip_prefix("docker://") {
-                            new_map.insert(
-                                stripped.to_string(),
-                                K8sMetadata {
-                                    pod_name: pod_name.clone(),
-                                    namespace: ns.clone(),
-                                    container_name: status.name.clone(),
-                                    owner_kind: owner_kind.clone(),
-                                    owner_name: owner_name.clone(),
-                                    priority: priority.clone(),
-                                    slo_tier: slo_tier.clone(),
-                                },
-                            );
-                        }
-                    }
-                }
+        let mut new_map = HashMap::new();
+        for pod in &pod_list.items {
+            for (id, meta) in container_entries_for_pod(pod) {
+                new_map.insert(id, meta);
             }
         }
 
@@ -194,43 +289,128 @@ impl K8sContext {
         Ok(())
     }
 
+    /// Read `/proc/<pid>/cgroup` and match any recognized container ID
+    /// against `container_map`. Scans every path segment of every line
+    /// (not just the last segment), so it copes with the cgroup v2 unified
+    /// hierarchy, cgroup v1's per-controller lines, and the systemd driver's
+    /// `<scope>:<controller>:<id>` layout alike.
     pub fn get_metadata_for_pid(&self, pid: u32) -> Option<K8sMetadata> {
-        // Read /proc/<pid>/cgroup
         let content = std::fs::read_to_string(format!("/proc/{}/cgroup", pid)).ok()?;
+        let map = self.container_map.read().unwrap();
+        candidate_container_ids(&content)
+            .into_iter()
+            .find_map(|id| map.get(&id).cloned())
+    }
+}
 
-        // Parse cgroup to find container ID
-        // Format: 0::/kubepods.slice/kubepods-burstable.slice/kubepods-burstable-pod<uid>.slice/cri-containerd-<id>.scope
-        // Or similar. We look for a 64-char hex string.
-
-        for line in content.lines() {
-            // Simple heuristic: look for last part that looks like a container ID
-            if let Some(last_part) = line.split('/').next_back() {
-                // Remove .scope suffix if present
-                let clean = last_part.trim_end_matches(".scope");
-                // Remove prefix like "cri-containerd-" or "docker-"
-                let id = if let Some(idx) = clean.rfind('-') {
-                    &clean[idx + 1..]
-                } else {
-                    clean
-                };
-
-                if id.len() == 64 {
-                    let map = self.container_map.read().unwrap();
-                    if let Some(meta) = map.get(id) {
-                        return Some(meta.clone());
-                    }
-                }
+fn is_hex64(s: &str) -> bool {
+    s.len() == 64 && s.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// Pull a container ID out of one cgroup path segment, covering both the
+/// bare-directory form cgroup v1 uses (`/docker/<id>`, `/kubepods/.../<id>`)
+/// and the `<runtime>-<id>.scope` form the systemd cgroup driver uses for
+/// containerd, CRI-O, and Docker alike (`cri-containerd-<id>.scope`,
+/// `crio-<id>.scope`, `docker-<id>.scope`).
+fn extract_candidate_id(segment: &str) -> Option<String> {
+    let cleaned = segment.trim_end_matches(".scope");
+    if is_hex64(cleaned) {
+        return Some(cleaned.to_string());
+    }
+    let candidate = cleaned.rsplit('-').next()?;
+    is_hex64(candidate).then(|| candidate.to_string())
+}
+
+/// Every plausible container ID across all lines of a `/proc/<pid>/cgroup`
+/// file, scanning both `/`- and `:`-separated segments so the systemd
+/// driver's `kubepods-burstable-pod<uid>.slice:cri-containerd:<id>` form is
+/// covered alongside the usual path-based layouts.
+fn candidate_container_ids(cgroup_content: &str) -> Vec<String> {
+    cgroup_content
+        .lines()
+        .flat_map(|line| line.split(['/', ':']))
+        .filter_map(extract_candidate_id)
+        .collect()
+}
+
+/// Runtime URI prefixes a pod status's `containerID` field may carry.
+const RUNTIME_PREFIXES: [&str; 3] = ["containerd://", "docker://", "cri-o://"];
+
+fn strip_runtime_prefix(container_id: &str) -> Option<&str> {
+    RUNTIME_PREFIXES
+        .iter()
+        .find_map(|prefix| container_id.strip_prefix(prefix))
+}
+
+/// Derive the `(container_id, metadata)` entries a pod contributes to
+/// `container_map`, stripping the runtime prefix off each `containerID`.
+/// Shared by the full-list path (`refresh_pods`) and the incremental watch
+/// path (`apply_watch_line`) so both stay in sync.
+fn container_entries_for_pod(pod: &Pod) -> Vec<(String, K8sMetadata)> {
+    let ns = pod.metadata.namespace.clone().unwrap_or_default();
+    let pod_name = pod.metadata.name.clone().unwrap_or_default();
+
+    let (owner_kind, owner_name) = match pod.metadata.owner_references.as_ref().and_then(|o| o.first())
+    {
+        Some(owner) => (Some(owner.kind.clone()), Some(owner.name.clone())),
+        None => (None, None),
+    };
+
+    let (priority, slo_tier) = if let Some(labels) = &pod.metadata.labels {
+        let p = labels
+            .get("linnix.dev/priority")
+            .map(|s| Priority::from(s.as_str()))
+            .unwrap_or_default();
+        let s = labels.get("linnix.dev/slo-tier").cloned();
+        (p, s)
+    } else {
+        (Priority::default(), None)
+    };
+
+    let mut entries = Vec::new();
+    if let Some(statuses) = &pod.status.container_statuses {
+        for status in statuses {
+            let Some(container_id) = &status.container_id else {
+                continue;
+            };
+            if let Some(id) = strip_runtime_prefix(container_id) {
+                entries.push((
+                    id.to_string(),
+                    K8sMetadata {
+                        pod_name: pod_name.clone(),
+                        namespace: ns.clone(),
+                        container_name: status.name.clone(),
+                        owner_kind: owner_kind.clone(),
+                        owner_name: owner_name.clone(),
+                        priority: priority.clone(),
+                        slo_tier: slo_tier.clone(),
+                    },
+                ));
             }
         }
-        None
+    }
+    entries
+}
+
+impl crate::metadata_provider::MetadataProvider for K8sContext {
+    fn metadata_for_pid(&self, pid: u32) -> Option<crate::metadata_provider::WorkloadMetadata> {
+        self.get_metadata_for_pid(pid).map(Into::into)
     }
 }
 
 #[derive(Deserialize)]
 struct PodList {
+    #[serde(default)]
+    metadata: ListMeta,
     items: Vec<Pod>,
 }
 
+#[derive(Deserialize, Default)]
+struct ListMeta {
+    #[serde(rename = "resourceVersion")]
+    resource_version: Option<String>,
+}
+
 #[derive(Deserialize)]
 struct Pod {
     metadata: PodMetadata,
@@ -265,6 +445,24 @@ struct ContainerStatus {
     container_id: Option<String>,
 }
 
+/// One line of a Kubernetes Watch API response: `type` is `ADDED` /
+/// `MODIFIED` / `DELETED` / `BOOKMARK` / `ERROR`; `object` is a `Pod` for the
+/// first four and a `Status` object for `ERROR`, hence the untyped value.
+#[derive(Deserialize)]
+struct WatchEvent {
+    #[serde(rename = "type")]
+    event_type: String,
+    object: serde_json::Value,
+}
+
+/// The subset of a Kubernetes `Status` object we care about: whether the
+/// watch was torn down because the resourceVersion aged out of the apiserver
+/// cache (`reason: "Expired"`), which means the cursor is unusable.
+#[derive(Deserialize, Default)]
+struct WatchStatus {
+    reason: Option<String>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -291,4 +489,150 @@ mod tests {
         );
         assert_eq!(serde_json::to_string(&Priority::Low).unwrap(), "\"low\"");
     }
+
+    fn sample_pod(container_id: &str) -> Pod {
+        serde_json::from_value(serde_json::json!({
+            "metadata": { "name": "web-0", "namespace": "default" },
+            "status": { "containerStatuses": [
+                { "name": "web", "containerID": format!("containerd://{container_id}") }
+            ] }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn container_entries_strip_runtime_prefix() {
+        let pod = sample_pod("abc123");
+        let entries = container_entries_for_pod(&pod);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].0, "abc123");
+        assert_eq!(entries[0].1.pod_name, "web-0");
+    }
+
+    #[test]
+    fn container_entries_strip_crio_prefix() {
+        let pod: Pod = serde_json::from_value(serde_json::json!({
+            "metadata": { "name": "web-0", "namespace": "default" },
+            "status": { "containerStatuses": [
+                { "name": "web", "containerID": "cri-o://abc123" }
+            ] }
+        }))
+        .unwrap();
+        let entries = container_entries_for_pod(&pod);
+        assert_eq!(entries[0].0, "abc123");
+    }
+
+    const SAMPLE_ID: &str = "a1b2c3d4e5f60718293a4b5c6d7e8f90a1b2c3d4e5f60718293a4b5c6d7e8f90";
+
+    #[test]
+    fn extracts_candidate_id_from_systemd_containerd_scope() {
+        let line = format!(
+            "0::/kubepods.slice/kubepods-burstable.slice/kubepods-burstable-poduid.slice/cri-containerd-{SAMPLE_ID}.scope"
+        );
+        assert_eq!(candidate_container_ids(&line), vec![SAMPLE_ID.to_string()]);
+    }
+
+    #[test]
+    fn extracts_candidate_id_from_crio_scope() {
+        let line = format!("0::/machine.slice/crio-{SAMPLE_ID}.scope");
+        assert_eq!(candidate_container_ids(&line), vec![SAMPLE_ID.to_string()]);
+    }
+
+    #[test]
+    fn extracts_candidate_id_from_cgroup_v1_bare_directory() {
+        let line = format!("4:memory:/docker/{SAMPLE_ID}");
+        assert_eq!(candidate_container_ids(&line), vec![SAMPLE_ID.to_string()]);
+
+        let line = format!("1:name=systemd:/kubepods/besteffort/poduid/{SAMPLE_ID}");
+        assert_eq!(candidate_container_ids(&line), vec![SAMPLE_ID.to_string()]);
+    }
+
+    #[test]
+    fn extracts_candidate_id_from_colon_separated_systemd_form() {
+        let line = format!("0::kubepods-burstable-poduid.slice:cri-containerd:{SAMPLE_ID}");
+        assert_eq!(candidate_container_ids(&line), vec![SAMPLE_ID.to_string()]);
+    }
+
+    #[test]
+    fn ignores_segments_that_are_not_container_ids() {
+        assert!(candidate_container_ids("0::/user.slice/user-1000.slice").is_empty());
+    }
+
+    #[test]
+    fn apply_watch_line_detects_expired_cursor() {
+        let ctx = K8sContext {
+            container_map: RwLock::new(HashMap::new()),
+            resource_version: RwLock::new(Some("100".to_string())),
+            client: Client::new(),
+            api_url: String::new(),
+            token: String::new(),
+            node_name: "n".to_string(),
+        };
+
+        let not_expired = ctx.apply_watch_line(
+            r#"{"type":"ERROR","object":{"reason":"InternalError"}}"#,
+        );
+        assert!(!not_expired);
+        assert_eq!(*ctx.resource_version.read().unwrap(), Some("100".to_string()));
+
+        let expired = ctx.apply_watch_line(r#"{"type":"ERROR","object":{"reason":"Expired"}}"#);
+        assert!(expired);
+    }
+
+    #[test]
+    fn apply_watch_line_upserts_and_removes_containers() {
+        let ctx = K8sContext {
+            container_map: RwLock::new(HashMap::new()),
+            resource_version: RwLock::new(Some("1".to_string())),
+            client: Client::new(),
+            api_url: String::new(),
+            token: String::new(),
+            node_name: "n".to_string(),
+        };
+
+        let added = serde_json::json!({
+            "type": "ADDED",
+            "object": {
+                "metadata": { "name": "web-0", "namespace": "default", "resourceVersion": "2" },
+                "status": { "containerStatuses": [
+                    { "name": "web", "containerID": "containerd://abc123" }
+                ] }
+            }
+        });
+        assert!(!ctx.apply_watch_line(&added.to_string()));
+        assert!(ctx.container_map.read().unwrap().contains_key("abc123"));
+        assert_eq!(*ctx.resource_version.read().unwrap(), Some("2".to_string()));
+
+        let deleted = serde_json::json!({
+            "type": "DELETED",
+            "object": {
+                "metadata": { "name": "web-0", "namespace": "default", "resourceVersion": "3" },
+                "status": { "containerStatuses": [
+                    { "name": "web", "containerID": "containerd://abc123" }
+                ] }
+            }
+        });
+        assert!(!ctx.apply_watch_line(&deleted.to_string()));
+        assert!(!ctx.container_map.read().unwrap().contains_key("abc123"));
+        assert_eq!(*ctx.resource_version.read().unwrap(), Some("3".to_string()));
+    }
+
+    #[test]
+    fn apply_watch_line_advances_version_on_bookmark() {
+        let ctx = K8sContext {
+            container_map: RwLock::new(HashMap::new()),
+            resource_version: RwLock::new(Some("1".to_string())),
+            client: Client::new(),
+            api_url: String::new(),
+            token: String::new(),
+            node_name: "n".to_string(),
+        };
+
+        let bookmark = serde_json::json!({
+            "type": "BOOKMARK",
+            "object": { "metadata": { "resourceVersion": "42" } }
+        });
+        assert!(!ctx.apply_watch_line(&bookmark.to_string()));
+        assert_eq!(*ctx.resource_version.read().unwrap(), Some("42".to_string()));
+    }
 }
@@ -1,9 +1,27 @@
 // let_chains stabilized in Rust 1.82 (Jan 2025)
 // Both local stable and Docker stable support it without feature flags
 
+pub mod admin_api;
+pub mod capabilities;
+pub mod circuit_breaker;
+pub mod collectors;
 pub mod config;
+pub mod consul;
+pub mod enforcement;
+pub mod export;
+pub mod handler;
+pub mod history;
+pub mod incidents;
+pub mod inference;
+pub mod insights;
+pub mod k8s;
+pub mod metadata_provider;
 pub mod metrics;
+pub mod telemetry;
+pub mod triage;
+pub mod types;
 pub mod ui;
+mod utils;
 
 pub use config::{Config, LoggingConfig, OfflineGuard, OutputConfig, RuntimeConfig};
 pub use metrics::Metrics;
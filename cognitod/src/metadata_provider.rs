@@ -0,0 +1,101 @@
+//! Generic pid -> workload-metadata enrichment, decoupled from any one
+//! orchestrator. [`crate::k8s::K8sContext`] and [`crate::consul::ConsulContext`]
+//! both implement [`MetadataProvider`] so the daemon can run with zero, one,
+//! or several providers layered -- whichever one knows about a given pid wins.
+
+use crate::k8s::{K8sMetadata, Priority};
+
+/// Workload identity and priority/SLO enrichment for one pid, independent of
+/// whether it came from a Kubernetes apiserver, a Consul catalog, or (in
+/// future) some other discovery source.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkloadMetadata {
+    pub workload_name: String,
+    pub namespace: Option<String>,
+    pub container_name: Option<String>,
+    pub owner_kind: Option<String>,
+    pub owner_name: Option<String>,
+    pub priority: Priority,
+    pub slo_tier: Option<String>,
+}
+
+impl From<K8sMetadata> for WorkloadMetadata {
+    fn from(meta: K8sMetadata) -> Self {
+        Self {
+            workload_name: meta.pod_name,
+            namespace: Some(meta.namespace),
+            container_name: Some(meta.container_name),
+            owner_kind: meta.owner_kind,
+            owner_name: meta.owner_name,
+            priority: meta.priority,
+            slo_tier: meta.slo_tier,
+        }
+    }
+}
+
+/// Resolves a pid to the workload metadata (priority, SLO tier, owning
+/// controller) describing it, regardless of discovery mechanism.
+pub trait MetadataProvider: Send + Sync {
+    fn metadata_for_pid(&self, pid: u32) -> Option<WorkloadMetadata>;
+}
+
+/// Runs each provider in order and returns the first match, so the daemon
+/// can layer zero, one, or several providers (e.g. Kubernetes + Consul)
+/// without callers caring which one actually resolved a given pid.
+pub struct LayeredMetadataProvider {
+    providers: Vec<Box<dyn MetadataProvider>>,
+}
+
+impl LayeredMetadataProvider {
+    pub fn new(providers: Vec<Box<dyn MetadataProvider>>) -> Self {
+        Self { providers }
+    }
+}
+
+impl MetadataProvider for LayeredMetadataProvider {
+    fn metadata_for_pid(&self, pid: u32) -> Option<WorkloadMetadata> {
+        self.providers.iter().find_map(|p| p.metadata_for_pid(pid))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Always(WorkloadMetadata);
+    impl MetadataProvider for Always {
+        fn metadata_for_pid(&self, _pid: u32) -> Option<WorkloadMetadata> {
+            Some(self.0.clone())
+        }
+    }
+    struct Never;
+    impl MetadataProvider for Never {
+        fn metadata_for_pid(&self, _pid: u32) -> Option<WorkloadMetadata> {
+            None
+        }
+    }
+
+    fn sample() -> WorkloadMetadata {
+        WorkloadMetadata {
+            workload_name: "web".to_string(),
+            namespace: None,
+            container_name: None,
+            owner_kind: None,
+            owner_name: None,
+            priority: Priority::High,
+            slo_tier: None,
+        }
+    }
+
+    #[test]
+    fn layered_provider_returns_first_match() {
+        let layered = LayeredMetadataProvider::new(vec![Box::new(Never), Box::new(Always(sample()))]);
+        assert_eq!(layered.metadata_for_pid(1).unwrap().workload_name, "web");
+    }
+
+    #[test]
+    fn layered_provider_returns_none_when_all_miss() {
+        let layered = LayeredMetadataProvider::new(vec![Box::new(Never)]);
+        assert!(layered.metadata_for_pid(1).is_none());
+    }
+}
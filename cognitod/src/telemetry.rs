@@ -0,0 +1,280 @@
+//! Prometheus-style metrics exporter for PSI stalls, insights, and incidents
+//!
+//! Complements `crate::metrics::Metrics` (events/sec, ILM health) with data
+//! that previously only showed up in logs: per-pod/per-resource PSI stall
+//! deltas from `PsiMonitor`, insight counts by class from `InsightStore`, and
+//! circuit-breaker incident counts/recovery times. `TelemetryRegistry` holds
+//! shared gauge/counter state that those subsystems update directly, and
+//! `render_prometheus` formats it in the Prometheus text exposition format
+//! for an HTTP `/metrics` endpoint.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::sync::{Arc, Mutex};
+
+use axum::extract::State;
+use axum::response::IntoResponse;
+
+/// (namespace, pod, resource) -> most recent stall delta, in microseconds.
+type PsiKey = (String, String, String);
+
+/// Upper bounds (inclusive) of each latency bucket, in milliseconds. The
+/// final `+Inf` bucket is implicit, matching Prometheus histogram semantics.
+const ILM_LATENCY_BUCKETS_MS: &[u64] = &[10, 25, 50, 100, 250, 500, 1_000, 2_500, 5_000, 10_000, 30_000];
+
+/// Per-bucket observation counts (same length as [`ILM_LATENCY_BUCKETS_MS`]
+/// plus one for `+Inf`), plus the running sum/count for the `_sum`/`_count`
+/// series every Prometheus histogram exposes.
+#[derive(Default)]
+struct LatencyHistogram {
+    bucket_counts: Vec<u64>,
+    sum_ms: u64,
+    count: u64,
+}
+
+impl LatencyHistogram {
+    fn observe(&mut self, duration_ms: u64) {
+        if self.bucket_counts.is_empty() {
+            self.bucket_counts = vec![0; ILM_LATENCY_BUCKETS_MS.len() + 1];
+        }
+        let bucket = ILM_LATENCY_BUCKETS_MS
+            .iter()
+            .position(|&le| duration_ms <= le)
+            .unwrap_or(ILM_LATENCY_BUCKETS_MS.len());
+        self.bucket_counts[bucket] += 1;
+        self.sum_ms += duration_ms;
+        self.count += 1;
+    }
+
+    /// Cumulative count at or below `ILM_LATENCY_BUCKETS_MS[idx]` (or `+Inf` past the end).
+    fn cumulative(&self, idx: usize) -> u64 {
+        self.bucket_counts[..=idx].iter().sum()
+    }
+}
+
+#[derive(Default)]
+pub struct TelemetryRegistry {
+    psi_stall_delta_us: Mutex<HashMap<PsiKey, u64>>,
+    insights_by_class: Mutex<HashMap<String, u64>>,
+    incidents_by_event_type: Mutex<HashMap<String, u64>>,
+    /// event_type -> (sum of recovery_time_ms, sample count), for an average gauge.
+    incident_recovery_ms: Mutex<HashMap<String, (u64, u64)>>,
+    /// (phase, outcome) -> latency histogram, e.g. ("primary", "success").
+    ilm_chat_latency_ms: Mutex<HashMap<(String, String), LatencyHistogram>>,
+}
+
+impl TelemetryRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_psi_stall_delta(&self, namespace: &str, pod: &str, resource: &str, delta_us: u64) {
+        self.psi_stall_delta_us.lock().unwrap().insert(
+            (namespace.to_string(), pod.to_string(), resource.to_string()),
+            delta_us,
+        );
+    }
+
+    pub fn record_insight(&self, class: &str) {
+        *self
+            .insights_by_class
+            .lock()
+            .unwrap()
+            .entry(class.to_string())
+            .or_insert(0) += 1;
+    }
+
+    pub fn record_incident(&self, event_type: &str, recovery_time_ms: Option<u64>) {
+        *self
+            .incidents_by_event_type
+            .lock()
+            .unwrap()
+            .entry(event_type.to_string())
+            .or_insert(0) += 1;
+
+        if let Some(ms) = recovery_time_ms {
+            let mut recovery = self.incident_recovery_ms.lock().unwrap();
+            let entry = recovery.entry(event_type.to_string()).or_insert((0, 0));
+            entry.0 += ms;
+            entry.1 += 1;
+        }
+    }
+
+    /// Record one `client.chat` call's latency, tagged by reasoning phase
+    /// (`primary`, `fixup`, `followup`) and outcome (`success`, `timeout`,
+    /// `schema_error`, `request_failed`), so the histogram doubles as an
+    /// error-rate breakdown alongside p50/p95/p99 latency.
+    pub fn observe_ilm_chat_latency(&self, phase: &str, outcome: &str, duration_ms: u64) {
+        self.ilm_chat_latency_ms
+            .lock()
+            .unwrap()
+            .entry((phase.to_string(), outcome.to_string()))
+            .or_default()
+            .observe(duration_ms);
+    }
+
+    /// Render all tracked series in Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+
+        writeln!(
+            out,
+            "# HELP linnix_psi_stall_delta_microseconds PSI stall delta since last scan, per pod and resource\n\
+             # TYPE linnix_psi_stall_delta_microseconds gauge"
+        )
+        .ok();
+        for ((namespace, pod, resource), delta) in self.psi_stall_delta_us.lock().unwrap().iter() {
+            writeln!(
+                out,
+                "linnix_psi_stall_delta_microseconds{{namespace=\"{namespace}\",pod=\"{pod}\",resource=\"{resource}\"}} {delta}"
+            )
+            .ok();
+        }
+
+        writeln!(
+            out,
+            "# HELP linnix_insights_total Insights recorded, by class\n\
+             # TYPE linnix_insights_total counter"
+        )
+        .ok();
+        for (class, count) in self.insights_by_class.lock().unwrap().iter() {
+            writeln!(out, "linnix_insights_total{{severity=\"{class}\"}} {count}").ok();
+        }
+
+        writeln!(
+            out,
+            "# HELP linnix_incidents_total Circuit-breaker incidents, by event type\n\
+             # TYPE linnix_incidents_total counter"
+        )
+        .ok();
+        for (event_type, count) in self.incidents_by_event_type.lock().unwrap().iter() {
+            writeln!(
+                out,
+                "linnix_incidents_total{{event_type=\"{event_type}\"}} {count}"
+            )
+            .ok();
+        }
+
+        writeln!(
+            out,
+            "# HELP linnix_incident_recovery_time_ms_avg Average recovery time, by event type\n\
+             # TYPE linnix_incident_recovery_time_ms_avg gauge"
+        )
+        .ok();
+        for (event_type, (sum, count)) in self.incident_recovery_ms.lock().unwrap().iter() {
+            if *count > 0 {
+                writeln!(
+                    out,
+                    "linnix_incident_recovery_time_ms_avg{{event_type=\"{event_type}\"}} {:.1}",
+                    *sum as f64 / *count as f64
+                )
+                .ok();
+            }
+        }
+
+        writeln!(
+            out,
+            "# HELP linnix_ilm_chat_latency_ms Local-ILM chat call latency, by phase and outcome\n\
+             # TYPE linnix_ilm_chat_latency_ms histogram"
+        )
+        .ok();
+        for ((phase, outcome), histogram) in self.ilm_chat_latency_ms.lock().unwrap().iter() {
+            for (idx, &le) in ILM_LATENCY_BUCKETS_MS.iter().enumerate() {
+                writeln!(
+                    out,
+                    "linnix_ilm_chat_latency_ms_bucket{{phase=\"{phase}\",outcome=\"{outcome}\",le=\"{le}\"}} {}",
+                    histogram.cumulative(idx)
+                )
+                .ok();
+            }
+            writeln!(
+                out,
+                "linnix_ilm_chat_latency_ms_bucket{{phase=\"{phase}\",outcome=\"{outcome}\",le=\"+Inf\"}} {}",
+                histogram.count
+            )
+            .ok();
+            writeln!(
+                out,
+                "linnix_ilm_chat_latency_ms_sum{{phase=\"{phase}\",outcome=\"{outcome}\"}} {}",
+                histogram.sum_ms
+            )
+            .ok();
+            writeln!(
+                out,
+                "linnix_ilm_chat_latency_ms_count{{phase=\"{phase}\",outcome=\"{outcome}\"}} {}",
+                histogram.count
+            )
+            .ok();
+        }
+
+        out
+    }
+}
+
+/// Serve the registry in Prometheus text exposition format at `GET /metrics`.
+pub async fn metrics_handler(State(registry): State<Arc<TelemetryRegistry>>) -> impl IntoResponse {
+    (
+        [(
+            axum::http::header::CONTENT_TYPE,
+            "text/plain; version=0.0.4",
+        )],
+        registry.render(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_psi_gauge_with_labels() {
+        let registry = TelemetryRegistry::new();
+        registry.set_psi_stall_delta("prod", "api-0", "cpu", 12345);
+
+        let text = registry.render();
+        assert!(text.contains("linnix_psi_stall_delta_microseconds"));
+        assert!(text.contains("namespace=\"prod\""));
+        assert!(text.contains("resource=\"cpu\""));
+        assert!(text.contains("12345"));
+    }
+
+    #[test]
+    fn counts_insights_by_class() {
+        let registry = TelemetryRegistry::new();
+        registry.record_insight("fork_storm");
+        registry.record_insight("fork_storm");
+        registry.record_insight("normal");
+
+        let text = registry.render();
+        assert!(text.contains("linnix_insights_total{severity=\"fork_storm\"} 2"));
+        assert!(text.contains("linnix_insights_total{severity=\"normal\"} 1"));
+    }
+
+    #[test]
+    fn averages_incident_recovery_time() {
+        let registry = TelemetryRegistry::new();
+        registry.record_incident("circuit_breaker_cpu", Some(100));
+        registry.record_incident("circuit_breaker_cpu", Some(300));
+
+        let text = registry.render();
+        assert!(text.contains("linnix_incidents_total{event_type=\"circuit_breaker_cpu\"} 2"));
+        assert!(text.contains(
+            "linnix_incident_recovery_time_ms_avg{event_type=\"circuit_breaker_cpu\"} 200.0"
+        ));
+    }
+
+    #[test]
+    fn buckets_ilm_chat_latency_by_phase_and_outcome() {
+        let registry = TelemetryRegistry::new();
+        registry.observe_ilm_chat_latency("primary", "success", 42);
+        registry.observe_ilm_chat_latency("primary", "success", 400);
+        registry.observe_ilm_chat_latency("primary", "timeout", 30_000);
+
+        let text = registry.render();
+        assert!(text.contains("linnix_ilm_chat_latency_ms_bucket{phase=\"primary\",outcome=\"success\",le=\"50\"} 1"));
+        assert!(text.contains("linnix_ilm_chat_latency_ms_bucket{phase=\"primary\",outcome=\"success\",le=\"500\"} 2"));
+        assert!(text.contains("linnix_ilm_chat_latency_ms_count{phase=\"primary\",outcome=\"success\"} 2"));
+        assert!(text.contains("linnix_ilm_chat_latency_ms_sum{phase=\"primary\",outcome=\"success\"} 442"));
+        assert!(text.contains("linnix_ilm_chat_latency_ms_bucket{phase=\"primary\",outcome=\"timeout\",le=\"+Inf\"} 1"));
+    }
+}
@@ -0,0 +1,598 @@
+//! Declarative triage-rule engine for PSI and system metrics
+//!
+//! Modeled on Fuchsia's "triage" tool: a TOML config defines *metrics*
+//! (arithmetic/boolean expressions over selectors and other metrics) and
+//! *actions* that fire when a boolean metric becomes true. Rules are loaded
+//! once at startup and re-evaluated every scan cycle against a [`Facts`]
+//! snapshot built from `PsiSnapshot` deltas, `SystemSnapshot` fields, and
+//! per-pod history - letting operators tune thresholds without recompiling.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result, anyhow, bail};
+use serde::Deserialize;
+
+/// Rule file as loaded from disk (TOML).
+#[derive(Debug, Clone, Deserialize, Default)]
+pub struct TriageConfig {
+    /// Named metric expressions, e.g. `thrash = "psi_cpu_some_avg10 > 40 and cpu_percent < 50"`.
+    #[serde(default)]
+    pub metrics: HashMap<String, String>,
+    /// Actions that fire when their `trigger` expression evaluates to true.
+    #[serde(default)]
+    pub actions: Vec<ActionConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ActionConfig {
+    pub name: String,
+    pub trigger: String,
+    /// Templated message, e.g. `"{pod} thrashing: psi={psi_cpu_some_avg10}"`.
+    pub why: String,
+    /// Suppress repeat firings of this action for this many seconds.
+    #[serde(default = "default_snooze_secs")]
+    pub snooze_secs: u64,
+    /// Whether this action should additionally propose a circuit-breaker kill.
+    #[serde(default)]
+    pub kill: bool,
+}
+
+fn default_snooze_secs() -> u64 {
+    300
+}
+
+impl TriageConfig {
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("reading triage config {}", path.display()))?;
+        let cfg: TriageConfig =
+            toml::from_str(&text).with_context(|| format!("parsing {}", path.display()))?;
+        Ok(cfg)
+    }
+}
+
+/// A single fact (selector value) available to expressions during one tick.
+#[derive(Debug, Clone, Copy)]
+pub enum Value {
+    Num(f64),
+    Bool(bool),
+}
+
+impl Value {
+    fn as_num(self) -> Result<f64> {
+        match self {
+            Value::Num(n) => Ok(n),
+            Value::Bool(b) => bail!("expected number, found bool {b}"),
+        }
+    }
+
+    fn as_bool(self) -> Result<bool> {
+        match self {
+            Value::Bool(b) => Ok(b),
+            Value::Num(n) => bail!("expected bool, found number {n}"),
+        }
+    }
+}
+
+/// Selector values gathered for a single evaluation tick, keyed by selector
+/// name (e.g. `psi_cpu_some_avg10`, `delta_stall_us`, `cpu_percent`, `load_avg_0`).
+#[derive(Debug, Clone, Default)]
+pub struct Facts {
+    values: HashMap<String, Value>,
+}
+
+impl Facts {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_num(&mut self, selector: &str, value: f64) -> &mut Self {
+        self.values.insert(selector.to_string(), Value::Num(value));
+        self
+    }
+
+    pub fn set_bool(&mut self, selector: &str, value: bool) -> &mut Self {
+        self.values
+            .insert(selector.to_string(), Value::Bool(value));
+        self
+    }
+
+    fn get(&self, selector: &str) -> Option<Value> {
+        self.values.get(selector).copied()
+    }
+
+    /// Rendered for `why` templates: `{selector}` tokens are substituted.
+    fn render(&self, template: &str) -> String {
+        let mut out = String::with_capacity(template.len());
+        let mut chars = template.char_indices().peekable();
+        let bytes = template.as_bytes();
+        let mut i = 0;
+        while i < bytes.len() {
+            if bytes[i] == b'{'
+                && let Some(end) = template[i..].find('}')
+            {
+                let name = &template[i + 1..i + end];
+                match self.get(name) {
+                    Some(Value::Num(n)) => out.push_str(&format!("{n:.1}")),
+                    Some(Value::Bool(b)) => out.push_str(&b.to_string()),
+                    None => out.push_str(&format!("{{{name}}}")),
+                }
+                i += end + 1;
+                continue;
+            }
+            let ch = template[i..].chars().next().unwrap();
+            out.push(ch);
+            i += ch.len_utf8();
+        }
+        let _ = &mut chars;
+        out
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    Num(f64),
+    Selector(String),
+    Metric(String),
+    Unary(UnOp, Box<Expr>),
+    Binary(BinOp, Box<Expr>, Box<Expr>),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum UnOp {
+    Not,
+    Neg,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Gt,
+    Lt,
+    Ge,
+    Le,
+    Eq,
+    And,
+    Or,
+}
+
+/// Recursive-descent parser over a small tokenizer; precedence (low to high):
+/// `or` < `and` < `not` < comparisons < `+ -` < `* /` < unary < atom.
+struct Parser<'a> {
+    tokens: Vec<&'a str>,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(src: &'a str) -> Self {
+        Self {
+            tokens: tokenize(src),
+            pos: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<&str> {
+        self.tokens.get(self.pos).copied()
+    }
+
+    fn bump(&mut self) -> Option<&str> {
+        let tok = self.tokens.get(self.pos).copied();
+        self.pos += 1;
+        tok
+    }
+
+    fn expect(&mut self, expected: &str) -> Result<()> {
+        match self.bump() {
+            Some(tok) if tok == expected => Ok(()),
+            other => bail!("expected '{expected}', found {other:?}"),
+        }
+    }
+
+    fn parse_expr(&mut self) -> Result<Expr> {
+        self.parse_or()
+    }
+
+    fn parse_or(&mut self) -> Result<Expr> {
+        let mut lhs = self.parse_and()?;
+        while self.peek() == Some("or") {
+            self.bump();
+            let rhs = self.parse_and()?;
+            lhs = Expr::Binary(BinOp::Or, Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr> {
+        let mut lhs = self.parse_not()?;
+        while self.peek() == Some("and") {
+            self.bump();
+            let rhs = self.parse_not()?;
+            lhs = Expr::Binary(BinOp::And, Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_not(&mut self) -> Result<Expr> {
+        if self.peek() == Some("not") {
+            self.bump();
+            return Ok(Expr::Unary(UnOp::Not, Box::new(self.parse_not()?)));
+        }
+        self.parse_cmp()
+    }
+
+    fn parse_cmp(&mut self) -> Result<Expr> {
+        let lhs = self.parse_add()?;
+        let op = match self.peek() {
+            Some(">=") => BinOp::Ge,
+            Some("<=") => BinOp::Le,
+            Some(">") => BinOp::Gt,
+            Some("<") => BinOp::Lt,
+            Some("==") => BinOp::Eq,
+            _ => return Ok(lhs),
+        };
+        self.bump();
+        let rhs = self.parse_add()?;
+        Ok(Expr::Binary(op, Box::new(lhs), Box::new(rhs)))
+    }
+
+    fn parse_add(&mut self) -> Result<Expr> {
+        let mut lhs = self.parse_mul()?;
+        loop {
+            let op = match self.peek() {
+                Some("+") => BinOp::Add,
+                Some("-") => BinOp::Sub,
+                _ => break,
+            };
+            self.bump();
+            let rhs = self.parse_mul()?;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_mul(&mut self) -> Result<Expr> {
+        let mut lhs = self.parse_unary()?;
+        loop {
+            let op = match self.peek() {
+                Some("*") => BinOp::Mul,
+                Some("/") => BinOp::Div,
+                _ => break,
+            };
+            self.bump();
+            let rhs = self.parse_unary()?;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr> {
+        if self.peek() == Some("-") {
+            self.bump();
+            return Ok(Expr::Unary(UnOp::Neg, Box::new(self.parse_unary()?)));
+        }
+        self.parse_atom()
+    }
+
+    fn parse_atom(&mut self) -> Result<Expr> {
+        match self.bump() {
+            Some("(") => {
+                let inner = self.parse_expr()?;
+                self.expect(")")?;
+                Ok(inner)
+            }
+            Some(tok) => {
+                if let Ok(n) = tok.parse::<f64>() {
+                    Ok(Expr::Num(n))
+                } else if let Some(metric) = tok.strip_prefix('$') {
+                    Ok(Expr::Metric(metric.to_string()))
+                } else {
+                    Ok(Expr::Selector(tok.to_string()))
+                }
+            }
+            None => bail!("unexpected end of expression"),
+        }
+    }
+}
+
+fn tokenize(src: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let bytes = src.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        let c = bytes[i] as char;
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        if "+-*/()".contains(c) {
+            tokens.push(&src[i..i + 1]);
+            i += 1;
+            continue;
+        }
+        if ">=<=".contains(c) || c == '=' {
+            if i + 1 < bytes.len() && bytes[i + 1] as char == '=' {
+                tokens.push(&src[i..i + 2]);
+                i += 2;
+            } else {
+                tokens.push(&src[i..i + 1]);
+                i += 1;
+            }
+            continue;
+        }
+        let start = i;
+        while i < bytes.len() {
+            let c = bytes[i] as char;
+            if c.is_whitespace() || "+-*/()><=".contains(c) {
+                break;
+            }
+            i += 1;
+        }
+        tokens.push(&src[start..i]);
+    }
+    tokens
+}
+
+fn parse(expr: &str) -> Result<Expr> {
+    let mut parser = Parser::new(expr);
+    let parsed = parser.parse_expr()?;
+    if parser.peek().is_some() {
+        bail!(
+            "trailing tokens after parsing '{expr}': {:?}",
+            &parser.tokens[parser.pos..]
+        );
+    }
+    Ok(parsed)
+}
+
+fn metric_refs(expr: &Expr, out: &mut HashSet<String>) {
+    match expr {
+        Expr::Metric(name) => {
+            out.insert(name.clone());
+        }
+        Expr::Unary(_, inner) => metric_refs(inner, out),
+        Expr::Binary(_, lhs, rhs) => {
+            metric_refs(lhs, out);
+            metric_refs(rhs, out);
+        }
+        Expr::Num(_) | Expr::Selector(_) => {}
+    }
+}
+
+/// Topologically order metrics so dependencies evaluate before dependents.
+/// Returns an error naming the cycle if one is found.
+fn topo_order(parsed: &HashMap<String, Expr>) -> Result<Vec<String>> {
+    let mut order = Vec::with_capacity(parsed.len());
+    let mut visited: HashMap<&str, bool> = HashMap::new(); // false=in-progress, true=done
+
+    fn visit<'a>(
+        name: &'a str,
+        parsed: &'a HashMap<String, Expr>,
+        visited: &mut HashMap<&'a str, bool>,
+        order: &mut Vec<String>,
+    ) -> Result<()> {
+        match visited.get(name) {
+            Some(true) => return Ok(()),
+            Some(false) => bail!("cycle detected in metric dependencies at '{name}'"),
+            None => {}
+        }
+        let Some(expr) = parsed.get(name) else {
+            bail!("metric '{name}' referenced but not defined");
+        };
+        visited.insert(name, false);
+        let mut deps = HashSet::new();
+        metric_refs(expr, &mut deps);
+        for dep in deps {
+            visit(&dep, parsed, visited, order)?;
+        }
+        visited.insert(name, true);
+        order.push(name.to_string());
+        Ok(())
+    }
+
+    for name in parsed.keys() {
+        visit(name, parsed, &mut visited, &mut order)?;
+    }
+    Ok(order)
+}
+
+fn eval(expr: &Expr, facts: &Facts, metrics: &HashMap<String, Value>) -> Result<Value> {
+    match expr {
+        Expr::Num(n) => Ok(Value::Num(*n)),
+        Expr::Selector(name) => facts
+            .get(name)
+            .ok_or_else(|| anyhow!("selector '{name}' not present in facts")),
+        Expr::Metric(name) => metrics
+            .get(name)
+            .copied()
+            .ok_or_else(|| anyhow!("metric '{name}' not yet evaluated")),
+        Expr::Unary(UnOp::Not, inner) => Ok(Value::Bool(!eval(inner, facts, metrics)?.as_bool()?)),
+        Expr::Unary(UnOp::Neg, inner) => Ok(Value::Num(-eval(inner, facts, metrics)?.as_num()?)),
+        Expr::Binary(op, lhs, rhs) => {
+            let lhs = eval(lhs, facts, metrics)?;
+            let rhs = eval(rhs, facts, metrics)?;
+            match op {
+                BinOp::Add => Ok(Value::Num(lhs.as_num()? + rhs.as_num()?)),
+                BinOp::Sub => Ok(Value::Num(lhs.as_num()? - rhs.as_num()?)),
+                BinOp::Mul => Ok(Value::Num(lhs.as_num()? * rhs.as_num()?)),
+                BinOp::Div => Ok(Value::Num(lhs.as_num()? / rhs.as_num()?)),
+                BinOp::Gt => Ok(Value::Bool(lhs.as_num()? > rhs.as_num()?)),
+                BinOp::Lt => Ok(Value::Bool(lhs.as_num()? < rhs.as_num()?)),
+                BinOp::Ge => Ok(Value::Bool(lhs.as_num()? >= rhs.as_num()?)),
+                BinOp::Le => Ok(Value::Bool(lhs.as_num()? <= rhs.as_num()?)),
+                BinOp::Eq => Ok(Value::Bool(lhs.as_num()? == rhs.as_num()?)),
+                BinOp::And => Ok(Value::Bool(lhs.as_bool()? && rhs.as_bool()?)),
+                BinOp::Or => Ok(Value::Bool(lhs.as_bool()? || rhs.as_bool()?)),
+            }
+        }
+    }
+}
+
+/// An action whose trigger evaluated to true this tick, not currently snoozed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Firing {
+    pub action: String,
+    pub why: String,
+    pub kill: bool,
+}
+
+/// Compiled rule set, ready to evaluate once per scan cycle.
+pub struct TriageEngine {
+    metric_order: Vec<String>,
+    parsed_metrics: HashMap<String, Expr>,
+    actions: Vec<(ActionConfig, Expr)>,
+    last_fired: HashMap<String, Instant>,
+}
+
+impl TriageEngine {
+    pub fn new(config: TriageConfig) -> Result<Self> {
+        let mut parsed_metrics = HashMap::with_capacity(config.metrics.len());
+        for (name, expr) in &config.metrics {
+            parsed_metrics.insert(name.clone(), parse(expr)?);
+        }
+        let metric_order = topo_order(&parsed_metrics)?;
+
+        let mut actions = Vec::with_capacity(config.actions.len());
+        for action in config.actions {
+            let trigger = parse(&action.trigger)?;
+            actions.push((action, trigger));
+        }
+
+        Ok(Self {
+            metric_order,
+            parsed_metrics,
+            actions,
+            last_fired: HashMap::new(),
+        })
+    }
+
+    /// Evaluate all metrics (in dependency order) then actions against one
+    /// tick of facts. Snoozed actions are skipped even if their trigger fires.
+    pub fn evaluate(&mut self, facts: &Facts) -> Vec<Firing> {
+        let mut metrics: HashMap<String, Value> = HashMap::new();
+        for name in &self.metric_order {
+            let expr = &self.parsed_metrics[name];
+            match eval(expr, facts, &metrics) {
+                Ok(value) => {
+                    metrics.insert(name.clone(), value);
+                }
+                Err(err) => {
+                    log::warn!("[triage] metric '{name}' failed to evaluate: {err}");
+                }
+            }
+        }
+
+        let now = Instant::now();
+        let mut fired = Vec::new();
+        for (action, trigger) in &self.actions {
+            let triggered = match eval(trigger, facts, &metrics).and_then(Value::as_bool) {
+                Ok(b) => b,
+                Err(err) => {
+                    log::warn!(
+                        "[triage] action '{}' trigger failed to evaluate: {err}",
+                        action.name
+                    );
+                    continue;
+                }
+            };
+            if !triggered {
+                continue;
+            }
+
+            if let Some(last) = self.last_fired.get(&action.name)
+                && now.duration_since(*last) < Duration::from_secs(action.snooze_secs)
+            {
+                continue;
+            }
+
+            self.last_fired.insert(action.name.clone(), now);
+            fired.push(Firing {
+                action: action.name.clone(),
+                why: facts.render(&action.why),
+                kill: action.kill,
+            });
+        }
+        fired
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cfg(metrics: &[(&str, &str)], actions: &[(&str, &str, &str, u64)]) -> TriageConfig {
+        TriageConfig {
+            metrics: metrics
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            actions: actions
+                .iter()
+                .map(|(name, trigger, why, snooze_secs)| ActionConfig {
+                    name: name.to_string(),
+                    trigger: trigger.to_string(),
+                    why: why.to_string(),
+                    snooze_secs: *snooze_secs,
+                    kill: false,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn evaluates_simple_threshold() {
+        let config = cfg(
+            &[("thrash", "psi_cpu_some_avg10 > 40 and cpu_percent < 50")],
+            &[(
+                "cpu_thrash",
+                "$thrash",
+                "{pod} thrashing psi={psi_cpu_some_avg10}",
+                300,
+            )],
+        );
+        let mut engine = TriageEngine::new(config).unwrap();
+
+        let mut facts = Facts::new();
+        facts
+            .set_num("psi_cpu_some_avg10", 55.0)
+            .set_num("cpu_percent", 20.0)
+            .set_num("pod", 0.0); // placeholder selector, rendering checked separately
+
+        let fired = engine.evaluate(&facts);
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].action, "cpu_thrash");
+        assert!(fired[0].why.contains("55.0"));
+    }
+
+    #[test]
+    fn snooze_suppresses_repeat_firing() {
+        let config = cfg(&[], &[("always", "1 > 0", "fired", 3600)]);
+        let mut engine = TriageEngine::new(config).unwrap();
+        let facts = Facts::new();
+
+        assert_eq!(engine.evaluate(&facts).len(), 1);
+        assert_eq!(engine.evaluate(&facts).len(), 0, "should be snoozed");
+    }
+
+    #[test]
+    fn detects_metric_cycles() {
+        let config = cfg(&[("a", "$b + 1"), ("b", "$a + 1")], &[]);
+        let err = TriageEngine::new(config).unwrap_err();
+        assert!(err.to_string().contains("cycle"));
+    }
+
+    #[test]
+    fn metric_dependencies_resolve_in_order() {
+        let config = cfg(
+            &[("base", "10"), ("doubled", "$base * 2")],
+            &[("doubled_high", "$doubled > 15", "doubled={doubled}", 0)],
+        );
+        let mut engine = TriageEngine::new(config).unwrap();
+        let fired = engine.evaluate(&Facts::new());
+        assert_eq!(fired.len(), 1);
+    }
+}
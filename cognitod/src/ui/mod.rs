@@ -2,12 +2,57 @@
 ///
 /// Serves a single-page application with real-time process monitoring,
 /// alert visualization, and system metrics.
-use axum::response::{Html, IntoResponse};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::http::{HeaderMap, HeaderValue, StatusCode, header};
+use axum::response::{Html, IntoResponse, Response};
+use once_cell::sync::Lazy;
+
+pub mod security;
+pub mod stream;
+
+use security::DashboardSecurityConfig;
 
 /// Embedded dashboard HTML
 const DASHBOARD_HTML: &str = include_str!("dashboard.html");
 
-/// Serve the main dashboard page
-pub async fn dashboard_handler() -> impl IntoResponse {
-    Html(DASHBOARD_HTML)
+/// Content-derived `ETag` for `DASHBOARD_HTML`, computed once on first use
+/// and stable for the life of the process since the page is embedded at
+/// compile time.
+static DASHBOARD_ETAG: Lazy<String> = Lazy::new(|| {
+    let mut hasher = DefaultHasher::new();
+    DASHBOARD_HTML.hash(&mut hasher);
+    format!("\"{:016x}\"", hasher.finish())
+});
+
+/// Serve the main dashboard page, 304-ing it via `If-None-Match` against a
+/// content-derived `ETag`, and otherwise tagging it with `Cache-Control:
+/// public, max-age=<configured>` so browsers stop re-fetching it every load.
+pub async fn dashboard_handler(
+    State(config): State<Arc<DashboardSecurityConfig>>,
+    headers: HeaderMap,
+) -> Response {
+    let etag = DASHBOARD_ETAG.as_str();
+    if headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        == Some(etag)
+    {
+        return StatusCode::NOT_MODIFIED.into_response();
+    }
+
+    let mut response = Html(DASHBOARD_HTML).into_response();
+    let response_headers = response.headers_mut();
+    if let Ok(value) = HeaderValue::from_str(etag) {
+        response_headers.insert(header::ETAG, value);
+    }
+    if let Ok(value) =
+        HeaderValue::from_str(&format!("public, max-age={}", config.cache_max_age_secs))
+    {
+        response_headers.insert(header::CACHE_CONTROL, value);
+    }
+    response
 }
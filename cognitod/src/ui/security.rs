@@ -0,0 +1,78 @@
+//! Security/caching headers for dashboard and admin-API responses.
+//!
+//! Modeled on vaultwarden's header-injecting fairing: an `axum` middleware
+//! layer that stamps every response with a configurable CSP, frame/referrer
+//! policy, and `X-Content-Type-Options: nosniff`. Tunable via the daemon
+//! config so deployments that inline extra scripts or front the dashboard
+//! with a reverse proxy can relax or tighten it.
+
+use std::sync::Arc;
+
+use axum::extract::{Request, State};
+use axum::http::{HeaderValue, header};
+use axum::middleware::Next;
+use axum::response::Response;
+
+/// Security/caching policy for the embedded dashboard and its API routes.
+#[derive(Debug, Clone)]
+pub struct DashboardSecurityConfig {
+    pub content_security_policy: String,
+    pub x_frame_options: String,
+    pub referrer_policy: String,
+    /// `Cache-Control: public, max-age=<this>` on the dashboard HTML itself.
+    pub cache_max_age_secs: u64,
+}
+
+impl Default for DashboardSecurityConfig {
+    fn default() -> Self {
+        Self {
+            content_security_policy:
+                "default-src 'self'; style-src 'self' 'unsafe-inline'; img-src 'self' data:"
+                    .to_string(),
+            x_frame_options: "DENY".to_string(),
+            referrer_policy: "no-referrer".to_string(),
+            cache_max_age_secs: 60,
+        }
+    }
+}
+
+/// `axum::middleware::from_fn_with_state` handler -- wrap the dashboard/admin
+/// router with this so every response, success or error, carries the
+/// configured security headers.
+pub async fn security_headers(
+    State(config): State<Arc<DashboardSecurityConfig>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let mut response = next.run(request).await;
+    let headers = response.headers_mut();
+
+    if let Ok(value) = HeaderValue::from_str(&config.content_security_policy) {
+        headers.insert(header::CONTENT_SECURITY_POLICY, value);
+    }
+    if let Ok(value) = HeaderValue::from_str(&config.x_frame_options) {
+        headers.insert(header::X_FRAME_OPTIONS, value);
+    }
+    if let Ok(value) = HeaderValue::from_str(&config.referrer_policy) {
+        headers.insert(header::REFERRER_POLICY, value);
+    }
+    headers.insert(
+        header::X_CONTENT_TYPE_OPTIONS,
+        HeaderValue::from_static("nosniff"),
+    );
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_are_restrictive() {
+        let config = DashboardSecurityConfig::default();
+        assert_eq!(config.x_frame_options, "DENY");
+        assert_eq!(config.referrer_policy, "no-referrer");
+        assert!(config.content_security_policy.contains("default-src 'self'"));
+    }
+}
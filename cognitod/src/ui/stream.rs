@@ -0,0 +1,232 @@
+//! Long-poll / SSE feed for the dashboard.
+//!
+//! `dashboard_handler` only serves the static SPA shell, so today the page
+//! has to poll REST endpoints on its own cadence. This gives it a single
+//! "poll for changes since a version" endpoint instead, modeled on garage's
+//! K2V `PollItem`: the client sends a monotonically increasing cursor, the
+//! handler blocks up to a timeout until a newer snapshot exists, then
+//! returns the delta plus the new cursor -- or the *unchanged* cursor if
+//! nothing happened before the timeout, so the client can always just loop
+//! without treating a timeout as an error. The same feed is also exposed as
+//! an `axum` SSE stream, one event per change batch.
+
+use std::collections::VecDeque;
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use axum::Json;
+use axum::extract::{Query, State};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use futures_util::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Notify;
+use tokio::time::timeout;
+
+use crate::types::{ProcessAlert, SystemSnapshot};
+
+/// How many change batches are retained so a poller that hasn't been seen in
+/// a while can still catch up instead of missing everything before it.
+const DEFAULT_BACKLOG: usize = 256;
+/// How long a long-poll / SSE tick blocks waiting for something new.
+const DEFAULT_LONG_POLL_SECS: u64 = 30;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DashboardEventKind {
+    Alert(ProcessAlert),
+    Snapshot(SystemSnapshot),
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DashboardEvent {
+    pub cursor: u64,
+    #[serde(flatten)]
+    pub kind: DashboardEventKind,
+}
+
+struct Inner {
+    backlog: VecDeque<DashboardEvent>,
+    next_cursor: u64,
+}
+
+/// Shared feed of process alerts and system snapshots. Every push is
+/// assigned a fresh monotonic cursor and wakes anyone blocked in
+/// `poll_since`.
+pub struct DashboardFeed {
+    inner: Mutex<Inner>,
+    capacity: usize,
+    notify: Notify,
+}
+
+impl DashboardFeed {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Mutex::new(Inner {
+                backlog: VecDeque::with_capacity(capacity),
+                next_cursor: 1,
+            }),
+            capacity,
+            notify: Notify::new(),
+        }
+    }
+
+    pub fn push_alert(&self, alert: ProcessAlert) {
+        self.push(DashboardEventKind::Alert(alert));
+    }
+
+    pub fn push_snapshot(&self, snapshot: SystemSnapshot) {
+        self.push(DashboardEventKind::Snapshot(snapshot));
+    }
+
+    fn push(&self, kind: DashboardEventKind) {
+        let mut inner = self.inner.lock().unwrap();
+        let cursor = inner.next_cursor;
+        inner.next_cursor += 1;
+        if inner.backlog.len() == self.capacity {
+            inner.backlog.pop_front();
+        }
+        inner.backlog.push_back(DashboardEvent { cursor, kind });
+        drop(inner);
+        self.notify.notify_waiters();
+    }
+
+    fn current_cursor(&self) -> u64 {
+        self.inner.lock().unwrap().next_cursor.saturating_sub(1)
+    }
+
+    fn events_since(&self, cursor: u64) -> Vec<DashboardEvent> {
+        self.inner
+            .lock()
+            .unwrap()
+            .backlog
+            .iter()
+            .filter(|event| event.cursor > cursor)
+            .cloned()
+            .collect()
+    }
+
+    /// Block up to `wait` for events newer than `cursor`. Always returns a
+    /// cursor: the caller's own one (unchanged) if nothing showed up before
+    /// the timeout, or the latest one alongside the new events otherwise.
+    pub async fn poll_since(&self, cursor: u64, wait: Duration) -> (u64, Vec<DashboardEvent>) {
+        loop {
+            // Subscribe before checking, so a push between the check and the
+            // await can't be missed: `notify_waiters` only wakes futures that
+            // already exist.
+            let notified = self.notify.notified();
+            let events = self.events_since(cursor);
+            if !events.is_empty() {
+                return (self.current_cursor(), events);
+            }
+            tokio::pin!(notified);
+            if timeout(wait, notified).await.is_err() {
+                return (cursor, Vec::new());
+            }
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PollQuery {
+    #[serde(default)]
+    pub cursor: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PollResponse {
+    pub cursor: u64,
+    pub events: Vec<DashboardEvent>,
+}
+
+/// `GET /api/stream?cursor=<n>` -- long-poll for alerts/snapshots newer than
+/// `cursor`, blocking up to 30s. On timeout, returns `cursor` unchanged with
+/// an empty `events` list so the client can immediately re-poll.
+pub async fn poll_dashboard(
+    State(feed): State<Arc<DashboardFeed>>,
+    Query(query): Query<PollQuery>,
+) -> Json<PollResponse> {
+    let (cursor, events) = feed
+        .poll_since(query.cursor, Duration::from_secs(DEFAULT_LONG_POLL_SECS))
+        .await;
+    Json(PollResponse { cursor, events })
+}
+
+/// `GET /api/stream/sse?cursor=<n>` -- the same feed as `text/event-stream`,
+/// one SSE event per non-empty change batch, so new container-attributed
+/// alerts show up on the dashboard within a second of firing.
+pub async fn stream_dashboard(
+    State(feed): State<Arc<DashboardFeed>>,
+    Query(query): Query<PollQuery>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let stream = futures_util::stream::unfold((feed, query.cursor), |(feed, mut cursor)| async move {
+        loop {
+            let (new_cursor, events) = feed
+                .poll_since(cursor, Duration::from_secs(DEFAULT_LONG_POLL_SECS))
+                .await;
+            cursor = new_cursor;
+            if !events.is_empty() {
+                let event = Event::default().json_data(&events).ok()?;
+                return Some((event, (feed, cursor)));
+            }
+        }
+    })
+    .map(Ok);
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+impl Default for DashboardFeed {
+    fn default() -> Self {
+        Self::new(DEFAULT_BACKLOG)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_alert(pid: u32) -> ProcessAlert {
+        ProcessAlert {
+            pid,
+            comm: "test".to_string(),
+            cpu_percent: Some(10.0),
+            mem_percent: None,
+            event_type: 1,
+            reason: "reason".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn poll_since_returns_immediately_when_backlog_has_newer_events() {
+        let feed = DashboardFeed::new(16);
+        feed.push_alert(sample_alert(1));
+        feed.push_alert(sample_alert(2));
+
+        let (cursor, events) = feed.poll_since(0, Duration::from_secs(5)).await;
+        assert_eq!(cursor, 2);
+        assert_eq!(events.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn poll_since_returns_unchanged_cursor_on_timeout() {
+        let feed = DashboardFeed::new(16);
+        let (cursor, events) = feed.poll_since(0, Duration::from_millis(20)).await;
+        assert_eq!(cursor, 0);
+        assert!(events.is_empty());
+    }
+
+    #[tokio::test]
+    async fn poll_since_wakes_up_on_a_concurrent_push() {
+        let feed = Arc::new(DashboardFeed::new(16));
+        let waiter = {
+            let feed = Arc::clone(&feed);
+            tokio::spawn(async move { feed.poll_since(0, Duration::from_secs(5)).await })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        feed.push_alert(sample_alert(7));
+
+        let (cursor, events) = waiter.await.unwrap();
+        assert_eq!(cursor, 1);
+        assert_eq!(events.len(), 1);
+    }
+}
@@ -10,7 +10,9 @@
 //!
 //! - "some" = at least one task stalled (maps to tail latency/P99)
 //! - "full" = ALL runnable tasks stalled (maps to throughput loss)
-//! - "avg10" = 10-second average (best for circuit breaker responsiveness)
+//! - "avg10"/"avg60"/"avg300" = 10s/60s/300s averages; comparing them tells
+//!   a transient spike (high avg10, low avg300) from sustained saturation
+//!   (all three windows high) apart, which a single-window view can't.
 
 use std::fs;
 use std::io;
@@ -18,29 +20,41 @@ use std::path::Path;
 
 use std::env;
 
+use serde::Serialize;
+
 fn get_psi_path(metric: &str) -> String {
     env::var(format!("LINNIX_PSI_{}_PATH", metric.to_uppercase()))
         .unwrap_or_else(|_| format!("/proc/pressure/{}", metric))
 }
 
+/// One `some`/`full` line of a PSI file, exactly as the kernel reports it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct PressureRecord {
+    pub avg10: f32,
+    pub avg60: f32,
+    pub avg300: f32,
+    /// Cumulative stalled time in microseconds since boot.
+    pub total: u64,
+}
+
+/// A resource's full PSI picture. `full` is `None` for CPU, which the
+/// kernel only reports "some" pressure for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct Pressure {
+    pub some: PressureRecord,
+    pub full: Option<PressureRecord>,
+}
+
 /// PSI metrics for the entire system
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize)]
 #[allow(dead_code)]
 pub struct PsiMetrics {
-    /// CPU pressure: % time at least one task stalled waiting for CPU (10s avg)
-    pub cpu_some_avg10: f32,
-
-    /// Memory pressure: % time at least one task stalled waiting for memory
-    pub memory_some_avg10: f32,
-
-    /// Memory thrashing: % time ALL tasks stalled (complete memory pressure)
-    pub memory_full_avg10: f32,
-
-    /// I/O pressure: % time at least one task stalled on I/O
-    pub io_some_avg10: f32,
-
-    /// I/O saturation: % time ALL tasks stalled on I/O
-    pub io_full_avg10: f32,
+    /// CPU pressure ("some" only -- the kernel has no "full" cpu.pressure line).
+    pub cpu: Pressure,
+    /// Memory pressure: "some" (tail latency) and "full" (complete thrashing).
+    pub memory: Pressure,
+    /// I/O pressure: "some" (tail latency) and "full" (complete stall).
+    pub io: Pressure,
 }
 
 #[allow(dead_code)]
@@ -55,31 +69,21 @@ impl PsiMetrics {
         let cpu_path = get_psi_path("cpu");
         if let Ok(content) = fs::read_to_string(&cpu_path) {
             log::info!("Reading PSI from {}: {}", cpu_path, content.trim());
-            if let Some(value) = parse_avg10(&content, "some") {
-                metrics.cpu_some_avg10 = value;
-            }
+            metrics.cpu.some = parse_pressure_record(&content, "some").unwrap_or_default();
         } else {
             log::warn!("Failed to read PSI from {}", cpu_path);
         }
 
         // Memory pressure (has both "some" and "full")
         if let Ok(content) = fs::read_to_string(get_psi_path("memory")) {
-            if let Some(value) = parse_avg10(&content, "some") {
-                metrics.memory_some_avg10 = value;
-            }
-            if let Some(value) = parse_avg10(&content, "full") {
-                metrics.memory_full_avg10 = value;
-            }
+            metrics.memory.some = parse_pressure_record(&content, "some").unwrap_or_default();
+            metrics.memory.full = parse_pressure_record(&content, "full");
         }
 
         // I/O pressure (has both "some" and "full")
         if let Ok(content) = fs::read_to_string(get_psi_path("io")) {
-            if let Some(value) = parse_avg10(&content, "some") {
-                metrics.io_some_avg10 = value;
-            }
-            if let Some(value) = parse_avg10(&content, "full") {
-                metrics.io_full_avg10 = value;
-            }
+            metrics.io.some = parse_pressure_record(&content, "some").unwrap_or_default();
+            metrics.io.full = parse_pressure_record(&content, "full");
         }
 
         Ok(metrics)
@@ -94,29 +98,96 @@ impl PsiMetrics {
     pub fn summary(&self) -> String {
         format!(
             "cpu={:.1}% mem_some={:.1}% mem_full={:.1}% io_some={:.1}% io_full={:.1}%",
-            self.cpu_some_avg10,
-            self.memory_some_avg10,
-            self.memory_full_avg10,
-            self.io_some_avg10,
-            self.io_full_avg10
+            self.cpu_some_avg10(),
+            self.memory_some_avg10(),
+            self.memory_full_avg10(),
+            self.io_some_avg10(),
+            self.io_full_avg10()
         )
     }
+
+    // Thin avg10 accessors kept for backward compatibility with callers that
+    // only care about the 10s window; new code should read `.cpu`/`.memory`/`.io`
+    // directly for the full avg10/avg60/avg300/total picture.
+    pub fn cpu_some_avg10(&self) -> f32 {
+        self.cpu.some.avg10
+    }
+
+    pub fn memory_some_avg10(&self) -> f32 {
+        self.memory.some.avg10
+    }
+
+    pub fn memory_full_avg10(&self) -> f32 {
+        self.memory.full.map(|record| record.avg10).unwrap_or(0.0)
+    }
+
+    pub fn io_some_avg10(&self) -> f32 {
+        self.io.some.avg10
+    }
+
+    pub fn io_full_avg10(&self) -> f32 {
+        self.io.full.map(|record| record.avg10).unwrap_or(0.0)
+    }
+}
+
+/// Build [`PsiMetrics`] from an already-collected [`crate::types::SystemSnapshot`]
+/// instead of re-reading `/proc/pressure/*`, for callers (e.g. the local-ILM
+/// worker feeding [`crate::circuit_breaker::CircuitBreaker`]) that already
+/// have a fresh snapshot in hand this tick.
+impl From<&crate::types::SystemSnapshot> for PsiMetrics {
+    fn from(snap: &crate::types::SystemSnapshot) -> Self {
+        Self {
+            cpu: Pressure {
+                some: PressureRecord {
+                    avg10: snap.psi_cpu_some_avg10,
+                    ..Default::default()
+                },
+                full: None,
+            },
+            memory: Pressure {
+                some: PressureRecord {
+                    avg10: snap.psi_memory_some_avg10,
+                    ..Default::default()
+                },
+                full: Some(PressureRecord {
+                    avg10: snap.psi_memory_full_avg10,
+                    ..Default::default()
+                }),
+            },
+            io: Pressure {
+                some: PressureRecord {
+                    avg10: snap.psi_io_some_avg10,
+                    ..Default::default()
+                },
+                full: Some(PressureRecord {
+                    avg10: snap.psi_io_full_avg10,
+                    ..Default::default()
+                }),
+            },
+        }
+    }
 }
 
-/// Parse avg10 value from a PSI line
-///
-/// Input: "some avg10=5.23 avg60=3.45 avg300=2.11 total=123456"
-/// Output: Some(5.23)
-fn parse_avg10(content: &str, line_prefix: &str) -> Option<f32> {
+/// Parse one `some`/`full` line (e.g. `some avg10=5.23 avg60=3.45
+/// avg300=2.11 total=123456`) into a [`PressureRecord`]. Missing fields
+/// default to 0/0.0, and `None` is returned only when no line starts with
+/// `line_prefix` at all.
+fn parse_pressure_record(content: &str, line_prefix: &str) -> Option<PressureRecord> {
     for line in content.lines() {
         if line.starts_with(line_prefix) {
-            // Line format: "some avg10=5.23 avg60=..."
+            let mut record = PressureRecord::default();
             for part in line.split_whitespace() {
-                if part.starts_with("avg10=") {
-                    let value_str = part.strip_prefix("avg10=")?;
-                    return value_str.parse::<f32>().ok();
+                if let Some((key, value)) = part.split_once('=') {
+                    match key {
+                        "avg10" => record.avg10 = value.parse().unwrap_or(0.0),
+                        "avg60" => record.avg60 = value.parse().unwrap_or(0.0),
+                        "avg300" => record.avg300 = value.parse().unwrap_or(0.0),
+                        "total" => record.total = value.parse().unwrap_or(0),
+                        _ => {}
+                    }
                 }
             }
+            return Some(record);
         }
     }
     None
@@ -127,58 +198,139 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_parse_avg10_some() {
+    fn test_parse_pressure_record_some() {
         let content = "some avg10=5.23 avg60=3.45 avg300=2.11 total=123456\n";
-        assert_eq!(parse_avg10(content, "some"), Some(5.23));
+        let record = parse_pressure_record(content, "some").unwrap();
+        assert_eq!(record.avg10, 5.23);
+        assert_eq!(record.avg60, 3.45);
+        assert_eq!(record.avg300, 2.11);
+        assert_eq!(record.total, 123456);
     }
 
     #[test]
-    fn test_parse_avg10_full() {
+    fn test_parse_pressure_record_full() {
         let content = "full avg10=0.12 avg60=0.08 avg300=0.05 total=78901\n";
-        assert_eq!(parse_avg10(content, "full"), Some(0.12));
+        let record = parse_pressure_record(content, "full").unwrap();
+        assert_eq!(record.avg10, 0.12);
+        assert_eq!(record.total, 78901);
     }
 
     #[test]
-    fn test_parse_avg10_multiline() {
+    fn test_parse_pressure_record_multiline() {
         let content = "some avg10=10.50 avg60=8.30 avg300=5.20 total=999999\n\
                        full avg10=2.34 avg60=1.56 avg300=0.78 total=111111\n";
-        assert_eq!(parse_avg10(content, "some"), Some(10.50));
-        assert_eq!(parse_avg10(content, "full"), Some(2.34));
+        assert_eq!(parse_pressure_record(content, "some").unwrap().avg10, 10.50);
+        assert_eq!(parse_pressure_record(content, "full").unwrap().avg10, 2.34);
     }
 
     #[test]
-    fn test_parse_avg10_missing() {
+    fn test_parse_pressure_record_missing_fields_default_to_zero() {
         let content = "some avg60=3.45 avg300=2.11 total=123456\n";
-        assert_eq!(parse_avg10(content, "some"), None);
+        let record = parse_pressure_record(content, "some").unwrap();
+        assert_eq!(record.avg10, 0.0);
+        assert_eq!(record.avg60, 3.45);
+        assert_eq!(record.total, 123456);
+    }
+
+    #[test]
+    fn test_psi_metrics_from_system_snapshot() {
+        let snap = crate::types::SystemSnapshot {
+            timestamp: 0,
+            cpu_percent: 0.0,
+            mem_percent: 0.0,
+            load_avg: [0.0, 0.0, 0.0],
+            disk_read_bytes: 0,
+            disk_write_bytes: 0,
+            net_rx_bytes: 0,
+            net_tx_bytes: 0,
+            psi_cpu_some_avg10: 12.0,
+            psi_memory_some_avg10: 3.0,
+            psi_memory_full_avg10: 25.0,
+            psi_io_some_avg10: 4.0,
+            psi_io_full_avg10: 30.0,
+        };
+        let metrics = PsiMetrics::from(&snap);
+        assert_eq!(metrics.cpu_some_avg10(), 12.0);
+        assert_eq!(metrics.memory_full_avg10(), 25.0);
+        assert_eq!(metrics.io_full_avg10(), 30.0);
     }
 
     #[test]
-    fn test_parse_avg10_invalid_float() {
+    fn test_parse_pressure_record_invalid_float_defaults_to_zero() {
         let content = "some avg10=invalid avg60=3.45 avg300=2.11 total=123456\n";
-        assert_eq!(parse_avg10(content, "some"), None);
+        let record = parse_pressure_record(content, "some").unwrap();
+        assert_eq!(record.avg10, 0.0);
+        assert_eq!(record.avg60, 3.45);
+    }
+
+    #[test]
+    fn test_parse_pressure_record_absent_line_returns_none() {
+        let content = "full avg10=0.12 avg60=0.08 avg300=0.05 total=78901\n";
+        assert_eq!(parse_pressure_record(content, "some"), None);
     }
 
     #[test]
     fn test_psi_metrics_default() {
         let metrics = PsiMetrics::default();
-        assert_eq!(metrics.cpu_some_avg10, 0.0);
-        assert_eq!(metrics.memory_some_avg10, 0.0);
-        assert_eq!(metrics.memory_full_avg10, 0.0);
-        assert_eq!(metrics.io_some_avg10, 0.0);
-        assert_eq!(metrics.io_full_avg10, 0.0);
+        assert_eq!(metrics.cpu_some_avg10(), 0.0);
+        assert_eq!(metrics.memory_some_avg10(), 0.0);
+        assert_eq!(metrics.memory_full_avg10(), 0.0);
+        assert_eq!(metrics.io_some_avg10(), 0.0);
+        assert_eq!(metrics.io_full_avg10(), 0.0);
     }
 
     #[test]
     fn test_psi_metrics_summary() {
         let metrics = PsiMetrics {
-            cpu_some_avg10: 12.5,
-            memory_some_avg10: 8.3,
-            memory_full_avg10: 2.1,
-            io_some_avg10: 15.7,
-            io_full_avg10: 0.5,
+            cpu: Pressure {
+                some: PressureRecord {
+                    avg10: 12.5,
+                    ..Default::default()
+                },
+                full: None,
+            },
+            memory: Pressure {
+                some: PressureRecord {
+                    avg10: 8.3,
+                    ..Default::default()
+                },
+                full: Some(PressureRecord {
+                    avg10: 2.1,
+                    ..Default::default()
+                }),
+            },
+            io: Pressure {
+                some: PressureRecord {
+                    avg10: 15.7,
+                    ..Default::default()
+                },
+                full: Some(PressureRecord {
+                    avg10: 0.5,
+                    ..Default::default()
+                }),
+            },
         };
         let summary = metrics.summary();
         assert!(summary.contains("cpu=12.5%"));
         assert!(summary.contains("mem_full=2.1%"));
     }
+
+    #[test]
+    fn test_psi_metrics_distinguishes_transient_from_sustained() {
+        // High avg10, low avg300: a transient spike.
+        let transient = PressureRecord {
+            avg10: 80.0,
+            avg300: 1.0,
+            ..Default::default()
+        };
+        // All windows high: sustained saturation.
+        let sustained = PressureRecord {
+            avg10: 80.0,
+            avg60: 75.0,
+            avg300: 70.0,
+            ..Default::default()
+        };
+        assert!(transient.avg10 > transient.avg300 * 10.0);
+        assert!(sustained.avg300 > 50.0);
+    }
 }
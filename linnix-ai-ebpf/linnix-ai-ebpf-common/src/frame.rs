@@ -0,0 +1,396 @@
+//! Compact binary framing for streaming events between `cognitod` and
+//! clients, as a high-throughput alternative to JSON on the hot
+//! ring-buffer path.
+//!
+//! Each frame is a fixed little-endian header followed immediately by the
+//! `#[repr(C)]` event body, written with [`bytemuck::bytes_of`]:
+//!
+//! ```text
+//! magic: u32 | version: u16 | event_type: u16 | seq: u64 | payload_len: u32 | payload
+//! ```
+//!
+//! `event_type` reuses the [`EventType`] discriminants, so a reader can
+//! dispatch on the header alone before touching the payload. JSON stays
+//! available as an opt-in debug format (just `serde_json::to_string` the
+//! event structs directly); this module is only about the default
+//! high-throughput transport.
+
+use bytemuck::{Pod, Zeroable, bytes_of, try_from_bytes};
+
+use crate::{
+    BlockIoEvent, BlockOp, EventType, FileIoEvent, NetEvent, PageFaultEvent, PageFaultFlags,
+    PageFaultOrigin, ProcessEvent, RssTraceEvent, SyscallEvent,
+};
+
+/// Four bytes identifying this as a linnix event frame, chosen to be
+/// unlikely to collide with a stray JSON payload (`{` is `0x7b`).
+pub const FRAME_MAGIC: u32 = 0x4c_4e_58_31; // "LNX1"
+pub const FRAME_VERSION: u16 = 1;
+
+/// Size in bytes of [`FrameHeader`] on the wire.
+pub const HEADER_LEN: usize = 20;
+
+// `packed` keeps the header exactly `HEADER_LEN` bytes on the wire -- the
+// natural alignment of `seq: u64` would otherwise pad the struct out to 24
+// bytes, and `Pod` requires no padding bytes.
+#[repr(C, packed)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+struct FrameHeader {
+    magic: u32,
+    version: u16,
+    event_type: u16,
+    seq: u64,
+    payload_len: u32,
+}
+
+/// Why a frame couldn't be decoded.
+#[derive(Debug)]
+pub enum FrameError {
+    /// Fewer bytes than [`HEADER_LEN`] were supplied.
+    Truncated,
+    /// `magic` didn't match [`FRAME_MAGIC`] -- not a linnix frame, or the
+    /// stream is out of sync.
+    BadMagic(u32),
+    /// `version` is higher than this reader understands.
+    UnsupportedVersion(u16),
+    /// `event_type` isn't one of the [`EventType`] discriminants.
+    UnknownEventType(u16),
+    /// `payload_len` didn't match what the event type's body requires.
+    PayloadLenMismatch { expected: usize, actual: usize },
+    /// The payload decoded, but a field inside it (e.g. `BlockIoEvent::op`)
+    /// carried a discriminant this reader doesn't recognize.
+    InvalidDiscriminant(u32),
+}
+
+impl std::fmt::Display for FrameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FrameError::Truncated => write!(f, "frame truncated before header complete"),
+            FrameError::BadMagic(got) => write!(f, "bad frame magic: {got:#010x}"),
+            FrameError::UnsupportedVersion(v) => write!(f, "unsupported frame version: {v}"),
+            FrameError::UnknownEventType(t) => write!(f, "unknown event type discriminant: {t}"),
+            FrameError::PayloadLenMismatch { expected, actual } => {
+                write!(f, "payload length mismatch: expected {expected}, got {actual}")
+            }
+            FrameError::InvalidDiscriminant(v) => write!(f, "invalid discriminant in payload: {v}"),
+        }
+    }
+}
+
+impl std::error::Error for FrameError {}
+
+/// A decoded frame: which event type it carries, its sequence number, and
+/// the typed payload.
+#[derive(Debug, Clone)]
+pub enum EventFrame {
+    Process(ProcessEvent),
+    Net(NetEvent),
+    FileIo(FileIoEvent),
+    BlockIo(BlockIoEvent),
+    PageFault(PageFaultEvent),
+    RssTrace(RssTraceEvent),
+    Syscall(SyscallEvent),
+}
+
+/// Encode `event` as a binary frame with sequence number `seq`, appending
+/// the header and raw payload bytes to `out`.
+///
+/// `ProcessEvent` carries `Exec`/`Fork`/`Exit` via its own `event_type`
+/// field rather than the frame header, so all three are framed under the
+/// `Exec` discriminant; a reader unpacks the real variant from the decoded
+/// payload.
+pub fn encode(event: &EventFrame, seq: u64, out: &mut Vec<u8>) {
+    match event {
+        EventFrame::Process(e) => encode_pod(EventType::Exec, seq, bytes_of(e), out),
+        EventFrame::Net(e) => encode_pod(EventType::Net, seq, bytes_of(e), out),
+        EventFrame::FileIo(e) => encode_pod(EventType::FileIo, seq, bytes_of(e), out),
+        EventFrame::RssTrace(e) => encode_pod(EventType::RssTrace, seq, bytes_of(e), out),
+        EventFrame::Syscall(e) => encode_pod(EventType::Syscall, seq, bytes_of(e), out),
+        EventFrame::BlockIo(e) => {
+            let payload = block_io_to_bytes(e);
+            encode_pod(EventType::BlockIo, seq, &payload, out);
+        }
+        EventFrame::PageFault(e) => {
+            let payload = page_fault_to_bytes(e);
+            encode_pod(EventType::PageFault, seq, &payload, out);
+        }
+    }
+}
+
+fn encode_pod(event_type: EventType, seq: u64, payload: &[u8], out: &mut Vec<u8>) {
+    let header = FrameHeader {
+        magic: FRAME_MAGIC,
+        version: FRAME_VERSION,
+        event_type: event_type as u32 as u16,
+        seq,
+        payload_len: payload.len() as u32,
+    };
+    out.extend_from_slice(bytes_of(&header));
+    out.extend_from_slice(payload);
+}
+
+/// Decode one frame from the front of `data`, returning the frame's
+/// sequence number, the frame itself, and the number of bytes consumed.
+pub fn decode(data: &[u8]) -> Result<(u64, EventFrame, usize), FrameError> {
+    if data.len() < HEADER_LEN {
+        return Err(FrameError::Truncated);
+    }
+    let header: &FrameHeader = try_from_bytes(&data[..HEADER_LEN]).map_err(|_| FrameError::Truncated)?;
+    if header.magic != FRAME_MAGIC {
+        return Err(FrameError::BadMagic(header.magic));
+    }
+    if header.version > FRAME_VERSION {
+        return Err(FrameError::UnsupportedVersion(header.version));
+    }
+
+    let payload_len = header.payload_len as usize;
+    let total_len = HEADER_LEN + payload_len;
+    if data.len() < total_len {
+        return Err(FrameError::Truncated);
+    }
+    let payload = &data[HEADER_LEN..total_len];
+
+    let seq = header.seq;
+    let event_type = header.event_type;
+    let event = if event_type == EventType::Net as u32 as u16 {
+        EventFrame::Net(pod_from_payload(payload)?)
+    } else if event_type == EventType::FileIo as u32 as u16 {
+        EventFrame::FileIo(pod_from_payload(payload)?)
+    } else if event_type == EventType::BlockIo as u32 as u16 {
+        EventFrame::BlockIo(block_io_from_bytes(payload)?)
+    } else if event_type == EventType::PageFault as u32 as u16 {
+        EventFrame::PageFault(page_fault_from_bytes(payload)?)
+    } else if event_type == EventType::Syscall as u32 as u16 {
+        EventFrame::Syscall(pod_from_payload(payload)?)
+    } else if event_type == EventType::RssTrace as u32 as u16 {
+        EventFrame::RssTrace(pod_from_payload(payload)?)
+    } else if event_type == EventType::Exec as u32 as u16
+        || event_type == EventType::Fork as u32 as u16
+        || event_type == EventType::Exit as u32 as u16
+    {
+        EventFrame::Process(pod_from_payload(payload)?)
+    } else {
+        return Err(FrameError::UnknownEventType(event_type));
+    };
+
+    Ok((seq, event, total_len))
+}
+
+fn pod_from_payload<T: Pod>(payload: &[u8]) -> Result<T, FrameError> {
+    try_from_bytes(payload).map(|v: &T| *v).map_err(|_| FrameError::PayloadLenMismatch {
+        expected: std::mem::size_of::<T>(),
+        actual: payload.len(),
+    })
+}
+
+/// `BlockIoEvent` carries a `BlockOp` enum, which can't derive `Pod`
+/// (not every `u32` is a valid discriminant), so it's framed as its Pod
+/// fields plus the op's raw discriminant. `packed` avoids the alignment
+/// padding `derive(Pod)` would otherwise reject.
+#[repr(C, packed)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+struct BlockIoEventWire {
+    pid: u32,
+    bytes: u64,
+    sector: u64,
+    device: u32,
+    op: u32,
+}
+
+fn block_io_to_bytes(e: &BlockIoEvent) -> Vec<u8> {
+    bytes_of(&BlockIoEventWire {
+        pid: e.pid,
+        bytes: e.bytes,
+        sector: e.sector,
+        device: e.device,
+        op: e.op as u32,
+    })
+    .to_vec()
+}
+
+fn block_io_from_bytes(payload: &[u8]) -> Result<BlockIoEvent, FrameError> {
+    let wire: BlockIoEventWire = pod_from_payload(payload)?;
+    let op = match wire.op {
+        x if x == BlockOp::Queue as u32 => BlockOp::Queue,
+        x if x == BlockOp::Issue as u32 => BlockOp::Issue,
+        x if x == BlockOp::Complete as u32 => BlockOp::Complete,
+        other => return Err(FrameError::InvalidDiscriminant(other)),
+    };
+    Ok(BlockIoEvent {
+        pid: wire.pid,
+        bytes: wire.bytes,
+        sector: wire.sector,
+        device: wire.device,
+        op,
+    })
+}
+
+/// `PageFaultEvent` carries a `PageFaultOrigin` enum for the same reason
+/// `BlockIoEvent` does; `PageFaultFlags` is already Pod. `packed` avoids
+/// the alignment padding `derive(Pod)` would otherwise reject.
+#[repr(C, packed)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+struct PageFaultEventWire {
+    pid: u32,
+    address: u64,
+    ip: u64,
+    flags: PageFaultFlags,
+    origin: u32,
+}
+
+fn page_fault_to_bytes(e: &PageFaultEvent) -> Vec<u8> {
+    bytes_of(&PageFaultEventWire {
+        pid: e.pid,
+        address: e.address,
+        ip: e.ip,
+        flags: e.flags,
+        origin: e.origin as u32,
+    })
+    .to_vec()
+}
+
+fn page_fault_from_bytes(payload: &[u8]) -> Result<PageFaultEvent, FrameError> {
+    let wire: PageFaultEventWire = pod_from_payload(payload)?;
+    let origin = match wire.origin {
+        x if x == PageFaultOrigin::User as u32 => PageFaultOrigin::User,
+        x if x == PageFaultOrigin::Kernel as u32 => PageFaultOrigin::Kernel,
+        other => return Err(FrameError::InvalidDiscriminant(other)),
+    };
+    Ok(PageFaultEvent {
+        pid: wire.pid,
+        address: wire.address,
+        ip: wire.ip,
+        flags: wire.flags,
+        origin,
+    })
+}
+
+/// How many sequence numbers a [`Reorderer`] will hold back waiting for a
+/// gap to fill before giving up on it.
+const REORDER_WINDOW: u64 = 64;
+
+/// Detects gaps in a `seq` stream and reorders frames that arrive slightly
+/// out of order (common when a producer fans out across a few sender
+/// tasks), within a bounded window so a permanently missing frame doesn't
+/// stall the stream forever.
+pub struct Reorderer {
+    next_seq: Option<u64>,
+    pending: std::collections::BTreeMap<u64, EventFrame>,
+}
+
+impl Reorderer {
+    pub fn new() -> Self {
+        Self {
+            next_seq: None,
+            pending: std::collections::BTreeMap::new(),
+        }
+    }
+
+    /// Feed one decoded frame in; returns the frames now ready to deliver
+    /// in order. Once more than [`REORDER_WINDOW`] frames are held behind a
+    /// gap, the gap is given up on and the stream resynchronizes at the
+    /// lowest pending seq.
+    pub fn push(&mut self, seq: u64, frame: EventFrame) -> Vec<EventFrame> {
+        let next_seq = *self.next_seq.get_or_insert(seq);
+
+        if seq < next_seq {
+            // Already delivered or too late; drop.
+            return Vec::new();
+        }
+        self.pending.insert(seq, frame);
+
+        let mut ready = Vec::new();
+        let mut cursor = next_seq;
+        while let Some(frame) = self.pending.remove(&cursor) {
+            ready.push(frame);
+            cursor += 1;
+        }
+
+        if ready.is_empty() && self.pending.len() as u64 > REORDER_WINDOW {
+            // The gap at `cursor` isn't filling; skip it and drain
+            // whatever contiguous run starts at the next pending seq.
+            if let Some(&resync) = self.pending.keys().next() {
+                cursor = resync;
+                while let Some(frame) = self.pending.remove(&cursor) {
+                    ready.push(frame);
+                    cursor += 1;
+                }
+            }
+        }
+
+        self.next_seq = Some(cursor);
+        ready
+    }
+}
+
+impl Default for Reorderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roundtrips_net_event() {
+        let event = EventFrame::Net(NetEvent { pid: 7, _pad: 0, bytes: 4096 });
+        let mut buf = Vec::new();
+        encode(&event, 42, &mut buf);
+
+        let (seq, decoded, consumed) = decode(&buf).unwrap();
+        assert_eq!(seq, 42);
+        assert_eq!(consumed, buf.len());
+        match decoded {
+            EventFrame::Net(e) => {
+                assert_eq!(e.pid, 7);
+                assert_eq!(e.bytes, 4096);
+            }
+            other => panic!("expected Net frame, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn roundtrips_block_io_event_with_enum_field() {
+        let event = EventFrame::BlockIo(BlockIoEvent {
+            pid: 1,
+            bytes: 512,
+            sector: 99,
+            device: 0x800,
+            op: BlockOp::Issue,
+        });
+        let mut buf = Vec::new();
+        encode(&event, 1, &mut buf);
+
+        let (_, decoded, _) = decode(&buf).unwrap();
+        match decoded {
+            EventFrame::BlockIo(e) => assert_eq!(e.op as u32, BlockOp::Issue as u32),
+            other => panic!("expected BlockIo frame, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn rejects_bad_magic() {
+        let buf = vec![0u8; HEADER_LEN];
+        let err = decode(&buf).unwrap_err();
+        assert!(matches!(err, FrameError::BadMagic(0)));
+    }
+
+    #[test]
+    fn truncated_header_is_reported() {
+        let err = decode(&[0u8; 4]).unwrap_err();
+        assert!(matches!(err, FrameError::Truncated));
+    }
+
+    #[test]
+    fn reorderer_delivers_in_order_despite_shuffled_arrival() {
+        let mut reorderer = Reorderer::new();
+        let e = |pid| EventFrame::Net(NetEvent { pid, _pad: 0, bytes: 0 });
+
+        assert!(reorderer.push(0, e(0)).len() == 1);
+        assert!(reorderer.push(2, e(2)).is_empty());
+        let ready = reorderer.push(1, e(1));
+        assert_eq!(ready.len(), 2);
+    }
+}
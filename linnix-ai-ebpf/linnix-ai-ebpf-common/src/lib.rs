@@ -80,7 +80,7 @@ pub enum PageFaultOrigin {
 }
 
 #[repr(transparent)]
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Pod, Zeroable)]
 #[cfg_attr(feature = "user", derive(serde::Serialize, serde::Deserialize))]
 pub struct PageFaultFlags(pub u32);
 
@@ -158,6 +158,16 @@ pub mod rss_source {
     pub const DISABLED: u32 = 2;
 }
 
+#[cfg(all(feature = "user", not(target_os = "none")))]
+pub mod symbolize;
+#[cfg(all(feature = "user", not(target_os = "none")))]
+pub use symbolize::{PageFaultEventSym, ResolvedAddress, Symbolizer};
+
+#[cfg(all(feature = "user", not(target_os = "none")))]
+pub mod frame;
+#[cfg(all(feature = "user", not(target_os = "none")))]
+pub use frame::{EventFrame, FrameError, Reorderer};
+
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Pod, Zeroable)]
 #[cfg_attr(feature = "user", derive(serde::Serialize, serde::Deserialize))]
@@ -185,6 +195,7 @@ pub enum EventType {
     Syscall = 5,
     BlockIo = 6,
     PageFault = 7,
+    RssTrace = 8,
 }
 
 #[cfg(all(feature = "user", not(target_os = "none")))]
@@ -273,29 +284,36 @@ impl core::ops::DerefMut for ProcessEventExt {
 }
 
 #[repr(C)]
-#[cfg_attr(not(feature = "user"), derive(Copy))]
-#[derive(Clone, Debug)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
 #[cfg_attr(
     all(feature = "user", not(target_os = "none")),
     derive(serde::Serialize, serde::Deserialize)
 )]
 pub struct NetEvent {
     pub pid: u32,
+    /// Explicit alignment filler ahead of `bytes` -- `derive(Pod)` rejects
+    /// implicit padding, so the gap a `u64` field would otherwise leave is
+    /// named instead.
+    pub _pad: u32,
     pub bytes: u64,
 }
 
 #[repr(C)]
-#[cfg_attr(not(feature = "user"), derive(Copy))]
-#[derive(Clone, Debug)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
 #[cfg_attr(
     all(feature = "user", not(target_os = "none")),
     derive(serde::Serialize, serde::Deserialize)
 )]
 pub struct FileIoEvent {
     pub pid: u32,
+    pub _pad: u32,
     pub bytes: u64,
 }
 
+/// Block-layer I/O event. `op` is a [`BlockOp`] enum, so this struct does
+/// not derive `Pod`/`Zeroable` (a C-style enum can't, since not every bit
+/// pattern is a valid discriminant); the binary `frame` encoding carries
+/// `op` as its raw `u32` discriminant instead.
 #[repr(C)]
 #[cfg_attr(not(feature = "user"), derive(Copy))]
 #[derive(Clone, Debug)]
@@ -309,8 +327,7 @@ pub struct BlockIoEvent {
 }
 
 #[repr(C)]
-#[cfg_attr(not(feature = "user"), derive(Copy))]
-#[derive(Clone, Debug)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
 #[cfg_attr(
     all(feature = "user", not(target_os = "none")),
     derive(serde::Serialize, serde::Deserialize)
@@ -0,0 +1,220 @@
+//! Resolves raw instruction pointers captured by [`PageFaultEvent`] into
+//! `module + offset + symbol`, so triage output shows e.g.
+//! `libc.so.6+0x91a20 (malloc)` instead of a bare hex address.
+//!
+//! Walks `/proc/<pid>/maps` to find which file-backed mapping contains the
+//! address, then looks the symbol up in that file's ELF symbol table. Each
+//! module's symbol table is parsed once and cached by `(dev, inode,
+//! mtime)`, since the same few binaries (the traced process, libc, the
+//! allocator) account for most page faults.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use crate::PageFaultEvent;
+
+/// A resolved address: the file-backed mapping it fell in, its offset into
+/// that mapping, and (if found) the enclosing, demangled symbol.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ResolvedAddress {
+    pub module: String,
+    pub offset: u64,
+    pub symbol: Option<String>,
+}
+
+/// [`PageFaultEvent`] with `address` and `ip` resolved to their owning
+/// module and symbol.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct PageFaultEventSym {
+    pub pid: u32,
+    pub address: u64,
+    pub address_resolved: Option<ResolvedAddress>,
+    pub ip: u64,
+    pub ip_resolved: Option<ResolvedAddress>,
+}
+
+/// Identifies a module's symbol table in the cache without holding the
+/// file open; a rebuild (new `mtime`) invalidates the cached entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct ModuleKey {
+    dev: u64,
+    inode: u64,
+    mtime: i64,
+}
+
+/// One file's demangled symbol table, sorted by address for lookup by
+/// binary search.
+struct ModuleSymbols {
+    symbols: Vec<(u64, String)>,
+}
+
+impl ModuleSymbols {
+    fn parse(path: &Path) -> io::Result<Self> {
+        let data = fs::read(path)?;
+        let file = object::File::parse(&*data)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+
+        use object::{Object, ObjectSymbol};
+        let mut symbols: Vec<(u64, String)> = file
+            .symbols()
+            .chain(file.dynamic_symbols())
+            .filter(|s| s.is_definition())
+            .filter_map(|s| {
+                let name = s.name().ok()?;
+                if name.is_empty() {
+                    return None;
+                }
+                Some((s.address(), rustc_demangle::demangle(name).to_string()))
+            })
+            .collect();
+        symbols.sort_unstable_by_key(|(addr, _)| *addr);
+        symbols.dedup_by_key(|(addr, _)| *addr);
+
+        Ok(Self { symbols })
+    }
+
+    /// Find the symbol whose address is the closest one at or below
+    /// `file_offset`, treating it as the enclosing function.
+    fn resolve(&self, file_offset: u64) -> Option<&str> {
+        match self.symbols.binary_search_by_key(&file_offset, |(addr, _)| *addr) {
+            Ok(idx) => Some(self.symbols[idx].1.as_str()),
+            Err(0) => None,
+            Err(idx) => Some(self.symbols[idx - 1].1.as_str()),
+        }
+    }
+}
+
+/// One file-backed mapping from `/proc/<pid>/maps`.
+struct MapEntry {
+    start: u64,
+    end: u64,
+    file_offset: u64,
+    path: String,
+}
+
+fn parse_maps_line(line: &str) -> Option<MapEntry> {
+    let mut parts = line.split_whitespace();
+    let range = parts.next()?;
+    let _perms = parts.next()?;
+    let offset = parts.next()?;
+    let _dev = parts.next()?;
+    let _inode = parts.next()?;
+    let path = parts.next()?;
+    if !path.starts_with('/') {
+        return None;
+    }
+
+    let (start_s, end_s) = range.split_once('-')?;
+    let start = u64::from_str_radix(start_s, 16).ok()?;
+    let end = u64::from_str_radix(end_s, 16).ok()?;
+    let file_offset = u64::from_str_radix(offset, 16).ok()?;
+
+    Some(MapEntry {
+        start,
+        end,
+        file_offset,
+        path: path.to_string(),
+    })
+}
+
+fn parse_maps(pid: u32) -> io::Result<Vec<MapEntry>> {
+    let data = fs::read_to_string(format!("/proc/{pid}/maps"))?;
+    Ok(data.lines().filter_map(parse_maps_line).collect())
+}
+
+fn module_key(path: &str) -> io::Result<ModuleKey> {
+    use std::os::unix::fs::MetadataExt;
+    let meta = fs::metadata(path)?;
+    Ok(ModuleKey {
+        dev: meta.dev(),
+        inode: meta.ino(),
+        mtime: meta.mtime(),
+    })
+}
+
+/// Resolves raw addresses to `module + offset + symbol`, caching each
+/// module's parsed ELF symbol table by `(dev, inode, mtime)` so repeated
+/// faults in the same binary don't re-parse it.
+#[derive(Default)]
+pub struct Symbolizer {
+    cache: Mutex<HashMap<ModuleKey, Arc<ModuleSymbols>>>,
+}
+
+impl Symbolizer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Resolve one address for `pid`, or `None` if it doesn't fall in a
+    /// file-backed mapping (JIT'd code, an anonymous region, a since-exited
+    /// process) or the module's symbol table can't be parsed.
+    pub fn resolve(&self, pid: u32, addr: u64) -> Option<ResolvedAddress> {
+        let maps = parse_maps(pid).ok()?;
+        let vma = maps.iter().find(|m| addr >= m.start && addr < m.end)?;
+        let file_offset = addr - vma.start + vma.file_offset;
+
+        let key = module_key(&vma.path).ok()?;
+        let table = {
+            let mut cache = self.cache.lock().unwrap();
+            match cache.get(&key) {
+                Some(table) => Arc::clone(table),
+                None => {
+                    let table = Arc::new(ModuleSymbols::parse(Path::new(&vma.path)).ok()?);
+                    cache.insert(key, Arc::clone(&table));
+                    table
+                }
+            }
+        };
+
+        Some(ResolvedAddress {
+            module: vma.path.clone(),
+            offset: file_offset,
+            symbol: table.resolve(file_offset).map(|s| s.to_string()),
+        })
+    }
+
+    /// Symbolicate a [`PageFaultEvent`]'s `address` and `ip`.
+    pub fn symbolicate_page_fault(&self, event: &PageFaultEvent) -> PageFaultEventSym {
+        PageFaultEventSym {
+            pid: event.pid,
+            address: event.address,
+            address_resolved: self.resolve(event.pid, event.address),
+            ip: event.ip,
+            ip_resolved: self.resolve(event.pid, event.ip),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_file_backed_maps_line() {
+        let line = "7f1a2b400000-7f1a2b428000 r--p 00000000 08:01 123456 /usr/lib/libc.so.6";
+        let entry = parse_maps_line(line).expect("file-backed mapping should parse");
+        assert_eq!(entry.start, 0x7f1a2b400000);
+        assert_eq!(entry.end, 0x7f1a2b428000);
+        assert_eq!(entry.file_offset, 0);
+        assert_eq!(entry.path, "/usr/lib/libc.so.6");
+    }
+
+    #[test]
+    fn ignores_anonymous_maps_line() {
+        let line = "7f1a2b600000-7f1a2b800000 rw-p 00000000 00:00 0";
+        assert!(parse_maps_line(line).is_none());
+    }
+
+    #[test]
+    fn resolve_picks_closest_symbol_at_or_below_offset() {
+        let table = ModuleSymbols {
+            symbols: vec![(0x1000, "a".to_string()), (0x2000, "b".to_string())],
+        };
+        assert_eq!(table.resolve(0x1500), Some("a"));
+        assert_eq!(table.resolve(0x2500), Some("b"));
+        assert_eq!(table.resolve(0x500), None);
+    }
+}
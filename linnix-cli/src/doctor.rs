@@ -1,8 +1,116 @@
 use colored::*;
 use reqwest::Client;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::error::Error;
 
+/// Protocol versions this build of `linnix-cli` knows how to talk to.
+/// Bump `MAX_SUPPORTED_PROTOCOL_VERSION` when adding support for a newer
+/// agent; bump the lower bound only once compatibility with an older one
+/// is intentionally dropped.
+const MIN_SUPPORTED_PROTOCOL_VERSION: u32 = 1;
+const MAX_SUPPORTED_PROTOCOL_VERSION: u32 = 1;
+
+/// `doctor` output format, selected with `--format`.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+#[clap(rename_all = "snake_case")]
+pub enum OutputFormat {
+    /// Colored, human-readable checklist (default).
+    Text,
+    /// A single JSON report: every check plus an overall verdict, no color.
+    Json,
+}
+
+/// Process exit codes for `doctor`, so it's usable from CI/monitoring
+/// instead of only eyeballed from a terminal.
+pub const EXIT_HEALTHY: i32 = 0;
+pub const EXIT_UNHEALTHY: i32 = 1;
+/// Distinct from [`EXIT_UNHEALTHY`]: the agent itself couldn't be reached
+/// at all (as opposed to being reachable but reporting a problem).
+pub const EXIT_UNREACHABLE: i32 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum CheckStatus {
+    Ok,
+    Warn,
+    Fail,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct CheckResult {
+    name: &'static str,
+    status: CheckStatus,
+    detail: String,
+}
+
+#[derive(Debug, Serialize)]
+struct Report {
+    reachable: bool,
+    healthy: bool,
+    checks: Vec<CheckResult>,
+}
+
+/// Accumulates [`CheckResult`]s and, in [`OutputFormat::Text`] mode, prints
+/// each one as it's recorded so the existing line-by-line checklist output
+/// is unchanged.
+struct Checks {
+    format: OutputFormat,
+    results: Vec<CheckResult>,
+}
+
+impl Checks {
+    fn new(format: OutputFormat) -> Self {
+        Self {
+            format,
+            results: Vec::new(),
+        }
+    }
+
+    /// Record a check's outcome. `label` is the prefix already printed via
+    /// `print!("• ...")` in text mode before the colored verdict is known;
+    /// `colored` is that verdict, `detail` its plain-text equivalent for
+    /// JSON.
+    fn record(&mut self, name: &'static str, status: CheckStatus, colored: ColoredString, detail: impl Into<String>) {
+        if self.format == OutputFormat::Text {
+            println!("{}", colored);
+        }
+        self.results.push(CheckResult {
+            name,
+            status,
+            detail: detail.into(),
+        });
+    }
+
+    fn label(&self, text: &str) {
+        if self.format == OutputFormat::Text {
+            print!("{}", text);
+        }
+    }
+}
+
+/// Mirrors `cognitod::capabilities::Capabilities`. Every field has a
+/// `#[serde(default)]` so a client built against a newer protocol version
+/// (which may add capability flags) still deserializes an older agent's
+/// response, and an older agent that predates this endpoint entirely is
+/// treated as absent rather than a parse failure.
+#[derive(Deserialize, Debug, Default)]
+struct Capabilities {
+    #[serde(default)]
+    protocol_version: u32,
+    #[serde(default)]
+    binary_stream: bool,
+    #[serde(default)]
+    rss_probe: bool,
+    #[serde(default)]
+    ilm: bool,
+    #[serde(default)]
+    slack: bool,
+    #[serde(default)]
+    incident_store: bool,
+    #[serde(default)]
+    telemetry_export: bool,
+}
+
 #[derive(Deserialize, Debug)]
 struct HealthResponse {
     #[allow(dead_code)]
@@ -35,6 +143,8 @@ struct StatusResponse {
     slack_stats: SlackStats,
     perf_poll_errors: u64,
     dropped_events_total: u64,
+    #[serde(default)]
+    export_stats: Option<ExportStats>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -60,164 +170,478 @@ struct SlackStats {
     denied: u64,
 }
 
-pub async fn run_doctor(url: &str) -> Result<(), Box<dyn Error>> {
-    println!("{}", "🩺 Linnix Doctor".bold().cyan());
-    println!("{}", "Checking system health...".dimmed());
-    println!();
+/// Mirrors `cognitod::export::ExportStats`.
+#[derive(Deserialize, Debug)]
+struct ExportStats {
+    rows_exported_total: u64,
+    rows_dropped_total: u64,
+    flush_failures_total: u64,
+}
+
+/// Runs every health check against `url` and returns the process exit code
+/// it should terminate with ([`EXIT_HEALTHY`], [`EXIT_UNHEALTHY`], or
+/// [`EXIT_UNREACHABLE`]). In [`OutputFormat::Text`] the usual colored
+/// checklist is printed as each check completes; in [`OutputFormat::Json`]
+/// nothing is printed until the end, when a single JSON report is written
+/// to stdout instead.
+pub async fn run_doctor(url: &str, format: OutputFormat) -> Result<i32, Box<dyn Error>> {
+    if format == OutputFormat::Text {
+        println!("{}", "🩺 Linnix Doctor".bold().cyan());
+        println!("{}", "Checking system health...".dimmed());
+        println!();
+    }
 
     let client = Client::new();
-    let mut all_good = true;
+    let mut checks = Checks::new(format);
 
     // 1. Check Connectivity & Health
-    print!("• Agent Connectivity: ");
+    checks.label("• Agent Connectivity: ");
     match client.get(format!("{}/healthz", url)).send().await {
         Ok(resp) => {
             if resp.status().is_success() {
                 if resp.json::<HealthResponse>().await.is_ok() {
-                    println!("{}", "OK".green());
+                    checks.record("connectivity", CheckStatus::Ok, "OK".green(), "OK");
                 } else {
-                    println!("{}", "OK (Invalid JSON)".yellow());
+                    checks.record(
+                        "connectivity",
+                        CheckStatus::Warn,
+                        "OK (Invalid JSON)".yellow(),
+                        "OK (invalid JSON)",
+                    );
                 }
             } else {
-                println!("{}", format!("FAIL (Status {})", resp.status()).red());
-                all_good = false;
+                checks.record(
+                    "connectivity",
+                    CheckStatus::Fail,
+                    format!("FAIL (Status {})", resp.status()).red(),
+                    format!("FAIL (status {})", resp.status()),
+                );
             }
         }
         Err(e) => {
-            println!("{}", format!("FAIL ({})", e).red());
-            println!("  → Is cognitod running? Try 'systemctl status cognitod'");
-            return Ok(()); // Stop here if we can't connect
+            checks.record(
+                "connectivity",
+                CheckStatus::Fail,
+                format!("FAIL ({})", e).red(),
+                format!("unreachable: {e}"),
+            );
+            if format == OutputFormat::Text {
+                println!("  → Is cognitod running? Try 'systemctl status cognitod'");
+            }
+            return Ok(finish(checks, format, false));
         }
     }
 
-    // 2. Fetch Status for deeper checks
-    print!("• Agent Status:       ");
+    // 2. Negotiate capabilities so later checks can adapt instead of
+    // misreporting a capability the agent simply doesn't have.
+    checks.label("• Protocol Negotiation: ");
+    let capabilities: Option<Capabilities> = match client
+        .get(format!("{}/capabilities", url))
+        .send()
+        .await
+    {
+        Ok(resp) if resp.status().is_success() => resp.json::<Capabilities>().await.ok(),
+        _ => None,
+    };
+
+    let capabilities = match capabilities {
+        Some(caps) if caps.protocol_version > MAX_SUPPORTED_PROTOCOL_VERSION => {
+            checks.record(
+                "protocol_negotiation",
+                CheckStatus::Fail,
+                format!(
+                    "FAIL (agent speaks protocol v{}, this CLI only understands up to v{})",
+                    caps.protocol_version, MAX_SUPPORTED_PROTOCOL_VERSION
+                )
+                .red(),
+                format!(
+                    "agent speaks protocol v{}, this CLI only understands up to v{}",
+                    caps.protocol_version, MAX_SUPPORTED_PROTOCOL_VERSION
+                ),
+            );
+            if format == OutputFormat::Text {
+                println!("  → Upgrade linnix-cli to a version that supports the running agent.");
+            }
+            None
+        }
+        Some(caps) if caps.protocol_version < MIN_SUPPORTED_PROTOCOL_VERSION => {
+            checks.record(
+                "protocol_negotiation",
+                CheckStatus::Fail,
+                format!(
+                    "FAIL (agent speaks protocol v{}, this CLI requires at least v{})",
+                    caps.protocol_version, MIN_SUPPORTED_PROTOCOL_VERSION
+                )
+                .red(),
+                format!(
+                    "agent speaks protocol v{}, this CLI requires at least v{}",
+                    caps.protocol_version, MIN_SUPPORTED_PROTOCOL_VERSION
+                ),
+            );
+            if format == OutputFormat::Text {
+                println!("  → Upgrade cognitod, or downgrade linnix-cli to match it.");
+            }
+            None
+        }
+        Some(caps) => {
+            checks.record(
+                "protocol_negotiation",
+                CheckStatus::Ok,
+                format!("OK (protocol v{})", caps.protocol_version).green(),
+                format!("OK (protocol v{})", caps.protocol_version),
+            );
+            Some(caps)
+        }
+        None => {
+            checks.record(
+                "protocol_negotiation",
+                CheckStatus::Warn,
+                "unavailable (pre-handshake agent)".dimmed(),
+                "unavailable (pre-handshake agent)",
+            );
+            None
+        }
+    };
+
+    // 3. Fetch Status for deeper checks
+    checks.label("• Agent Status:       ");
     let status: StatusResponse = match client.get(format!("{}/status", url)).send().await {
-        Ok(resp) => resp.json().await?,
+        Ok(resp) => match resp.json().await {
+            Ok(status) => status,
+            Err(e) => {
+                checks.record(
+                    "status",
+                    CheckStatus::Fail,
+                    format!("FAIL ({})", e).red(),
+                    format!("unreachable: {e}"),
+                );
+                return Ok(finish(checks, format, false));
+            }
+        },
         Err(e) => {
-            println!("{}", format!("FAIL ({})", e).red());
-            return Ok(());
+            checks.record(
+                "status",
+                CheckStatus::Fail,
+                format!("FAIL ({})", e).red(),
+                format!("unreachable: {e}"),
+            );
+            return Ok(finish(checks, format, false));
         }
     };
-    println!("{}", format!("OK (v{})", status.version).green());
+    checks.record(
+        "status",
+        CheckStatus::Ok,
+        format!("OK (v{})", status.version).green(),
+        format!("OK (v{})", status.version),
+    );
 
-    // 3. Check Uptime
-    print!("• Uptime:             ");
+    // 4. Check Uptime
+    checks.label("• Uptime:             ");
     if status.uptime_s < 60 {
-        println!(
-            "{}",
-            format!("{}s (Just started)", status.uptime_s).yellow()
+        checks.record(
+            "uptime",
+            CheckStatus::Warn,
+            format!("{}s (Just started)", status.uptime_s).yellow(),
+            format!("{}s (just started)", status.uptime_s),
         );
     } else {
-        println!("{}", format!("{}s", status.uptime_s).green());
+        checks.record(
+            "uptime",
+            CheckStatus::Ok,
+            format!("{}s", status.uptime_s).green(),
+            format!("{}s", status.uptime_s),
+        );
     }
 
-    // 4. Check BPF Status
-    print!("• BPF Probes:         ");
+    // 5. Check BPF Status
+    checks.label("• BPF Probes:         ");
     if status.events_per_sec > 0 {
-        println!(
-            "{}",
-            format!("Active ({} events/sec)", status.events_per_sec).green()
+        checks.record(
+            "bpf_probes",
+            CheckStatus::Ok,
+            format!("Active ({} events/sec)", status.events_per_sec).green(),
+            format!("active ({} events/sec)", status.events_per_sec),
         );
     } else {
-        println!("{}", "Idle (0 events/sec)".yellow());
+        checks.record(
+            "bpf_probes",
+            CheckStatus::Warn,
+            "Idle (0 events/sec)".yellow(),
+            "idle (0 events/sec)",
+        );
     }
 
-    // 5. Check BTF
-    print!("• Kernel BTF:         ");
+    // 6. Check BTF
+    checks.label("• Kernel BTF:         ");
     if status.probes.btf {
-        println!("{}", "Available".green());
+        checks.record("kernel_btf", CheckStatus::Ok, "Available".green(), "available");
     } else {
-        println!("{}", "MISSING".red());
-        println!("  → Linnix needs BTF for optimal BPF performance.");
-        all_good = false;
+        checks.record("kernel_btf", CheckStatus::Fail, "MISSING".red(), "missing");
+        if format == OutputFormat::Text {
+            println!("  → Linnix needs BTF for optimal BPF performance.");
+        }
     }
 
-    // 6. Check RSS Mode
-    print!("• RSS Probe Mode:     ");
-    if status.probes.rss_probe == "disabled" {
-        println!("{}", "DISABLED".red());
-        println!("  → Memory metrics will be limited.");
-        all_good = false;
+    // 7. Check RSS Mode
+    checks.label("• RSS Probe Mode:     ");
+    if matches!(&capabilities, Some(caps) if !caps.rss_probe) {
+        checks.record(
+            "rss_probe_mode",
+            CheckStatus::Warn,
+            "not supported by this agent build".dimmed(),
+            "not supported by this agent build",
+        );
+    } else if status.probes.rss_probe == "disabled" {
+        checks.record("rss_probe_mode", CheckStatus::Fail, "DISABLED".red(), "disabled");
+        if format == OutputFormat::Text {
+            println!("  → Memory metrics will be limited.");
+        }
     } else {
-        println!("{}", status.probes.rss_probe.green());
+        checks.record(
+            "rss_probe_mode",
+            CheckStatus::Ok,
+            status.probes.rss_probe.green(),
+            status.probes.rss_probe.clone(),
+        );
     }
 
-    // 7. Check Errors
-    print!("• Perf Poll Errors:   ");
+    // 8. Check Errors
+    checks.label("• Perf Poll Errors:   ");
     if status.perf_poll_errors > 0 {
-        println!(
-            "{}",
-            format!("{} (Warning)", status.perf_poll_errors).yellow()
+        checks.record(
+            "perf_poll_errors",
+            CheckStatus::Warn,
+            format!("{} (Warning)", status.perf_poll_errors).yellow(),
+            format!("{} (warning)", status.perf_poll_errors),
         );
     } else {
-        println!("{}", "0".green());
+        checks.record("perf_poll_errors", CheckStatus::Ok, "0".green(), "0");
     }
 
-    // 8. Check Dropped Events
-    print!("• Dropped Events:     ");
+    // 9. Check Dropped Events
+    checks.label("• Dropped Events:     ");
     if status.dropped_events_total > 1000 {
-        println!(
-            "{}",
-            format!("{} (High Load)", status.dropped_events_total).yellow()
+        checks.record(
+            "dropped_events",
+            CheckStatus::Warn,
+            format!("{} (High Load)", status.dropped_events_total).yellow(),
+            format!("{} (high load)", status.dropped_events_total),
         );
     } else {
-        println!("{}", status.dropped_events_total.to_string().green());
+        checks.record(
+            "dropped_events",
+            CheckStatus::Ok,
+            status.dropped_events_total.to_string().green(),
+            status.dropped_events_total.to_string(),
+        );
     }
 
-    // 9. Check Incidents (Last 1h)
-    print!("• Incidents (1h):     ");
-    if let Some(count) = status.incidents_last_1h {
+    // 10. Check Incidents (Last 1h)
+    checks.label("• Incidents (1h):     ");
+    if matches!(&capabilities, Some(caps) if !caps.incident_store) {
+        checks.record(
+            "incidents_1h",
+            CheckStatus::Warn,
+            "not supported by this agent build".dimmed(),
+            "not supported by this agent build",
+        );
+    } else if let Some(count) = status.incidents_last_1h {
         if count > 0 {
-            println!("{}", format!("{} (Recent Activity)", count).yellow());
+            checks.record(
+                "incidents_1h",
+                CheckStatus::Warn,
+                format!("{} (Recent Activity)", count).yellow(),
+                format!("{} (recent activity)", count),
+            );
         } else {
-            println!("{}", "0".green());
+            checks.record("incidents_1h", CheckStatus::Ok, "0".green(), "0");
         }
     } else {
-        println!("{}", "N/A (Store disabled)".dimmed());
+        checks.record(
+            "incidents_1h",
+            CheckStatus::Warn,
+            "N/A (Store disabled)".dimmed(),
+            "N/A (store disabled)",
+        );
     }
 
-    // 10. Check Feedback
-    print!("• User Feedback:      ");
-    println!("{}", status.feedback_entries.to_string().green());
+    // 11. Check Feedback
+    checks.label("• User Feedback:      ");
+    checks.record(
+        "user_feedback",
+        CheckStatus::Ok,
+        status.feedback_entries.to_string().green(),
+        status.feedback_entries.to_string(),
+    );
 
-    // 11. Check Slack Integration
-    print!("• Slack Integration:  ");
-    if status.slack_stats.sent > 0 {
-        println!(
-            "{}",
+    // 12. Check Slack Integration
+    checks.label("• Slack Integration:  ");
+    if matches!(&capabilities, Some(caps) if !caps.slack) {
+        checks.record(
+            "slack_integration",
+            CheckStatus::Warn,
+            "not supported by this agent build".dimmed(),
+            "not supported by this agent build",
+        );
+    } else if status.slack_stats.sent > 0 {
+        checks.record(
+            "slack_integration",
+            CheckStatus::Ok,
             format!(
                 "Active ({} sent, {} approved, {} denied)",
                 status.slack_stats.sent, status.slack_stats.approved, status.slack_stats.denied
             )
-            .green()
+            .green(),
+            format!(
+                "active ({} sent, {} approved, {} denied)",
+                status.slack_stats.sent, status.slack_stats.approved, status.slack_stats.denied
+            ),
         );
     } else if status.slack_stats.failed > 0 {
-        println!(
-            "{}",
-            format!("Failing ({} errors)", status.slack_stats.failed).red()
+        checks.record(
+            "slack_integration",
+            CheckStatus::Fail,
+            format!("Failing ({} errors)", status.slack_stats.failed).red(),
+            format!("failing ({} errors)", status.slack_stats.failed),
         );
     } else {
-        println!("{}", "Idle / Not Configured".dimmed());
+        checks.record(
+            "slack_integration",
+            CheckStatus::Warn,
+            "Idle / Not Configured".dimmed(),
+            "idle / not configured",
+        );
     }
 
-    // 12. Check ILM Status
-    print!("• AI Analysis:        ");
-    if status.reasoner.ilm_enabled {
-        println!("{}", "Enabled".green());
+    // 13. Check ILM Status
+    checks.label("• AI Analysis:        ");
+    if matches!(&capabilities, Some(caps) if !caps.ilm) {
+        checks.record(
+            "ai_analysis",
+            CheckStatus::Warn,
+            "not supported by this agent build".dimmed(),
+            "not supported by this agent build",
+        );
+    } else if status.reasoner.ilm_enabled {
+        checks.record("ai_analysis", CheckStatus::Ok, "Enabled".green(), "enabled");
     } else {
-        println!("{}", "Disabled".dimmed());
+        checks.record("ai_analysis", CheckStatus::Warn, "Disabled".dimmed(), "disabled");
     }
 
-    println!();
-    if all_good {
-        println!(
-            "{}",
-            "✅ System is healthy and ready for triage.".bold().green()
+    // 14. Check Binary Streaming Transport
+    checks.label("• Binary Stream:      ");
+    match &capabilities {
+        Some(caps) if caps.binary_stream => {
+            checks.record("binary_stream", CheckStatus::Ok, "Supported".green(), "supported")
+        }
+        Some(_) => checks.record(
+            "binary_stream",
+            CheckStatus::Warn,
+            "not supported by this agent build".dimmed(),
+            "not supported by this agent build",
+        ),
+        None => checks.record(
+            "binary_stream",
+            CheckStatus::Warn,
+            "unknown (no capability handshake)".dimmed(),
+            "unknown (no capability handshake)",
+        ),
+    }
+
+    // 15. Check Telemetry Export (ClickHouse)
+    checks.label("• Telemetry Export:   ");
+    if matches!(&capabilities, Some(caps) if !caps.telemetry_export) {
+        checks.record(
+            "telemetry_export",
+            CheckStatus::Warn,
+            "not supported by this agent build".dimmed(),
+            "not supported by this agent build",
         );
+    } else if let Some(export) = &status.export_stats {
+        if export.flush_failures_total > 0 {
+            checks.record(
+                "telemetry_export",
+                CheckStatus::Fail,
+                format!(
+                    "FAILING ({} exported, {} dropped, {} flush failures)",
+                    export.rows_exported_total, export.rows_dropped_total, export.flush_failures_total
+                )
+                .red(),
+                format!(
+                    "failing ({} exported, {} dropped, {} flush failures)",
+                    export.rows_exported_total, export.rows_dropped_total, export.flush_failures_total
+                ),
+            );
+        } else if export.rows_dropped_total > 0 {
+            checks.record(
+                "telemetry_export",
+                CheckStatus::Warn,
+                format!(
+                    "{} exported ({} dropped)",
+                    export.rows_exported_total, export.rows_dropped_total
+                )
+                .yellow(),
+                format!(
+                    "{} exported ({} dropped)",
+                    export.rows_exported_total, export.rows_dropped_total
+                ),
+            );
+        } else {
+            checks.record(
+                "telemetry_export",
+                CheckStatus::Ok,
+                format!("{} exported", export.rows_exported_total).green(),
+                format!("{} exported", export.rows_exported_total),
+            );
+        }
     } else {
-        println!("{}", "⚠️  System has issues. See above.".bold().yellow());
+        checks.record(
+            "telemetry_export",
+            CheckStatus::Warn,
+            "N/A (export disabled)".dimmed(),
+            "N/A (export disabled)",
+        );
     }
 
-    Ok(())
+    Ok(finish(checks, format, true))
+}
+
+/// Renders the final verdict (text footer or JSON report) and returns the
+/// process exit code. `reachable` is false only when `/healthz` or
+/// `/status` couldn't be fetched at all, which maps to
+/// [`EXIT_UNREACHABLE`] regardless of what earlier checks found.
+fn finish(checks: Checks, format: OutputFormat, reachable: bool) -> i32 {
+    let healthy = reachable && checks.results.iter().all(|c| c.status != CheckStatus::Fail);
+
+    match format {
+        OutputFormat::Text => {
+            println!();
+            if healthy {
+                println!(
+                    "{}",
+                    "✅ System is healthy and ready for triage.".bold().green()
+                );
+            } else if reachable {
+                println!("{}", "⚠️  System has issues. See above.".bold().yellow());
+            } else {
+                println!("{}", "❌ Agent unreachable. See above.".bold().red());
+            }
+        }
+        OutputFormat::Json => {
+            let report = Report {
+                reachable,
+                healthy,
+                checks: checks.results,
+            };
+            println!("{}", serde_json::to_string_pretty(&report).unwrap());
+        }
+    }
+
+    if !reachable {
+        EXIT_UNREACHABLE
+    } else if healthy {
+        EXIT_HEALTHY
+    } else {
+        EXIT_UNHEALTHY
+    }
 }
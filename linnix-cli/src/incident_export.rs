@@ -0,0 +1,54 @@
+//! `linnix export-incidents` / `linnix import-incidents` -- pipe the
+//! daemon's incident history between hosts or to/from cold storage as
+//! newline-delimited JSON, hitting cognitod's `/admin/incidents/export` and
+//! `/admin/incidents/import` endpoints.
+
+use crate::ImportIdMode;
+use reqwest::Client;
+use std::error::Error;
+use std::io::{self, Read, Write};
+
+pub async fn run_export_incidents(client: &Client, url: &str) -> Result<(), Box<dyn Error>> {
+    let resp = client
+        .get(format!("{url}/admin/incidents/export"))
+        .send()
+        .await?;
+
+    if !resp.status().is_success() {
+        eprintln!("Export failed: {}", resp.status());
+        return Ok(());
+    }
+
+    let body = resp.bytes().await?;
+    io::stdout().write_all(&body)?;
+    Ok(())
+}
+
+pub async fn run_import_incidents(
+    client: &Client,
+    url: &str,
+    id_mode: ImportIdMode,
+) -> Result<(), Box<dyn Error>> {
+    let mut jsonl = String::new();
+    io::stdin().read_to_string(&mut jsonl)?;
+
+    let id_mode = match id_mode {
+        ImportIdMode::Preserve => "preserve",
+        ImportIdMode::Reassign => "reassign",
+    };
+
+    let resp = client
+        .post(format!("{url}/admin/incidents/import"))
+        .query(&[("id_mode", id_mode)])
+        .body(jsonl)
+        .send()
+        .await?;
+
+    if resp.status().is_success() {
+        let body: serde_json::Value = resp.json().await?;
+        println!("Imported {} incident(s).", body["inserted"]);
+    } else {
+        eprintln!("Import failed: {}", resp.status());
+    }
+    Ok(())
+}
@@ -10,9 +10,12 @@ mod blame;
 mod doctor;
 mod event;
 mod export;
+mod incident_export;
 mod pretty;
 mod processes;
+mod remediate;
 mod sse;
+mod watch;
 use alert::Alert;
 use event::ProcessEvent;
 use export::{export_incident, Format};
@@ -36,6 +39,18 @@ struct Args {
     #[clap(long)]
     no_color: bool,
 
+    /// Reconnect automatically on stream drop, replaying from Last-Event-ID
+    #[clap(long)]
+    reconnect: bool,
+
+    /// Maximum reconnect attempts before giving up (only with --reconnect; unset retries forever)
+    #[clap(long)]
+    max_retries: Option<u32>,
+
+    /// Replay history since this long ago (e.g. 15m, 1h) before switching to live streaming
+    #[clap(long)]
+    since: Option<String>,
+
     /// Subcommands
     #[clap(subcommand)]
     command: Option<Command>,
@@ -70,9 +85,57 @@ enum Command {
         rating: FeedbackRating,
     },
     /// Check system health and connectivity
-    Doctor,
+    Doctor {
+        /// Output format
+        #[clap(long, value_enum, default_value = "text")]
+        format: doctor::OutputFormat,
+    },
     /// List running processes with priority
     Processes,
+    /// Act on a classified incident: signal the offending PID or renice a runaway tree
+    Remediate {
+        /// Insight ID to act on
+        id: String,
+        /// Action to take
+        #[clap(subcommand)]
+        action: RemediateAction,
+        /// Actually perform the action (default is dry-run: print what would happen)
+        #[clap(long)]
+        execute: bool,
+    },
+    /// Stream every stored incident as newline-delimited JSON to stdout
+    ExportIncidents,
+    /// Bulk-load newline-delimited JSON incidents from stdin
+    ImportIncidents {
+        /// Keep each record's original id (exact mirror restore) instead of
+        /// letting the daemon assign fresh ones (merging into an existing store)
+        #[clap(long)]
+        preserve_ids: bool,
+    },
+    /// Tail decoded process/network/block/pagefault events live
+    Watch {
+        /// Only show events from this PID
+        #[clap(long)]
+        pid: Option<u32>,
+        /// Only show events from processes with this `comm` (process events only)
+        #[clap(long)]
+        comm: Option<String>,
+        /// Only show events of this type
+        #[clap(long, value_enum)]
+        event_type: Option<watch::EventTypeArg>,
+        /// Only show process events at or above this CPU percentage
+        #[clap(long)]
+        min_cpu_percent: Option<f32>,
+        /// Only show process events at or above this memory percentage
+        #[clap(long)]
+        min_mem_percent: Option<f32>,
+    },
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum ImportIdMode {
+    Preserve,
+    Reassign,
 }
 
 #[derive(clap::ValueEnum, Clone, Debug, serde::Serialize)]
@@ -82,6 +145,26 @@ enum FeedbackRating {
     Noise,
 }
 
+#[derive(Subcommand, Debug, Clone)]
+pub(crate) enum RemediateAction {
+    /// Send a signal to the offending PID
+    Signal {
+        #[clap(value_enum, default_value = "term")]
+        signal: SignalKind,
+    },
+    /// Renice a runaway process tree
+    Renice {
+        /// Target niceness (-20 to 19)
+        nice: i32,
+    },
+}
+
+#[derive(clap::ValueEnum, Clone, Debug)]
+pub(crate) enum SignalKind {
+    Term,
+    Kill,
+}
+
 #[derive(Deserialize, Debug)]
 struct Status {
     cpu_pct: f64,
@@ -131,9 +214,9 @@ async fn main() -> Result<(), Box<dyn Error>> {
         return Ok(());
     }
 
-    if let Some(Command::Doctor) = args.command {
-        doctor::run_doctor(&args.url).await?;
-        return Ok(());
+    if let Some(Command::Doctor { format }) = args.command {
+        let code = doctor::run_doctor(&args.url, format).await?;
+        std::process::exit(code);
     }
 
     if let Some(Command::Processes) = args.command {
@@ -141,6 +224,55 @@ async fn main() -> Result<(), Box<dyn Error>> {
         return Ok(());
     }
 
+    if let Some(Command::Remediate { id, action, execute }) = args.command.clone() {
+        remediate::run_remediate(&client, &args.url, &id, action, execute).await?;
+        return Ok(());
+    }
+
+    if let Some(Command::ExportIncidents) = args.command {
+        incident_export::run_export_incidents(&client, &args.url).await?;
+        return Ok(());
+    }
+
+    if let Some(Command::ImportIncidents { preserve_ids }) = args.command {
+        let id_mode = if preserve_ids {
+            ImportIdMode::Preserve
+        } else {
+            ImportIdMode::Reassign
+        };
+        incident_export::run_import_incidents(&client, &args.url, id_mode).await?;
+        return Ok(());
+    }
+
+    if let Some(Command::Watch { pid, comm, event_type, min_cpu_percent, min_mem_percent }) = args.command.clone() {
+        let filter = watch::WatchFilter {
+            pid,
+            comm,
+            event_type,
+            min_cpu_percent,
+            min_mem_percent,
+        };
+        let reconnect_config = sse::ReconnectConfig {
+            enabled: args.reconnect,
+            max_retries: args.max_retries,
+            ..sse::ReconnectConfig::default()
+        };
+        let url = format!("{}/stream", args.url);
+        let symbolizer = linnix_ai_ebpf_common::symbolize::Symbolizer::new();
+        let mut stream = watch::connect_watch(&client, &url, filter, reconnect_config).await?;
+        while let Some(payload) = stream.next().await {
+            match payload {
+                Ok(watch::Payload::Event { seq, frame }) => println!("{}", watch::render(seq, &frame, &symbolizer)),
+                Ok(watch::Payload::Response(watch::ResponseNote::Connected)) => eprintln!("-- connected --"),
+                Ok(watch::Payload::Response(watch::ResponseNote::SeqGap { expected, got })) => {
+                    eprintln!("-- gap detected: expected seq {expected}, got {got} --")
+                }
+                Err(e) => eprintln!("Error watching stream: {e}"),
+            }
+        }
+        return Ok(());
+    }
+
     if args.stats {
         let status: Status = client
             .get(format!("{}/status", args.url))
@@ -165,8 +297,15 @@ async fn main() -> Result<(), Box<dyn Error>> {
         return Ok(());
     }
 
+    let reconnect_config = sse::ReconnectConfig {
+        enabled: args.reconnect,
+        max_retries: args.max_retries,
+        ..sse::ReconnectConfig::default()
+    };
+
     if args.alerts {
-        let mut stream = sse::connect_sse(&client, &format!("{}/alerts", args.url)).await?;
+        let url = with_since_param(&format!("{}/alerts", args.url), args.since.as_deref());
+        let mut stream = sse::connect_sse_with(&client, &url, reconnect_config).await?;
         let mut seen: HashSet<Alert> = HashSet::new();
         while let Some(event) = stream.next().await {
             match event {
@@ -179,16 +318,16 @@ async fn main() -> Result<(), Box<dyn Error>> {
                     }
                 }
                 Ok(sse::SseEvent::Heartbeat) => {}
-                Err(e) => {
-                    eprintln!("Error reading SSE: {e}");
-                    break;
-                }
+                Ok(sse::SseEvent::HistoryStart) => eprintln!("-- replaying history --"),
+                Ok(sse::SseEvent::HistoryEnd) => eprintln!("-- live --"),
+                Err(e) => eprintln!("Error reading SSE: {e}"),
             }
         }
         return Ok(());
     }
 
-    let mut stream = sse::connect_sse(&client, &format!("{}/stream", args.url)).await?;
+    let url = with_since_param(&format!("{}/stream", args.url), args.since.as_deref());
+    let mut stream = sse::connect_sse_with(&client, &url, reconnect_config).await?;
 
     while let Some(event) = stream.next().await {
         match event {
@@ -203,11 +342,18 @@ async fn main() -> Result<(), Box<dyn Error>> {
                 }
             }
             Ok(sse::SseEvent::Heartbeat) => {}
-            Err(e) => {
-                eprintln!("Error reading SSE: {e}");
-                break;
-            }
+            Ok(sse::SseEvent::HistoryStart) => eprintln!("-- replaying history --"),
+            Ok(sse::SseEvent::HistoryEnd) => eprintln!("-- live --"),
+            Err(e) => eprintln!("Error reading SSE: {e}"),
         }
     }
     Ok(())
 }
+
+/// Append a `?since=` query parameter when `--since` was given.
+fn with_since_param(url: &str, since: Option<&str>) -> String {
+    match since {
+        Some(since) => format!("{url}?since={since}"),
+        None => url.to_string(),
+    }
+}
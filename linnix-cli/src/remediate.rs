@@ -0,0 +1,80 @@
+//! `linnix remediate` -- act on a classified incident: signal the offending
+//! PID or renice a runaway tree. Defaults to a dry run that only prints what
+//! would happen; pass `--execute` to actually perform it. The daemon
+//! re-validates the target PID's identity (comm + start time) against what
+//! the insight recorded before acting, so a PID recycled by an unrelated
+//! process is never touched.
+
+use crate::{RemediateAction, SignalKind};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+enum RemediateRequest {
+    Signal { signal: String },
+    Renice { nice: i32 },
+}
+
+#[derive(Debug, Deserialize)]
+struct RemediateResponse {
+    outcome: String,
+    detail: String,
+}
+
+pub async fn run_remediate(
+    client: &Client,
+    url: &str,
+    insight_id: &str,
+    action: RemediateAction,
+    execute: bool,
+) -> Result<(), Box<dyn Error>> {
+    let request = to_request(action);
+
+    if !execute {
+        println!(
+            "[dry-run] would {} for insight {insight_id} (pass --execute to perform it)",
+            describe(&request)
+        );
+        return Ok(());
+    }
+
+    let resp = client
+        .post(format!("{url}/insights/{insight_id}/remediate"))
+        .json(&request)
+        .send()
+        .await?;
+
+    if resp.status().is_success() {
+        let body: RemediateResponse = resp.json().await?;
+        println!("{}: {}", body.outcome, body.detail);
+    } else {
+        eprintln!("Remediation failed: {}", resp.status());
+    }
+
+    Ok(())
+}
+
+fn to_request(action: RemediateAction) -> RemediateRequest {
+    match action {
+        RemediateAction::Signal { signal } => RemediateRequest::Signal {
+            signal: signal_name(&signal).to_string(),
+        },
+        RemediateAction::Renice { nice } => RemediateRequest::Renice { nice },
+    }
+}
+
+fn signal_name(signal: &SignalKind) -> &'static str {
+    match signal {
+        SignalKind::Term => "SIGTERM",
+        SignalKind::Kill => "SIGKILL",
+    }
+}
+
+fn describe(request: &RemediateRequest) -> String {
+    match request {
+        RemediateRequest::Signal { signal } => format!("send {signal}"),
+        RemediateRequest::Renice { nice } => format!("renice to {nice}"),
+    }
+}
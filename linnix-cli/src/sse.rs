@@ -0,0 +1,321 @@
+//! Resilient Server-Sent Events client
+//!
+//! Wraps a streaming GET in a background task that reconnects on connection
+//! drop or server error with exponential backoff, resending `Last-Event-ID`
+//! so the server can resume without gaps. `: heartbeat` comments and real
+//! events both reset a watchdog timer; if neither arrives in time the
+//! connection is treated as dead and reconnected.
+
+use futures_util::{Stream, StreamExt};
+use reqwest::Client;
+use std::error::Error;
+use std::fmt;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time::timeout;
+
+/// One item read off the stream: a parsed `data:` event, a liveness-only
+/// `: heartbeat` comment, or one of the `history-start`/`history-end`
+/// markers a `?since=` backfill uses to bracket replayed records before
+/// live streaming resumes.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SseEvent {
+    Message(String),
+    Heartbeat,
+    HistoryStart,
+    HistoryEnd,
+}
+
+#[derive(Debug)]
+pub struct SseError(String);
+
+impl fmt::Display for SseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for SseError {}
+
+/// Reconnection behavior for [`connect_sse_with`].
+#[derive(Debug, Clone)]
+pub struct ReconnectConfig {
+    /// Reconnect automatically on connection drop, server error, or watchdog timeout.
+    pub enabled: bool,
+    /// Give up after this many consecutive failed attempts (`None` retries forever).
+    pub max_retries: Option<u32>,
+    /// Backoff before the first retry.
+    pub initial_backoff: Duration,
+    /// Backoff ceiling; doubled on each consecutive failure up to this value.
+    pub max_backoff: Duration,
+    /// Force a reconnect if neither an event nor a heartbeat arrives within this window.
+    pub watchdog_timeout: Duration,
+}
+
+impl Default for ReconnectConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_retries: None,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            watchdog_timeout: Duration::from_secs(60),
+        }
+    }
+}
+
+/// Connect with the default (reconnect-disabled, one-shot) configuration.
+pub async fn connect_sse(
+    client: &Client,
+    url: &str,
+) -> Result<impl Stream<Item = Result<SseEvent, SseError>>, Box<dyn Error + Send + Sync>> {
+    connect_sse_with(client, url, ReconnectConfig::default()).await
+}
+
+/// Connect and stream events, reconnecting per `config` on drop or error.
+pub async fn connect_sse_with(
+    client: &Client,
+    url: &str,
+    config: ReconnectConfig,
+) -> Result<impl Stream<Item = Result<SseEvent, SseError>>, Box<dyn Error + Send + Sync>> {
+    let (tx, rx) = mpsc::unbounded_channel();
+    tokio::spawn(run(client.clone(), url.to_string(), config, tx));
+    Ok(ReceiverStream { rx })
+}
+
+struct ReceiverStream {
+    rx: mpsc::UnboundedReceiver<Result<SseEvent, SseError>>,
+}
+
+impl Stream for ReceiverStream {
+    type Item = Result<SseEvent, SseError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.rx.poll_recv(cx)
+    }
+}
+
+async fn run(
+    client: Client,
+    url: String,
+    config: ReconnectConfig,
+    tx: mpsc::UnboundedSender<Result<SseEvent, SseError>>,
+) {
+    let mut last_event_id: Option<String> = None;
+    let mut backoff = config.initial_backoff;
+    let mut attempt: u32 = 0;
+
+    loop {
+        let mut request = client.get(&url).header("Accept", "text/event-stream");
+        if let Some(id) = &last_event_id {
+            request = request.header("Last-Event-ID", id.clone());
+        }
+
+        let response = match request.send().await {
+            Ok(resp) if resp.status().is_success() => resp,
+            Ok(resp) => {
+                let _ = tx.send(Err(SseError(format!(
+                    "server returned {}",
+                    resp.status()
+                ))));
+                if !should_retry(&config, &mut attempt) {
+                    return;
+                }
+                tokio::time::sleep(backoff).await;
+                backoff = next_backoff(backoff, &config);
+                continue;
+            }
+            Err(err) => {
+                let _ = tx.send(Err(SseError(format!("connection failed: {err}"))));
+                if !should_retry(&config, &mut attempt) {
+                    return;
+                }
+                tokio::time::sleep(backoff).await;
+                backoff = next_backoff(backoff, &config);
+                continue;
+            }
+        };
+
+        attempt = 0;
+        backoff = config.initial_backoff;
+
+        let mut byte_stream = response.bytes_stream();
+        let mut buf = String::new();
+        let mut retry_override: Option<Duration> = None;
+
+        loop {
+            match timeout(config.watchdog_timeout, byte_stream.next()).await {
+                Ok(Some(Ok(chunk))) => {
+                    buf.push_str(&String::from_utf8_lossy(&chunk));
+                    while let Some(pos) = buf.find("\n\n") {
+                        let raw_event: String = buf.drain(..pos + 2).collect();
+                        if let Some(event) =
+                            parse_event(&raw_event, &mut last_event_id, &mut retry_override)
+                            && tx.send(Ok(event)).is_err()
+                        {
+                            return;
+                        }
+                    }
+                }
+                Ok(Some(Err(err))) => {
+                    let _ = tx.send(Err(SseError(format!("stream error: {err}"))));
+                    break;
+                }
+                Ok(None) => break,
+                Err(_) => {
+                    let _ = tx.send(Err(SseError(
+                        "watchdog timeout: no data received".to_string(),
+                    )));
+                    break;
+                }
+            }
+        }
+
+        if !config.enabled {
+            return;
+        }
+        if let Some(custom) = retry_override.take() {
+            backoff = custom;
+        }
+        if !should_retry(&config, &mut attempt) {
+            return;
+        }
+        tokio::time::sleep(backoff).await;
+        backoff = next_backoff(backoff, &config);
+    }
+}
+
+fn should_retry(config: &ReconnectConfig, attempt: &mut u32) -> bool {
+    if !config.enabled {
+        return false;
+    }
+    *attempt += 1;
+    match config.max_retries {
+        Some(max) => *attempt <= max,
+        None => true,
+    }
+}
+
+fn next_backoff(current: Duration, config: &ReconnectConfig) -> Duration {
+    std::cmp::min(current * 2, config.max_backoff)
+}
+
+/// Parse one `\n\n`-terminated SSE block, updating `last_event_id`/`retry_override`
+/// from its `id:`/`retry:` fields as a side effect.
+fn parse_event(
+    raw: &str,
+    last_event_id: &mut Option<String>,
+    retry_override: &mut Option<Duration>,
+) -> Option<SseEvent> {
+    let mut data_lines: Vec<&str> = Vec::new();
+    let mut saw_heartbeat = false;
+    let mut event_name: Option<&str> = None;
+
+    for line in raw.lines() {
+        if let Some(rest) = line.strip_prefix("data:") {
+            data_lines.push(rest.strip_prefix(' ').unwrap_or(rest));
+        } else if let Some(rest) = line.strip_prefix("event:") {
+            event_name = Some(rest.trim());
+        } else if let Some(rest) = line.strip_prefix("id:") {
+            let id = rest.trim();
+            if !id.is_empty() {
+                *last_event_id = Some(id.to_string());
+            }
+        } else if let Some(rest) = line.strip_prefix("retry:")
+            && let Ok(ms) = rest.trim().parse::<u64>()
+        {
+            *retry_override = Some(Duration::from_millis(ms));
+        } else if let Some(comment) = line.strip_prefix(':')
+            && comment.trim() == "heartbeat"
+        {
+            saw_heartbeat = true;
+        }
+    }
+
+    match event_name {
+        Some("history-start") => return Some(SseEvent::HistoryStart),
+        Some("history-end") => return Some(SseEvent::HistoryEnd),
+        _ => {}
+    }
+
+    if !data_lines.is_empty() {
+        Some(SseEvent::Message(format!("data: {}", data_lines.join("\n"))))
+    } else if saw_heartbeat {
+        Some(SseEvent::Heartbeat)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_data_event() {
+        let mut last_id = None;
+        let mut retry = None;
+        let event = parse_event("data: {\"pid\":1}\n\n", &mut last_id, &mut retry).unwrap();
+        assert_eq!(event, SseEvent::Message("data: {\"pid\":1}".to_string()));
+    }
+
+    #[test]
+    fn parses_heartbeat_comment() {
+        let mut last_id = None;
+        let mut retry = None;
+        let event = parse_event(": heartbeat\n\n", &mut last_id, &mut retry).unwrap();
+        assert_eq!(event, SseEvent::Heartbeat);
+    }
+
+    #[test]
+    fn captures_id_and_retry_fields() {
+        let mut last_id = None;
+        let mut retry = None;
+        parse_event(
+            "id: 42\nretry: 2000\ndata: hello\n\n",
+            &mut last_id,
+            &mut retry,
+        );
+        assert_eq!(last_id, Some("42".to_string()));
+        assert_eq!(retry, Some(Duration::from_millis(2000)));
+    }
+
+    #[test]
+    fn ignores_event_only_blocks() {
+        let mut last_id = None;
+        let mut retry = None;
+        let event = parse_event("event: ping\n\n", &mut last_id, &mut retry);
+        assert!(event.is_none());
+    }
+
+    #[test]
+    fn recognizes_history_markers() {
+        let mut last_id = None;
+        let mut retry = None;
+        assert_eq!(
+            parse_event("event: history-start\n\n", &mut last_id, &mut retry),
+            Some(SseEvent::HistoryStart)
+        );
+        assert_eq!(
+            parse_event("event: history-end\n\n", &mut last_id, &mut retry),
+            Some(SseEvent::HistoryEnd)
+        );
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_ceiling() {
+        let config = ReconnectConfig {
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(300),
+            ..Default::default()
+        };
+        let b1 = next_backoff(config.initial_backoff, &config);
+        let b2 = next_backoff(b1, &config);
+        let b3 = next_backoff(b2, &config);
+        assert_eq!(b1, Duration::from_millis(200));
+        assert_eq!(b2, Duration::from_millis(300));
+        assert_eq!(b3, Duration::from_millis(300));
+    }
+}
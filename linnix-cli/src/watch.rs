@@ -0,0 +1,563 @@
+//! Long-lived `watch` transport: tails `/stream`'s decoded events in real
+//! time instead of the one-shot `/status` poll, rendering while a
+//! background task drains the socket.
+//!
+//! Structured as an async message loop, mirroring [`crate::sse`]'s
+//! reconnect-with-backoff shape: a background task reads the response
+//! body and feeds an unbounded `mpsc` channel of [`Payload`] values, so the
+//! foreground can render each event as it arrives instead of blocking on
+//! the socket itself. The server's binary frame format
+//! (`linnix_ai_ebpf_common::frame`) is decoded directly when negotiated
+//! via `Accept`, falling back to newline-delimited JSON otherwise.
+//!
+//! Frames arrive in order within one TCP connection, so there's nothing
+//! to reorder here (unlike the fan-out producer side `frame::Reorderer`
+//! targets); what a reconnect *can* lose is whatever was in flight during
+//! the drop, so `run` tracks the last `seq` seen and reports a
+//! [`ResponseNote::SeqGap`] the next time the numbering doesn't pick up
+//! where it left off.
+
+use futures_util::{Stream, StreamExt};
+use linnix_ai_ebpf_common::frame::{self, EventFrame};
+use linnix_ai_ebpf_common::symbolize::Symbolizer;
+use linnix_ai_ebpf_common::{
+    BlockIoEvent, EventType, FileIoEvent, NetEvent, PageFaultEvent, ProcessEvent, RssTraceEvent,
+    SyscallEvent,
+};
+use reqwest::Client;
+use serde::Deserialize;
+use std::error::Error;
+use std::fmt;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::sync::mpsc;
+use tokio::time::timeout;
+
+use crate::sse::ReconnectConfig;
+
+/// One item delivered to the foreground: a decoded event, or a note about
+/// the connection itself that doesn't carry a `seq` of its own.
+#[derive(Debug, Clone)]
+pub enum Payload {
+    Event { seq: u64, frame: EventFrame },
+    Response(ResponseNote),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResponseNote {
+    Connected,
+    /// The `seq` stream skipped ahead, almost always because events were
+    /// dropped during a reconnect.
+    SeqGap { expected: u64, got: u64 },
+}
+
+#[derive(Debug)]
+pub struct WatchError(String);
+
+impl fmt::Display for WatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for WatchError {}
+
+/// Which event categories `--event-type` accepts; mirrors [`EventType`]'s
+/// discriminants (a local copy since `clap::ValueEnum` can't be derived on
+/// a type from another crate).
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+#[clap(rename_all = "snake_case")]
+pub enum EventTypeArg {
+    Exec,
+    Fork,
+    Exit,
+    Net,
+    FileIo,
+    Syscall,
+    BlockIo,
+    PageFault,
+    RssTrace,
+}
+
+/// Criteria for `watch`'s `--pid`/`--comm`/`--event-type`/`--min-cpu-percent`/
+/// `--min-mem-percent` flags. `comm` and the percent thresholds only have
+/// meaning for [`EventFrame::Process`]; other event types pass them
+/// unfiltered since they don't carry that data.
+#[derive(Debug, Clone, Default)]
+pub struct WatchFilter {
+    pub pid: Option<u32>,
+    pub comm: Option<String>,
+    pub event_type: Option<EventTypeArg>,
+    pub min_cpu_percent: Option<f32>,
+    pub min_mem_percent: Option<f32>,
+}
+
+impl WatchFilter {
+    fn matches(&self, frame: &EventFrame) -> bool {
+        if let Some(want) = self.pid
+            && pid_of(frame) != want
+        {
+            return false;
+        }
+        if let Some(want) = &self.event_type
+            && !matches_event_type(frame, *want)
+        {
+            return false;
+        }
+        if let EventFrame::Process(e) = frame {
+            if let Some(want) = &self.comm
+                && comm_of(e) != *want
+            {
+                return false;
+            }
+            if let Some(min) = self.min_cpu_percent
+                && cpu_percent(e).is_none_or(|pct| pct < min)
+            {
+                return false;
+            }
+            if let Some(min) = self.min_mem_percent
+                && mem_percent(e).is_none_or(|pct| pct < min)
+            {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+fn pid_of(frame: &EventFrame) -> u32 {
+    match frame {
+        EventFrame::Process(e) => e.pid,
+        EventFrame::Net(e) => e.pid,
+        EventFrame::FileIo(e) => e.pid,
+        EventFrame::BlockIo(e) => e.pid,
+        EventFrame::PageFault(e) => e.pid,
+        EventFrame::RssTrace(e) => e.pid,
+        EventFrame::Syscall(e) => e.pid,
+    }
+}
+
+fn comm_of(event: &ProcessEvent) -> String {
+    let nul = event.comm.iter().position(|&b| b == 0).unwrap_or(event.comm.len());
+    String::from_utf8_lossy(&event.comm[..nul]).into_owned()
+}
+
+fn cpu_percent(event: &ProcessEvent) -> Option<f32> {
+    const UNKNOWN: u16 = u16::MAX;
+    (event.cpu_pct_milli != UNKNOWN).then(|| event.cpu_pct_milli as f32 / 1000.0)
+}
+
+fn mem_percent(event: &ProcessEvent) -> Option<f32> {
+    const UNKNOWN: u16 = u16::MAX;
+    (event.mem_pct_milli != UNKNOWN).then(|| event.mem_pct_milli as f32 / 1000.0)
+}
+
+fn matches_event_type(frame: &EventFrame, want: EventTypeArg) -> bool {
+    let actual = match frame {
+        EventFrame::Process(e) => match e.event_type {
+            x if x == EventType::Exec as u32 => EventTypeArg::Exec,
+            x if x == EventType::Fork as u32 => EventTypeArg::Fork,
+            x if x == EventType::Exit as u32 => EventTypeArg::Exit,
+            _ => return false,
+        },
+        EventFrame::Net(_) => EventTypeArg::Net,
+        EventFrame::FileIo(_) => EventTypeArg::FileIo,
+        EventFrame::BlockIo(_) => EventTypeArg::BlockIo,
+        EventFrame::PageFault(_) => EventTypeArg::PageFault,
+        EventFrame::RssTrace(_) => EventTypeArg::RssTrace,
+        EventFrame::Syscall(_) => EventTypeArg::Syscall,
+    };
+    actual == want
+}
+
+/// Connect to `{url}` and stream decoded, filtered events, reconnecting
+/// per `config` on drop or error.
+pub async fn connect_watch(
+    client: &Client,
+    url: &str,
+    filter: WatchFilter,
+    config: ReconnectConfig,
+) -> Result<impl Stream<Item = Result<Payload, WatchError>>, Box<dyn Error + Send + Sync>> {
+    let (tx, rx) = mpsc::unbounded_channel();
+    tokio::spawn(run(client.clone(), url.to_string(), filter, config, tx));
+    Ok(ReceiverStream { rx })
+}
+
+struct ReceiverStream {
+    rx: mpsc::UnboundedReceiver<Result<Payload, WatchError>>,
+}
+
+impl Stream for ReceiverStream {
+    type Item = Result<Payload, WatchError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.rx.poll_recv(cx)
+    }
+}
+
+async fn run(
+    client: Client,
+    url: String,
+    filter: WatchFilter,
+    config: ReconnectConfig,
+    tx: mpsc::UnboundedSender<Result<Payload, WatchError>>,
+) {
+    let mut backoff = config.initial_backoff;
+    let mut attempt: u32 = 0;
+    let mut last_seq: Option<u64> = None;
+
+    loop {
+        let request = client
+            .get(&url)
+            .header("Accept", "application/octet-stream, application/x-ndjson");
+
+        let response = match request.send().await {
+            Ok(resp) if resp.status().is_success() => resp,
+            Ok(resp) => {
+                let _ = tx.send(Err(WatchError(format!("server returned {}", resp.status()))));
+                if !should_retry(&config, &mut attempt) {
+                    return;
+                }
+                tokio::time::sleep(backoff).await;
+                backoff = next_backoff(backoff, &config);
+                continue;
+            }
+            Err(err) => {
+                let _ = tx.send(Err(WatchError(format!("connection failed: {err}"))));
+                if !should_retry(&config, &mut attempt) {
+                    return;
+                }
+                tokio::time::sleep(backoff).await;
+                backoff = next_backoff(backoff, &config);
+                continue;
+            }
+        };
+
+        attempt = 0;
+        backoff = config.initial_backoff;
+        let _ = tx.send(Ok(Payload::Response(ResponseNote::Connected)));
+
+        let binary = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|ct| ct.contains("octet-stream"));
+
+        let mut byte_stream = response.bytes_stream();
+        let mut bin_buf: Vec<u8> = Vec::new();
+        let mut line_buf = String::new();
+
+        loop {
+            match timeout(config.watchdog_timeout, byte_stream.next()).await {
+                Ok(Some(Ok(chunk))) => {
+                    if binary {
+                        bin_buf.extend_from_slice(&chunk);
+                        if !drain_binary(&mut bin_buf, &filter, &mut last_seq, &tx) {
+                            break;
+                        }
+                    } else {
+                        line_buf.push_str(&String::from_utf8_lossy(&chunk));
+                        drain_lines(&mut line_buf, &filter, &mut last_seq, &tx);
+                    }
+                }
+                Ok(Some(Err(err))) => {
+                    let _ = tx.send(Err(WatchError(format!("stream error: {err}"))));
+                    break;
+                }
+                Ok(None) => break,
+                Err(_) => {
+                    let _ = tx.send(Err(WatchError("watchdog timeout: no data received".to_string())));
+                    break;
+                }
+            }
+        }
+
+        if !config.enabled {
+            return;
+        }
+        if !should_retry(&config, &mut attempt) {
+            return;
+        }
+        tokio::time::sleep(backoff).await;
+        backoff = next_backoff(backoff, &config);
+    }
+}
+
+/// Decode as many complete binary frames as `buf` holds, forwarding each
+/// that survives `filter`. Returns `false` if the stream desynchronized
+/// and the connection should be dropped and retried.
+fn drain_binary(
+    buf: &mut Vec<u8>,
+    filter: &WatchFilter,
+    last_seq: &mut Option<u64>,
+    tx: &mpsc::UnboundedSender<Result<Payload, WatchError>>,
+) -> bool {
+    loop {
+        match frame::decode(buf) {
+            Ok((seq, decoded, consumed)) => {
+                buf.drain(..consumed);
+                if !deliver(seq, decoded, filter, last_seq, tx) {
+                    return true;
+                }
+            }
+            Err(frame::FrameError::Truncated) => return true,
+            Err(err) => {
+                let _ = tx.send(Err(WatchError(format!("frame decode error: {err}"))));
+                return false;
+            }
+        }
+    }
+}
+
+/// Parse as many complete newline-delimited JSON records as `line_buf`
+/// holds, forwarding each that survives `filter`.
+fn drain_lines(
+    line_buf: &mut String,
+    filter: &WatchFilter,
+    last_seq: &mut Option<u64>,
+    tx: &mpsc::UnboundedSender<Result<Payload, WatchError>>,
+) {
+    while let Some(pos) = line_buf.find('\n') {
+        let line: String = line_buf.drain(..=pos).collect();
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<JsonLine>(line) {
+            Ok(parsed) => {
+                if !deliver(parsed.seq, parsed.event.into(), filter, last_seq, tx) {
+                    return;
+                }
+            }
+            Err(err) => {
+                let _ = tx.send(Err(WatchError(format!("failed to parse event: {err}"))));
+            }
+        }
+    }
+}
+
+/// Report a gap if `seq` didn't pick up where `last_seq` left off, then
+/// forward the event itself if it survives `filter`. Returns `false` if
+/// the receiver hung up and the caller should stop reading.
+fn deliver(
+    seq: u64,
+    frame: EventFrame,
+    filter: &WatchFilter,
+    last_seq: &mut Option<u64>,
+    tx: &mpsc::UnboundedSender<Result<Payload, WatchError>>,
+) -> bool {
+    if let Some(prev) = *last_seq
+        && seq != prev + 1
+        && tx
+            .send(Ok(Payload::Response(ResponseNote::SeqGap { expected: prev + 1, got: seq })))
+            .is_err()
+    {
+        return false;
+    }
+    *last_seq = Some(seq);
+
+    if filter.matches(&frame) {
+        return tx.send(Ok(Payload::Event { seq, frame })).is_ok();
+    }
+    true
+}
+
+fn should_retry(config: &ReconnectConfig, attempt: &mut u32) -> bool {
+    if !config.enabled {
+        return false;
+    }
+    *attempt += 1;
+    match config.max_retries {
+        Some(max) => *attempt <= max,
+        None => true,
+    }
+}
+
+fn next_backoff(current: std::time::Duration, config: &ReconnectConfig) -> std::time::Duration {
+    std::cmp::min(current * 2, config.max_backoff)
+}
+
+/// Newline-delimited JSON fallback for when the server doesn't negotiate
+/// the binary frame format. Reuses the event structs' own `Deserialize`
+/// impls rather than duplicating their fields.
+#[derive(Deserialize)]
+struct JsonLine {
+    seq: u64,
+    #[serde(flatten)]
+    event: JsonEvent,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "event_kind", rename_all = "snake_case")]
+enum JsonEvent {
+    Process(ProcessEvent),
+    Net(NetEvent),
+    FileIo(FileIoEvent),
+    BlockIo(BlockIoEvent),
+    PageFault(PageFaultEvent),
+    RssTrace(RssTraceEvent),
+    Syscall(SyscallEvent),
+}
+
+impl From<JsonEvent> for EventFrame {
+    fn from(event: JsonEvent) -> Self {
+        match event {
+            JsonEvent::Process(e) => EventFrame::Process(e),
+            JsonEvent::Net(e) => EventFrame::Net(e),
+            JsonEvent::FileIo(e) => EventFrame::FileIo(e),
+            JsonEvent::BlockIo(e) => EventFrame::BlockIo(e),
+            JsonEvent::PageFault(e) => EventFrame::PageFault(e),
+            JsonEvent::RssTrace(e) => EventFrame::RssTrace(e),
+            JsonEvent::Syscall(e) => EventFrame::Syscall(e),
+        }
+    }
+}
+
+/// One-line rendering of a decoded frame for the `watch` subcommand.
+/// Page faults are symbolicated through `symbolizer` (a bare hex address is
+/// otherwise meaningless to an operator) by walking `/proc/<pid>/maps` on
+/// this host, so it only resolves faults from processes visible here --
+/// typically fine since `watch` is run on the same box as the traced
+/// workload, same as `remediate`'s direct pid signaling.
+pub fn render(seq: u64, frame: &EventFrame, symbolizer: &Symbolizer) -> String {
+    match frame {
+        EventFrame::Process(e) => format!(
+            "#{seq} pid={} ppid={} comm={} cpu%={} mem%={}",
+            e.pid,
+            e.ppid,
+            comm_of(e),
+            format_pct(cpu_percent(e)),
+            format_pct(mem_percent(e)),
+        ),
+        EventFrame::Net(e) => format!("#{seq} net pid={} bytes={}", e.pid, e.bytes),
+        EventFrame::FileIo(e) => format!("#{seq} file_io pid={} bytes={}", e.pid, e.bytes),
+        EventFrame::BlockIo(e) => {
+            format!("#{seq} block_io pid={} op={:?} bytes={} sector={}", e.pid, e.op, e.bytes, e.sector)
+        }
+        EventFrame::PageFault(e) => {
+            let sym = symbolizer.symbolicate_page_fault(e);
+            format!(
+                "#{seq} page_fault pid={} address={} ip={} origin={:?}",
+                e.pid,
+                format_resolved(e.address, &sym.address_resolved),
+                format_resolved(e.ip, &sym.ip_resolved),
+                e.origin,
+            )
+        }
+        EventFrame::RssTrace(e) => {
+            format!("#{seq} rss_trace pid={} member={} delta_pages={}", e.pid, e.member, e.delta_pages)
+        }
+        EventFrame::Syscall(e) => format!("#{seq} syscall pid={} nr={}", e.pid, e.syscall),
+    }
+}
+
+/// Render a resolved address as `module+offset (symbol)`, falling back to
+/// bare hex when the address didn't fall in any known mapping.
+fn format_resolved(addr: u64, resolved: &Option<linnix_ai_ebpf_common::symbolize::ResolvedAddress>) -> String {
+    match resolved {
+        Some(r) => match &r.symbol {
+            Some(symbol) => format!("{}+{:#x} ({symbol})", r.module, r.offset),
+            None => format!("{}+{:#x}", r.module, r.offset),
+        },
+        None => format!("{addr:#x}"),
+    }
+}
+
+fn format_pct(pct: Option<f32>) -> String {
+    match pct {
+        Some(value) => format!("{value:.1}"),
+        None => "-".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process_event(pid: u32, comm: &str, cpu_pct_milli: u16) -> ProcessEvent {
+        let mut comm_bytes = [0u8; 16];
+        comm_bytes[..comm.len()].copy_from_slice(comm.as_bytes());
+        ProcessEvent {
+            pid,
+            ppid: 1,
+            uid: 0,
+            gid: 0,
+            event_type: EventType::Exec as u32,
+            ts_ns: 0,
+            seq: 0,
+            comm: comm_bytes,
+            exit_time_ns: 0,
+            cpu_pct_milli,
+            mem_pct_milli: 0,
+            data: 0,
+            data2: 0,
+            aux: 0,
+            aux2: 0,
+        }
+    }
+
+    #[test]
+    fn filter_matches_on_pid() {
+        let filter = WatchFilter { pid: Some(7), ..Default::default() };
+        let frame = EventFrame::Net(NetEvent { pid: 7, _pad: 0, bytes: 0 });
+        assert!(filter.matches(&frame));
+        let frame = EventFrame::Net(NetEvent { pid: 8, _pad: 0, bytes: 0 });
+        assert!(!filter.matches(&frame));
+    }
+
+    #[test]
+    fn filter_matches_on_comm_only_for_process_events() {
+        let filter = WatchFilter { comm: Some("sshd".to_string()), ..Default::default() };
+        assert!(filter.matches(&EventFrame::Process(process_event(1, "sshd", 0))));
+        assert!(!filter.matches(&EventFrame::Process(process_event(1, "bash", 0))));
+        // Non-process events don't carry a comm, so they pass unfiltered.
+        assert!(filter.matches(&EventFrame::Net(NetEvent { pid: 1, _pad: 0, bytes: 0 })));
+    }
+
+    #[test]
+    fn filter_matches_on_min_cpu_percent() {
+        let filter = WatchFilter { min_cpu_percent: Some(50.0), ..Default::default() };
+        assert!(filter.matches(&EventFrame::Process(process_event(1, "x", 60_000))));
+        assert!(!filter.matches(&EventFrame::Process(process_event(1, "x", 10_000))));
+    }
+
+    #[test]
+    fn filter_matches_on_event_type() {
+        let filter = WatchFilter { event_type: Some(EventTypeArg::Net), ..Default::default() };
+        assert!(filter.matches(&EventFrame::Net(NetEvent { pid: 1, _pad: 0, bytes: 0 })));
+        assert!(!filter.matches(&EventFrame::FileIo(FileIoEvent { pid: 1, _pad: 0, bytes: 0 })));
+    }
+
+    #[test]
+    fn deliver_reports_a_gap_then_the_event() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut last_seq = Some(4);
+        let ok = deliver(
+            9,
+            EventFrame::Net(NetEvent { pid: 1, _pad: 0, bytes: 0 }),
+            &WatchFilter::default(),
+            &mut last_seq,
+            &tx,
+        );
+        assert!(ok);
+        assert_eq!(last_seq, Some(9));
+        match rx.try_recv().unwrap() {
+            Ok(Payload::Response(ResponseNote::SeqGap { expected: 5, got: 9 })) => {}
+            other => panic!("expected a seq gap, got {other:?}"),
+        }
+        assert!(matches!(rx.try_recv().unwrap(), Ok(Payload::Event { seq: 9, .. })));
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_ceiling() {
+        let config = ReconnectConfig {
+            initial_backoff: std::time::Duration::from_millis(100),
+            max_backoff: std::time::Duration::from_millis(300),
+            ..Default::default()
+        };
+        let b1 = next_backoff(config.initial_backoff, &config);
+        let b2 = next_backoff(b1, &config);
+        assert_eq!(b1, std::time::Duration::from_millis(200));
+        assert_eq!(b2, std::time::Duration::from_millis(300));
+    }
+}
@@ -0,0 +1,572 @@
+//! Small query language for `--filter`, e.g. `"cpu > 50 && comm ~ nginx"` or
+//! `"tag = oom || (uid = 0 && event = exec)"`.
+//!
+//! Tokenizer + recursive-descent parser producing a predicate tree, evaluated
+//! against either `ProcessEvent` (the live `--stream`) or `ProcessAlert` (the
+//! `--alerts` list) via the [`FilterFields`] trait, so the same compiled
+//! expression filters both client-side. Modeled on cognitod's triage-rule
+//! expression parser (`cognitod/src/triage.rs`): tokenize, then a
+//! precedence-climbing `Parser` over `||` < `&&` < `!` < comparisons < atoms.
+
+use std::fmt;
+
+/// A value on the right-hand side of a comparison: numeric literals compare
+/// numerically, everything else compares as a case-insensitive string/glob.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Num(f64),
+    Str(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Gt,
+    Lt,
+    Ge,
+    Le,
+    /// `~`: substring or `*`-glob match.
+    Match,
+}
+
+/// A compiled filter expression.
+#[derive(Debug, Clone)]
+pub enum Predicate {
+    Compare {
+        field: String,
+        op: CompareOp,
+        value: Value,
+    },
+    And(Box<Predicate>, Box<Predicate>),
+    Or(Box<Predicate>, Box<Predicate>),
+    Not(Box<Predicate>),
+}
+
+/// Fields a [`Predicate`] can be evaluated against. Implemented for both
+/// `ProcessEvent` and `ProcessAlert` so one compiled filter works for either.
+pub trait FilterFields {
+    /// Look up `pid`/`ppid`/`uid`/`gid`/`comm`/`event`/`cpu`/`mem`.
+    /// `None` means the field doesn't apply (e.g. unknown cpu/mem sample),
+    /// which never matches.
+    fn field(&self, name: &str) -> Option<Value>;
+    /// The `tag`/`tags` set, tested for membership rather than equality.
+    fn tags(&self) -> &[String];
+}
+
+const KNOWN_FIELDS: &[&str] = &[
+    "pid", "ppid", "uid", "gid", "comm", "event", "cpu", "mem", "tag", "tags",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub message: String,
+    pub column: usize,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "column {}: {}", self.column, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[derive(Debug, Clone)]
+struct Token {
+    text: String,
+    column: usize,
+}
+
+fn tokenize(src: &str) -> Result<Vec<Token>, ParseError> {
+    let chars: Vec<char> = src.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let column = i + 1;
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        if c == '(' || c == ')' {
+            tokens.push(Token {
+                text: c.to_string(),
+                column,
+            });
+            i += 1;
+            continue;
+        }
+        if c == '"' {
+            let start = i + 1;
+            let mut j = start;
+            while j < chars.len() && chars[j] != '"' {
+                j += 1;
+            }
+            if j >= chars.len() {
+                return Err(ParseError {
+                    message: "unterminated string literal".to_string(),
+                    column,
+                });
+            }
+            tokens.push(Token {
+                text: chars[start..j].iter().collect(),
+                column,
+            });
+            i = j + 1;
+            continue;
+        }
+        if c == '&' {
+            if chars.get(i + 1) == Some(&'&') {
+                tokens.push(Token {
+                    text: "&&".to_string(),
+                    column,
+                });
+                i += 2;
+                continue;
+            }
+            return Err(ParseError {
+                message: "expected '&&'".to_string(),
+                column,
+            });
+        }
+        if c == '|' {
+            if chars.get(i + 1) == Some(&'|') {
+                tokens.push(Token {
+                    text: "||".to_string(),
+                    column,
+                });
+                i += 2;
+                continue;
+            }
+            return Err(ParseError {
+                message: "expected '||'".to_string(),
+                column,
+            });
+        }
+        if c == '!' {
+            if chars.get(i + 1) == Some(&'=') {
+                tokens.push(Token {
+                    text: "!=".to_string(),
+                    column,
+                });
+                i += 2;
+            } else {
+                tokens.push(Token {
+                    text: "!".to_string(),
+                    column,
+                });
+                i += 1;
+            }
+            continue;
+        }
+        if c == '=' {
+            tokens.push(Token {
+                text: "=".to_string(),
+                column,
+            });
+            i += 1;
+            continue;
+        }
+        if c == '~' {
+            tokens.push(Token {
+                text: "~".to_string(),
+                column,
+            });
+            i += 1;
+            continue;
+        }
+        if c == '>' || c == '<' {
+            if chars.get(i + 1) == Some(&'=') {
+                tokens.push(Token {
+                    text: format!("{c}="),
+                    column,
+                });
+                i += 2;
+            } else {
+                tokens.push(Token {
+                    text: c.to_string(),
+                    column,
+                });
+                i += 1;
+            }
+            continue;
+        }
+        let start = i;
+        while i < chars.len() && !chars[i].is_whitespace() && !"()&|!=~<>\"".contains(chars[i]) {
+            i += 1;
+        }
+        tokens.push(Token {
+            text: chars[start..i].iter().collect(),
+            column: start + 1,
+        });
+    }
+    Ok(tokens)
+}
+
+/// Recursive-descent parser over the tokenized expression; precedence (low
+/// to high): `||` < `&&` < `!` < comparisons < parens/atoms.
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn new(tokens: Vec<Token>) -> Self {
+        Self { tokens, pos: 0 }
+    }
+
+    fn peek(&self) -> Option<&str> {
+        self.tokens.get(self.pos).map(|t| t.text.as_str())
+    }
+
+    fn peek_column(&self) -> usize {
+        match self.tokens.get(self.pos) {
+            Some(tok) => tok.column,
+            None => self
+                .tokens
+                .last()
+                .map(|t| t.column + t.text.chars().count())
+                .unwrap_or(1),
+        }
+    }
+
+    fn bump(&mut self) -> Option<Token> {
+        let tok = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        tok
+    }
+
+    fn expect(&mut self, expected: &str) -> Result<(), ParseError> {
+        match self.bump() {
+            Some(tok) if tok.text == expected => Ok(()),
+            Some(tok) => Err(ParseError {
+                message: format!("expected '{expected}', found '{}'", tok.text),
+                column: tok.column,
+            }),
+            None => Err(ParseError {
+                message: format!("expected '{expected}', found end of input"),
+                column: self.peek_column(),
+            }),
+        }
+    }
+
+    fn parse_expr(&mut self) -> Result<Predicate, ParseError> {
+        self.parse_or()
+    }
+
+    fn parse_or(&mut self) -> Result<Predicate, ParseError> {
+        let mut lhs = self.parse_and()?;
+        while self.peek() == Some("||") {
+            self.bump();
+            let rhs = self.parse_and()?;
+            lhs = Predicate::Or(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_and(&mut self) -> Result<Predicate, ParseError> {
+        let mut lhs = self.parse_not()?;
+        while self.peek() == Some("&&") {
+            self.bump();
+            let rhs = self.parse_not()?;
+            lhs = Predicate::And(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_not(&mut self) -> Result<Predicate, ParseError> {
+        if self.peek() == Some("!") {
+            self.bump();
+            return Ok(Predicate::Not(Box::new(self.parse_not()?)));
+        }
+        self.parse_atom()
+    }
+
+    fn parse_atom(&mut self) -> Result<Predicate, ParseError> {
+        if self.peek() == Some("(") {
+            self.bump();
+            let inner = self.parse_expr()?;
+            self.expect(")")?;
+            return Ok(inner);
+        }
+        self.parse_compare()
+    }
+
+    fn parse_compare(&mut self) -> Result<Predicate, ParseError> {
+        let field = self.bump().ok_or_else(|| ParseError {
+            message: "expected a field name".to_string(),
+            column: self.peek_column(),
+        })?;
+        if field.text == ")" {
+            return Err(ParseError {
+                message: "expected a field name, found ')'".to_string(),
+                column: field.column,
+            });
+        }
+        let field_name = field.text.to_lowercase();
+        if !KNOWN_FIELDS.contains(&field_name.as_str()) {
+            return Err(ParseError {
+                message: format!(
+                    "unknown field '{}' (expected one of: {})",
+                    field.text,
+                    KNOWN_FIELDS.join(", ")
+                ),
+                column: field.column,
+            });
+        }
+
+        let op_tok = self.bump().ok_or_else(|| ParseError {
+            message: "expected a comparison operator".to_string(),
+            column: self.peek_column(),
+        })?;
+        let op = match op_tok.text.as_str() {
+            "=" => CompareOp::Eq,
+            "!=" => CompareOp::Ne,
+            ">" => CompareOp::Gt,
+            "<" => CompareOp::Lt,
+            ">=" => CompareOp::Ge,
+            "<=" => CompareOp::Le,
+            "~" => CompareOp::Match,
+            other => {
+                return Err(ParseError {
+                    message: format!("expected a comparison operator, found '{other}'"),
+                    column: op_tok.column,
+                });
+            }
+        };
+
+        let value_tok = self.bump().ok_or_else(|| ParseError {
+            message: "expected a value".to_string(),
+            column: self.peek_column(),
+        })?;
+        let value = match value_tok.text.parse::<f64>() {
+            Ok(n) => Value::Num(n),
+            Err(_) => Value::Str(value_tok.text),
+        };
+
+        Ok(Predicate::Compare {
+            field: field_name,
+            op,
+            value,
+        })
+    }
+}
+
+/// Parse a filter expression into a compiled [`Predicate`]. Reports a clear
+/// error naming the offending column rather than matching nothing on a typo.
+pub fn parse(query: &str) -> Result<Predicate, ParseError> {
+    let tokens = tokenize(query)?;
+    if tokens.is_empty() {
+        return Err(ParseError {
+            message: "empty filter expression".to_string(),
+            column: 1,
+        });
+    }
+    let mut parser = Parser::new(tokens);
+    let predicate = parser.parse_expr()?;
+    if let Some(tok) = parser.tokens.get(parser.pos) {
+        return Err(ParseError {
+            message: format!("unexpected trailing token '{}'", tok.text),
+            column: tok.column,
+        });
+    }
+    Ok(predicate)
+}
+
+fn glob_match(haystack: &str, pattern: &str) -> bool {
+    let haystack = haystack.to_lowercase();
+    let pattern = pattern.to_lowercase();
+    if !pattern.contains('*') {
+        return haystack.contains(&pattern);
+    }
+    let parts: Vec<&str> = pattern.split('*').collect();
+    let mut pos = 0;
+    for (i, part) in parts.iter().enumerate() {
+        if part.is_empty() {
+            continue;
+        }
+        match haystack[pos..].find(part) {
+            Some(found) => {
+                if i == 0 && !pattern.starts_with('*') && found != 0 {
+                    return false;
+                }
+                pos += found + part.len();
+            }
+            None => return false,
+        }
+    }
+    if !pattern.ends_with('*') {
+        if let Some(last) = parts.last() {
+            if !last.is_empty() {
+                return haystack.ends_with(last);
+            }
+        }
+    }
+    true
+}
+
+fn compare_num(a: f64, op: CompareOp, b: f64) -> bool {
+    match op {
+        CompareOp::Eq => (a - b).abs() < f64::EPSILON,
+        CompareOp::Ne => (a - b).abs() >= f64::EPSILON,
+        CompareOp::Gt => a > b,
+        CompareOp::Lt => a < b,
+        CompareOp::Ge => a >= b,
+        CompareOp::Le => a <= b,
+        CompareOp::Match => a.to_string().contains(&b.to_string()),
+    }
+}
+
+fn compare_num_to_str(a: f64, op: CompareOp, b: &str) -> bool {
+    if op == CompareOp::Match {
+        return a.to_string().contains(b);
+    }
+    match b.parse::<f64>() {
+        Ok(b) => compare_num(a, op, b),
+        Err(_) => false,
+    }
+}
+
+fn compare_str(a: &str, op: CompareOp, b: &str) -> bool {
+    match op {
+        CompareOp::Eq => a.eq_ignore_ascii_case(b),
+        CompareOp::Ne => !a.eq_ignore_ascii_case(b),
+        CompareOp::Match => glob_match(a, b),
+        CompareOp::Gt => a.to_lowercase() > b.to_lowercase(),
+        CompareOp::Lt => a.to_lowercase() < b.to_lowercase(),
+        CompareOp::Ge => a.to_lowercase() >= b.to_lowercase(),
+        CompareOp::Le => a.to_lowercase() <= b.to_lowercase(),
+    }
+}
+
+fn eval_tag(tags: &[String], op: CompareOp, value: &Value) -> bool {
+    let needle = match value {
+        Value::Str(s) => s.clone(),
+        Value::Num(n) => n.to_string(),
+    };
+    match op {
+        CompareOp::Eq => tags.iter().any(|t| t.eq_ignore_ascii_case(&needle)),
+        CompareOp::Ne => !tags.iter().any(|t| t.eq_ignore_ascii_case(&needle)),
+        CompareOp::Match => tags.iter().any(|t| glob_match(t, &needle)),
+        CompareOp::Gt | CompareOp::Lt | CompareOp::Ge | CompareOp::Le => false,
+    }
+}
+
+fn eval_compare(target: &dyn FilterFields, field: &str, op: CompareOp, value: &Value) -> bool {
+    if field == "tag" || field == "tags" {
+        return eval_tag(target.tags(), op, value);
+    }
+    match (target.field(field), value) {
+        (Some(Value::Num(a)), Value::Num(b)) => compare_num(a, op, *b),
+        (Some(Value::Num(a)), Value::Str(b)) => compare_num_to_str(a, op, b),
+        (Some(Value::Str(a)), Value::Str(b)) => compare_str(&a, op, b),
+        (Some(Value::Str(a)), Value::Num(b)) => compare_str(&a, op, &b.to_string()),
+        (None, _) => false,
+    }
+}
+
+impl Predicate {
+    /// Evaluate this predicate against an event or alert.
+    pub fn matches(&self, target: &dyn FilterFields) -> bool {
+        match self {
+            Predicate::Compare { field, op, value } => eval_compare(target, field, *op, value),
+            Predicate::And(lhs, rhs) => lhs.matches(target) && rhs.matches(target),
+            Predicate::Or(lhs, rhs) => lhs.matches(target) || rhs.matches(target),
+            Predicate::Not(inner) => !inner.matches(target),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Fake {
+        fields: Vec<(&'static str, Value)>,
+        tags: Vec<String>,
+    }
+
+    impl FilterFields for Fake {
+        fn field(&self, name: &str) -> Option<Value> {
+            self.fields
+                .iter()
+                .find(|(k, _)| *k == name)
+                .map(|(_, v)| v.clone())
+        }
+
+        fn tags(&self) -> &[String] {
+            &self.tags
+        }
+    }
+
+    fn nginx_event() -> Fake {
+        Fake {
+            fields: vec![
+                ("pid", Value::Num(1234.0)),
+                ("uid", Value::Num(0.0)),
+                ("comm", Value::Str("nginx-worker".to_string())),
+                ("event", Value::Str("exec".to_string())),
+                ("cpu", Value::Num(75.0)),
+            ],
+            tags: vec!["oom".to_string()],
+        }
+    }
+
+    #[test]
+    fn parses_and_matches_numeric_comparison() {
+        let predicate = parse("cpu > 50").unwrap();
+        assert!(predicate.matches(&nginx_event()));
+        assert!(!parse("cpu > 90").unwrap().matches(&nginx_event()));
+    }
+
+    #[test]
+    fn parses_and_matches_glob_on_strings() {
+        let predicate = parse("comm ~ nginx").unwrap();
+        assert!(predicate.matches(&nginx_event()));
+        assert!(!parse("comm ~ apache").unwrap().matches(&nginx_event()));
+    }
+
+    #[test]
+    fn and_or_parentheses_and_precedence() {
+        let predicate = parse("tag = oom || (uid = 0 && event = exec)").unwrap();
+        assert!(predicate.matches(&nginx_event()));
+    }
+
+    #[test]
+    fn not_negates() {
+        let predicate = parse("!(comm ~ nginx)").unwrap();
+        assert!(!predicate.matches(&nginx_event()));
+    }
+
+    #[test]
+    fn tag_field_is_set_membership_not_equality() {
+        let predicate = parse("tag = oom").unwrap();
+        assert!(predicate.matches(&nginx_event()));
+    }
+
+    #[test]
+    fn unknown_field_is_a_parse_error_with_column() {
+        let err = parse("bogus = 1").unwrap_err();
+        assert_eq!(err.column, 1);
+        assert!(err.message.contains("unknown field"));
+    }
+
+    #[test]
+    fn malformed_expression_reports_column_instead_of_silently_matching_nothing() {
+        let err = parse("cpu >").unwrap_err();
+        assert_eq!(err.column, 6);
+    }
+
+    #[test]
+    fn unterminated_parenthesis_reports_end_of_input_column() {
+        let err = parse("(cpu > 50").unwrap_err();
+        assert!(err.message.contains("expected ')'"));
+    }
+
+    #[test]
+    fn trailing_tokens_are_rejected() {
+        let err = parse("cpu > 50 comm").unwrap_err();
+        assert!(err.message.contains("unexpected trailing token"));
+    }
+}
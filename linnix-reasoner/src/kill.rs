@@ -0,0 +1,186 @@
+//! `--kill`/`--signal` remediation: send a signal to a flagged process,
+//! either by PID (`--pid`) or by matching the `--filter` query language
+//! against the live `/alerts` list (`--all-matching`). Immediately before
+//! signalling, the `/alerts` payload is re-fetched and the target's `comm`
+//! is re-checked against what it was selected under, so a PID recycled by
+//! an unrelated process between selection and execution is never touched --
+//! the same guard cognitod's `ProcessIdentity` check applies server-side in
+//! `enforcement.rs`, just client-side here. A bare `--pid` that isn't in
+//! `/alerts` has no `comm` to re-check and is signalled as given.
+
+use std::io::Write;
+
+use reqwest::Client;
+
+use crate::ProcessAlert;
+use crate::filter;
+
+#[derive(Debug, Clone, Copy)]
+pub enum Signal {
+    Term,
+    Kill,
+    Stop,
+}
+
+impl Signal {
+    fn as_libc(self) -> i32 {
+        match self {
+            Signal::Term => libc::SIGTERM,
+            Signal::Kill => libc::SIGKILL,
+            Signal::Stop => libc::SIGSTOP,
+        }
+    }
+}
+
+impl std::str::FromStr for Signal {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().as_str() {
+            "TERM" => Ok(Signal::Term),
+            "KILL" => Ok(Signal::Kill),
+            "STOP" => Ok(Signal::Stop),
+            other => Err(format!(
+                "unknown signal '{other}' (expected TERM, KILL, or STOP)"
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for Signal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Signal::Term => "TERM",
+            Signal::Kill => "KILL",
+            Signal::Stop => "STOP",
+        };
+        write!(f, "{name}")
+    }
+}
+
+fn send_signal(pid: u32, signal: Signal) -> Result<(), String> {
+    // SAFETY: `kill` is a simple syscall wrapper; pid/signal are plain integers.
+    let ret = unsafe { libc::kill(pid as libc::pid_t, signal.as_libc()) };
+    if ret == 0 {
+        Ok(())
+    } else {
+        Err(std::io::Error::last_os_error().to_string())
+    }
+}
+
+async fn fetch_alerts(client: &Client, host: &str) -> anyhow::Result<Vec<ProcessAlert>> {
+    let alerts = client
+        .get(format!("{}/alerts", host.trim_end_matches('/')))
+        .send()
+        .await?
+        .json()
+        .await?;
+    Ok(alerts)
+}
+
+/// One pid to signal, with the `comm` it was selected under (if any) for the
+/// PID-reuse guard. `None` means there's nothing to compare against (a bare
+/// `--pid` that doesn't currently appear in `/alerts`).
+struct Target {
+    pid: u32,
+    expected_comm: Option<String>,
+}
+
+/// `linnix --kill --pid <PID>` or `linnix --kill --all-matching <expr>`.
+pub async fn run_kill(
+    client: &Client,
+    host: &str,
+    pid: Option<u32>,
+    all_matching: Option<&str>,
+    signal: Signal,
+) -> anyhow::Result<()> {
+    let alerts = fetch_alerts(client, host).await?;
+
+    let targets = if let Some(pid) = pid {
+        vec![Target {
+            pid,
+            expected_comm: alerts
+                .iter()
+                .find(|a| a.pid == pid)
+                .map(|a| a.comm.clone()),
+        }]
+    } else if let Some(expr) = all_matching {
+        let predicate = filter::parse(expr)
+            .map_err(|e| anyhow::anyhow!("invalid --all-matching expression: {e}"))?;
+        alerts
+            .iter()
+            .filter(|a| predicate.matches(*a))
+            .map(|a| Target {
+                pid: a.pid,
+                expected_comm: Some(a.comm.clone()),
+            })
+            .collect()
+    } else {
+        anyhow::bail!("--kill requires --pid <PID> or --all-matching <filter-expr>");
+    };
+
+    if targets.is_empty() {
+        println!("No matching processes to signal.");
+        return Ok(());
+    }
+
+    // Re-fetch right before acting so a PID recycled between selection and
+    // execution is caught rather than signalled blindly.
+    let current_alerts = fetch_alerts(client, host).await?;
+    for target in targets {
+        signal_one(&current_alerts, target, signal);
+    }
+    Ok(())
+}
+
+fn signal_one(current_alerts: &[ProcessAlert], target: Target, signal: Signal) {
+    let Target { pid, expected_comm } = target;
+    if let Some(ref comm) = expected_comm {
+        let still_matches = current_alerts
+            .iter()
+            .any(|a| a.pid == pid && &a.comm == comm);
+        if !still_matches {
+            println!(
+                "PID {pid}: skipped -- no longer matches the alert that flagged it (comm={comm}), likely reused"
+            );
+            return;
+        }
+    }
+    match send_signal(pid, signal) {
+        Ok(()) => println!("PID {pid}: sent SIG{signal} successfully"),
+        Err(e) => println!("PID {pid}: failed to signal ({e})"),
+    }
+}
+
+/// `linnix --alerts --kill`: after listing alerts, offer to terminate each
+/// one interactively.
+pub async fn offer_kill(
+    client: &Client,
+    host: &str,
+    alerts: &[ProcessAlert],
+    signal: Signal,
+) -> anyhow::Result<()> {
+    for alert in alerts {
+        print!(
+            "Signal SIG{signal} to PID {} ({}, reason: {})? [y/N] ",
+            alert.pid, alert.comm, alert.reason
+        );
+        std::io::stdout().flush().ok();
+        let mut answer = String::new();
+        std::io::stdin().read_line(&mut answer)?;
+        if !matches!(answer.trim().to_lowercase().as_str(), "y" | "yes") {
+            continue;
+        }
+
+        let current_alerts = fetch_alerts(client, host).await?;
+        signal_one(
+            &current_alerts,
+            Target {
+                pid: alert.pid,
+                expected_comm: Some(alert.comm.clone()),
+            },
+            signal,
+        );
+    }
+    Ok(())
+}
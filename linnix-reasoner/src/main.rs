@@ -7,6 +7,13 @@ use serde::{Deserialize, Serialize};
 use std::env;
 use sysinfo::System;
 
+mod filter;
+mod kill;
+mod psi;
+mod tui;
+
+use filter::{FilterFields, Value};
+
 #[derive(Parser)]
 struct Args {
     /// Print a one-line summary from the LLM
@@ -39,19 +46,44 @@ struct Args {
     /// Stream live process events from cognitod
     #[arg(long)]
     stream: bool,
+    /// Full-screen interactive dashboard: event feed, top processes, system
+    /// gauges, and PSI pressure bars, instead of line-oriented --stream output
+    #[arg(long)]
+    tui: bool,
     /// Output raw JSON for stream events
     #[arg(long)]
     raw: bool,
-    /// Only show events matching this tag
+    /// Only show events/alerts matching this filter expression, e.g.
+    /// "cpu > 50 && comm ~ nginx" or "tag = oom || (uid = 0 && event = exec)"
     #[arg(long)]
     filter: Option<String>,
+    /// Signal a flagged process instead of just displaying it. Use with
+    /// --pid or --all-matching, or alongside --alerts to be prompted
+    /// per-alert.
+    #[arg(long)]
+    kill: bool,
+    /// PID to signal with --kill
+    #[arg(long)]
+    pid: Option<u32>,
+    /// Signal every currently-alerted PID matching this filter expression
+    /// (see --filter) with --kill
+    #[arg(long)]
+    all_matching: Option<String>,
+    /// Signal to send: TERM, KILL, or STOP (default: TERM)
+    #[arg(long, default_value = "TERM")]
+    signal: String,
     /// Disable colored output
     #[arg(long)]
     no_color: bool,
+    /// Print a local PSI (Pressure Stall Information) summary and exit. High
+    /// "full" pressure means tasks are stalling (latency/throughput loss),
+    /// as opposed to high CPU%/MEM% alone, which is merely high utilization.
+    #[arg(long)]
+    psi: bool,
 }
 
-#[derive(Debug, Deserialize)]
-struct SystemSnapshot {
+#[derive(Debug, Deserialize, Clone)]
+pub(crate) struct SystemSnapshot {
     timestamp: u64,
     cpu_percent: f32,
     mem_percent: f32,
@@ -59,12 +91,10 @@ struct SystemSnapshot {
 }
 
 #[derive(Debug, Deserialize)]
-struct ProcessAlert {
+pub(crate) struct ProcessAlert {
     pid: u32,
-    #[allow(dead_code)]
     ppid: u32,
     comm: String,
-    #[allow(dead_code)]
     uid: u32,
     tags: Vec<String>,
     cpu_percent: Option<f32>,
@@ -74,11 +104,10 @@ struct ProcessAlert {
 }
 
 #[derive(Debug, Deserialize, Clone)]
-struct ProcessEvent {
+pub(crate) struct ProcessEvent {
     pid: u32,
     ppid: u32,
     uid: u32,
-    #[allow(dead_code)]
     gid: u32,
     comm: String,
     event_type: u32,
@@ -88,13 +117,72 @@ struct ProcessEvent {
     seq: u64,
     #[allow(dead_code)]
     exit_time_ns: u64,
-    #[allow(dead_code)]
     cpu_pct_milli: u16,
-    #[allow(dead_code)]
     mem_pct_milli: u16,
     tags: Vec<String>,
 }
 
+/// Map the raw `event_type` code to the lowercase name used by `--filter`
+/// expressions (`event = exec`), independent of the capitalized names used
+/// for terminal display below.
+pub(crate) fn event_type_name(event_type: u32) -> &'static str {
+    match event_type {
+        0 => "exec",
+        1 => "fork",
+        2 => "exit",
+        _ => "unknown",
+    }
+}
+
+/// `cpu_pct_milli`/`mem_pct_milli` store percent * 1000, with `u16::MAX` as
+/// an "unknown" sentinel (see `linnix-ai-ebpf-common`'s `PERCENT_MILLI_UNKNOWN`).
+pub(crate) fn pct_milli_to_percent(pct_milli: u16) -> Option<f64> {
+    if pct_milli == u16::MAX {
+        None
+    } else {
+        Some(pct_milli as f64 / 1000.0)
+    }
+}
+
+impl FilterFields for ProcessEvent {
+    fn field(&self, name: &str) -> Option<Value> {
+        match name {
+            "pid" => Some(Value::Num(self.pid as f64)),
+            "ppid" => Some(Value::Num(self.ppid as f64)),
+            "uid" => Some(Value::Num(self.uid as f64)),
+            "gid" => Some(Value::Num(self.gid as f64)),
+            "comm" => Some(Value::Str(self.comm.clone())),
+            "event" => Some(Value::Str(event_type_name(self.event_type).to_string())),
+            "cpu" => pct_milli_to_percent(self.cpu_pct_milli).map(Value::Num),
+            "mem" => pct_milli_to_percent(self.mem_pct_milli).map(Value::Num),
+            _ => None,
+        }
+    }
+
+    fn tags(&self) -> &[String] {
+        &self.tags
+    }
+}
+
+impl FilterFields for ProcessAlert {
+    fn field(&self, name: &str) -> Option<Value> {
+        match name {
+            "pid" => Some(Value::Num(self.pid as f64)),
+            "ppid" => Some(Value::Num(self.ppid as f64)),
+            "uid" => Some(Value::Num(self.uid as f64)),
+            "comm" => Some(Value::Str(self.comm.clone())),
+            "event" => Some(Value::Str(event_type_name(self.event_type).to_string())),
+            "cpu" => self.cpu_percent.map(|v| Value::Num(v as f64)),
+            "mem" => self.mem_percent.map(|v| Value::Num(v as f64)),
+            _ => None,
+        }
+    }
+
+    fn tags(&self) -> &[String] {
+        &self.tags
+    }
+}
+
 #[derive(Serialize)]
 struct ChatMessage {
     role: String,
@@ -127,6 +215,48 @@ async fn main() -> anyhow::Result<()> {
     let args = Args::parse();
     let client = Client::new();
 
+    let compiled_filter = match args.filter.as_deref().map(filter::parse) {
+        Some(Ok(predicate)) => Some(predicate),
+        Some(Err(e)) => {
+            eprintln!("invalid --filter expression: {e}");
+            std::process::exit(2);
+        }
+        None => None,
+    };
+
+    let signal: kill::Signal = match args.signal.parse() {
+        Ok(signal) => signal,
+        Err(e) => {
+            eprintln!("invalid --signal: {e}");
+            std::process::exit(2);
+        }
+    };
+
+    if args.kill && !args.alerts {
+        return kill::run_kill(
+            &client,
+            &args.host,
+            args.pid,
+            args.all_matching.as_deref(),
+            signal,
+        )
+        .await;
+    }
+
+    if args.tui {
+        return tui::run(&args, &client, compiled_filter).await;
+    }
+
+    if args.psi {
+        let metrics = psi::PsiMetrics::read();
+        if args.json {
+            println!("{}", serde_json::to_string_pretty(&metrics)?);
+        } else {
+            println!("{}", metrics.summary());
+        }
+        return Ok(());
+    }
+
     if args.stream {
         let url = format!("{}/stream", args.host.trim_end_matches('/'));
         let response = client.get(&url).send().await?.error_for_status()?;
@@ -142,8 +272,8 @@ async fn main() -> anyhow::Result<()> {
                     }
                     match serde_json::from_str::<ProcessEvent>(&data) {
                         Ok(ev) => {
-                            if let Some(ref tag) = args.filter {
-                                if !ev.tags.iter().any(|t| t == tag) {
+                            if let Some(ref predicate) = compiled_filter {
+                                if !predicate.matches(&ev) {
                                     continue;
                                 }
                             }
@@ -212,12 +342,15 @@ async fn main() -> anyhow::Result<()> {
     if args.alerts {
         let url = "http://localhost:3000/alerts";
         let resp = client.get(url).send().await?;
-        let alerts: Vec<ProcessAlert> = resp.json().await?;
+        let mut alerts: Vec<ProcessAlert> = resp.json().await?;
+        if let Some(ref predicate) = compiled_filter {
+            alerts.retain(|alert| predicate.matches(alert));
+        }
         if alerts.is_empty() {
             println!("No active alerts.");
         } else {
             println!("Active Alerts:");
-            for alert in alerts {
+            for alert in &alerts {
                 println!(
                     "PID: {} CMD: {} TAGS: {:?} CPU: {:.1?}% MEM: {:.1?}% EVENT: {} REASON: {}",
                     alert.pid,
@@ -229,6 +362,9 @@ async fn main() -> anyhow::Result<()> {
                     alert.reason
                 );
             }
+            if args.kill {
+                kill::offer_kill(&client, "http://localhost:3000", &alerts, signal).await?;
+            }
         }
         return Ok(());
     }
@@ -427,16 +563,25 @@ async fn main() -> anyhow::Result<()> {
         process_context.push_str("└─────────┴──────────────────────────────────────────────────────────────────┴─────────┴─────────┘\n");
     }
 
+    // PSI (Pressure Stall Information): unlike CPU%/MEM%, which only measure
+    // utilization, PSI measures whether tasks are actually stalled waiting
+    // for a resource, so it's included as its own section of the context.
+    let psi_metrics = psi::PsiMetrics::read();
+    let psi_context = format!("\n\nPressure Stall Information: {}\n", psi_metrics.summary());
+
     // Prepare prompt with process information
     let prompt = if args.short {
         format!(
-            "Given this Linux system snapshot: {snapshot:#?}{process_context}\n\
+            "Given this Linux system snapshot: {snapshot:#?}{process_context}{psi_context}\n\
             Provide a one-sentence summary mentioning the key processes from the table above."
         )
     } else {
         format!(
-            "Given this Linux system snapshot: {snapshot:#?}{process_context}\n\
+            "Given this Linux system snapshot: {snapshot:#?}{process_context}{psi_context}\n\
             IMPORTANT: Start your response by copying the process table exactly as shown above (including the box drawing characters).\n\
+            Note on Pressure Stall Information: high \"full\" pressure means tasks are stalling -- \
+            actual latency/throughput loss -- which is a materially more serious signal than high CPU%/MEM% alone, \
+            which only shows utilization and can be perfectly healthy. Weigh \"full\" pressure heavily in your risk assessment.\n\
             Then provide analysis: What is happening in the OS? Which specific processes (mention PIDs and full paths from the table) are consuming resources? \
             Any anomalies or risks? Suggest cleanup if needed."
         )
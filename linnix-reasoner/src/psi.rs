@@ -0,0 +1,99 @@
+//! Local PSI (Pressure Stall Information) reader, shared by `--psi`, the LLM
+//! prompt's system context, and the `--tui` dashboard's pressure bars.
+//!
+//! Mirrors the shape of cognitod's `utils::psi::PsiMetrics` (same
+//! avg10/avg60/avg300/total fields and some/full split) so output reads the
+//! same whether it came from the server or this fallback, but reads
+//! `/proc/pressure/*` directly: `linnix-reasoner` is a separate binary crate
+//! with no lib dependency on cognitod, so there's no way to call the real
+//! `PsiMetrics::read()` from here.
+
+use serde::Serialize;
+
+/// One `some`/`full` line of a PSI file, exactly as the kernel reports it.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub(crate) struct PressureRecord {
+    pub avg10: f32,
+    pub avg60: f32,
+    pub avg300: f32,
+    /// Cumulative stalled time in microseconds since boot.
+    pub total: u64,
+}
+
+/// A resource's full PSI picture. `full` is `None` for CPU, which the
+/// kernel only reports "some" pressure for.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub(crate) struct Pressure {
+    pub some: PressureRecord,
+    pub full: Option<PressureRecord>,
+}
+
+/// PSI metrics for the entire system.
+#[derive(Debug, Clone, Default, Serialize)]
+pub(crate) struct PsiMetrics {
+    /// CPU pressure ("some" only -- the kernel has no "full" cpu.pressure line).
+    pub cpu: Pressure,
+    /// Memory pressure: "some" (tail latency) and "full" (complete thrashing).
+    pub memory: Pressure,
+    /// I/O pressure: "some" (tail latency) and "full" (complete stall).
+    pub io: Pressure,
+}
+
+impl PsiMetrics {
+    /// Read PSI metrics from `/proc/pressure/*`. Returns all-zero defaults
+    /// for any file that's missing or unreadable (e.g. kernel < 4.20, or no
+    /// permission), so callers never have to special-case "PSI unavailable".
+    pub fn read() -> Self {
+        let cpu = read_pressure("/proc/pressure/cpu");
+        let memory = read_pressure("/proc/pressure/memory");
+        let io = read_pressure("/proc/pressure/io");
+        Self { cpu, memory, io }
+    }
+
+    /// Human-readable one-line summary for `--psi`.
+    pub fn summary(&self) -> String {
+        format!(
+            "cpu: some avg10={:.1}% | mem: some avg10={:.1}% full avg10={:.1}% | io: some avg10={:.1}% full avg10={:.1}%",
+            self.cpu.some.avg10,
+            self.memory.some.avg10,
+            self.memory.full.map(|r| r.avg10).unwrap_or(0.0),
+            self.io.some.avg10,
+            self.io.full.map(|r| r.avg10).unwrap_or(0.0),
+        )
+    }
+}
+
+fn read_pressure(path: &str) -> Pressure {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return Pressure::default();
+    };
+    Pressure {
+        some: parse_pressure_record(&content, "some").unwrap_or_default(),
+        full: parse_pressure_record(&content, "full"),
+    }
+}
+
+/// Parse one `some`/`full` line (e.g. `some avg10=5.23 avg60=3.45
+/// avg300=2.11 total=123456`) into a [`PressureRecord`]. Missing fields
+/// default to 0/0.0, and `None` is returned only when no line starts with
+/// `line_prefix` at all (e.g. `full` for `/proc/pressure/cpu`).
+fn parse_pressure_record(content: &str, line_prefix: &str) -> Option<PressureRecord> {
+    for line in content.lines() {
+        if line.starts_with(line_prefix) {
+            let mut record = PressureRecord::default();
+            for part in line.split_whitespace() {
+                if let Some((key, value)) = part.split_once('=') {
+                    match key {
+                        "avg10" => record.avg10 = value.parse().unwrap_or(0.0),
+                        "avg60" => record.avg60 = value.parse().unwrap_or(0.0),
+                        "avg300" => record.avg300 = value.parse().unwrap_or(0.0),
+                        "total" => record.total = value.parse().unwrap_or(0),
+                        _ => {}
+                    }
+                }
+            }
+            return Some(record);
+        }
+    }
+    None
+}
@@ -0,0 +1,499 @@
+//! `--tui`: full-screen terminal dashboard, an alternative to the
+//! line-oriented `--stream` output. Combines a scrolling, color-coded event
+//! feed, a `sysinfo`-based top-N process table, the `/system` snapshot
+//! gauges, and PSI pressure bars into one view, with keybindings to pause
+//! the feed, edit the `--filter` expression interactively, change the
+//! process table's sort column, and select a process to see its alert/tags.
+//!
+//! Degrades gracefully: the event feed and gauges keep showing the last
+//! cognitod data (and note "disconnected") if `/stream` or `/system` go
+//! away, while the process table and PSI bars are always collected locally
+//! via `sysinfo`/`/proc/pressure` so the dashboard stays useful offline.
+
+use std::collections::VecDeque;
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use crossterm::execute;
+use crossterm::terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode};
+use eventsource_stream::Eventsource;
+use futures_util::StreamExt;
+use ratatui::Frame;
+use ratatui::Terminal;
+use ratatui::backend::{Backend, CrosstermBackend};
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Cell, Gauge, List, ListItem, Paragraph, Row, Table};
+use reqwest::Client;
+use sysinfo::System;
+
+use crate::filter::{self, Predicate};
+use crate::psi::PsiMetrics;
+use crate::{Args, ProcessAlert, ProcessEvent, SystemSnapshot, event_type_name};
+
+const FEED_CAPACITY: usize = 200;
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+const TICK: Duration = Duration::from_millis(200);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SortKey {
+    Cpu,
+    Mem,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum InputMode {
+    Normal,
+    EditingFilter,
+}
+
+struct LocalProcess {
+    pid: u32,
+    name: String,
+    cpu: f32,
+    mem_bytes: u64,
+}
+
+/// Data the background network tasks publish into and the render loop reads
+/// every tick. cognitod connectivity is tracked per-feed so the dashboard
+/// can say exactly what's stale rather than just "disconnected".
+struct Shared {
+    feed: VecDeque<ProcessEvent>,
+    snapshot: Option<SystemSnapshot>,
+    alerts: Vec<ProcessAlert>,
+    stream_connected: bool,
+    system_connected: bool,
+}
+
+impl Shared {
+    fn new() -> Self {
+        Self {
+            feed: VecDeque::with_capacity(FEED_CAPACITY),
+            snapshot: None,
+            alerts: Vec::new(),
+            stream_connected: false,
+            system_connected: false,
+        }
+    }
+}
+
+async fn stream_task(client: Client, host: String, shared: Arc<Mutex<Shared>>, paused: Arc<AtomicBool>) {
+    let url = format!("{}/stream", host.trim_end_matches('/'));
+    let Ok(response) = client.get(&url).send().await.and_then(|r| r.error_for_status()) else {
+        return;
+    };
+    shared.lock().unwrap().stream_connected = true;
+
+    let mut stream = response.bytes_stream().eventsource();
+    while let Some(event) = stream.next().await {
+        let Ok(event) = event else { break };
+        if paused.load(Ordering::Relaxed) {
+            continue;
+        }
+        let Ok(ev) = serde_json::from_str::<ProcessEvent>(&event.data) else {
+            continue;
+        };
+        let mut shared = shared.lock().unwrap();
+        if shared.feed.len() >= FEED_CAPACITY {
+            shared.feed.pop_front();
+        }
+        shared.feed.push_back(ev);
+    }
+    shared.lock().unwrap().stream_connected = false;
+}
+
+async fn fetch_snapshot(client: &Client, host: &str) -> Option<SystemSnapshot> {
+    let url = format!("{}/system", host.trim_end_matches('/'));
+    client.get(url).send().await.ok()?.json().await.ok()
+}
+
+async fn fetch_alerts(client: &Client, host: &str) -> Option<Vec<ProcessAlert>> {
+    let url = format!("{}/alerts", host.trim_end_matches('/'));
+    client.get(url).send().await.ok()?.json().await.ok()
+}
+
+async fn poll_task(client: Client, host: String, shared: Arc<Mutex<Shared>>) {
+    loop {
+        let snapshot = fetch_snapshot(&client, &host).await;
+        let alerts = fetch_alerts(&client, &host).await;
+        {
+            let mut shared = shared.lock().unwrap();
+            shared.system_connected = snapshot.is_some();
+            if let Some(snapshot) = snapshot {
+                shared.snapshot = Some(snapshot);
+            }
+            if let Some(alerts) = alerts {
+                shared.alerts = alerts;
+            }
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+fn top_processes(sys: &System, sort_key: SortKey) -> Vec<LocalProcess> {
+    let mut processes: Vec<LocalProcess> = sys
+        .processes()
+        .iter()
+        .map(|(pid, proc)| LocalProcess {
+            pid: pid.to_string().parse().unwrap_or(0),
+            name: proc.name().to_string(),
+            cpu: proc.cpu_usage(),
+            mem_bytes: proc.memory(),
+        })
+        .collect();
+    match sort_key {
+        SortKey::Cpu => processes.sort_by(|a, b| {
+            b.cpu.partial_cmp(&a.cpu).unwrap_or(std::cmp::Ordering::Equal)
+        }),
+        SortKey::Mem => processes.sort_by(|a, b| b.mem_bytes.cmp(&a.mem_bytes)),
+    }
+    processes.truncate(20);
+    processes
+}
+
+/// `--tui`: run the full-screen dashboard until the user presses `q`/`Esc`.
+pub async fn run(args: &Args, client: &Client, initial_filter: Option<Predicate>) -> anyhow::Result<()> {
+    let shared = Arc::new(Mutex::new(Shared::new()));
+    let paused = Arc::new(AtomicBool::new(false));
+
+    let stream_handle = tokio::spawn(stream_task(
+        client.clone(),
+        args.host.clone(),
+        Arc::clone(&shared),
+        Arc::clone(&paused),
+    ));
+    let poll_handle = tokio::spawn(poll_task(client.clone(), args.host.clone(), Arc::clone(&shared)));
+
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    let backend = CrosstermBackend::new(stdout);
+    let mut terminal = Terminal::new(backend)?;
+
+    let result = run_app(&mut terminal, &shared, &paused, initial_filter, args.no_color).await;
+
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+    stream_handle.abort();
+    poll_handle.abort();
+
+    result
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn run_app<B: Backend>(
+    terminal: &mut Terminal<B>,
+    shared: &Arc<Mutex<Shared>>,
+    paused: &Arc<AtomicBool>,
+    initial_filter: Option<Predicate>,
+    no_color: bool,
+) -> anyhow::Result<()> {
+    let mut sys = System::new_all();
+    let mut last_sys_refresh = Instant::now() - POLL_INTERVAL;
+    let mut sort_key = SortKey::Cpu;
+    let mut input_mode = InputMode::Normal;
+    let mut filter_input = String::new();
+    let mut filter_error: Option<String> = None;
+    let mut predicate = initial_filter;
+    let mut selected: usize = 0;
+
+    loop {
+        if last_sys_refresh.elapsed() >= POLL_INTERVAL {
+            sys.refresh_all();
+            last_sys_refresh = Instant::now();
+        }
+        let local_psi = PsiMetrics::read();
+        let processes = top_processes(&sys, sort_key);
+        selected = selected.min(processes.len().saturating_sub(1));
+
+        {
+            let shared = shared.lock().unwrap();
+            terminal.draw(|f| {
+                draw(
+                    f,
+                    &shared,
+                    &processes,
+                    &local_psi,
+                    sort_key,
+                    input_mode,
+                    &filter_input,
+                    filter_error.as_deref(),
+                    predicate.as_ref(),
+                    selected,
+                    paused.load(Ordering::Relaxed),
+                    no_color,
+                )
+            })?;
+        }
+
+        if event::poll(TICK)? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+                match input_mode {
+                    InputMode::Normal => match key.code {
+                        KeyCode::Char('q') | KeyCode::Esc => break,
+                        KeyCode::Char('p') => {
+                            paused.fetch_xor(true, Ordering::Relaxed);
+                        }
+                        KeyCode::Char('c') => sort_key = SortKey::Cpu,
+                        KeyCode::Char('m') => sort_key = SortKey::Mem,
+                        KeyCode::Char('/') => {
+                            input_mode = InputMode::EditingFilter;
+                            filter_input.clear();
+                            filter_error = None;
+                        }
+                        KeyCode::Up | KeyCode::Char('k') => selected = selected.saturating_sub(1),
+                        KeyCode::Down | KeyCode::Char('j') => selected = selected.saturating_add(1),
+                        _ => {}
+                    },
+                    InputMode::EditingFilter => match key.code {
+                        KeyCode::Enter => {
+                            if filter_input.is_empty() {
+                                predicate = None;
+                                filter_error = None;
+                            } else {
+                                match filter::parse(&filter_input) {
+                                    Ok(p) => {
+                                        predicate = Some(p);
+                                        filter_error = None;
+                                    }
+                                    Err(e) => filter_error = Some(e.to_string()),
+                                }
+                            }
+                            input_mode = InputMode::Normal;
+                        }
+                        KeyCode::Esc => input_mode = InputMode::Normal,
+                        KeyCode::Backspace => {
+                            filter_input.pop();
+                        }
+                        KeyCode::Char(c) => filter_input.push(c),
+                        _ => {}
+                    },
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn draw(
+    f: &mut Frame,
+    shared: &Shared,
+    processes: &[LocalProcess],
+    psi: &PsiMetrics,
+    sort_key: SortKey,
+    input_mode: InputMode,
+    filter_input: &str,
+    filter_error: Option<&str>,
+    predicate: Option<&Predicate>,
+    selected: usize,
+    paused: bool,
+    no_color: bool,
+) {
+    let area = f.size();
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(10), Constraint::Length(7), Constraint::Length(3)])
+        .split(area);
+
+    let top = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+        .split(chunks[0]);
+
+    draw_feed(f, top[0], shared, predicate, no_color);
+    draw_processes(f, top[1], processes, sort_key, selected, &shared.alerts);
+    draw_gauges(f, chunks[1], shared, psi);
+    draw_status(f, chunks[2], input_mode, filter_input, filter_error, paused, shared);
+}
+
+fn draw_feed(f: &mut Frame, area: Rect, shared: &Shared, predicate: Option<&Predicate>, no_color: bool) {
+    let items: Vec<ListItem> = shared
+        .feed
+        .iter()
+        .rev()
+        .filter(|ev| predicate.is_none_or(|p| p.matches(*ev)))
+        .take(area.height.saturating_sub(2) as usize)
+        .map(|ev| {
+            let label = event_type_name(ev.event_type);
+            let color = if no_color {
+                Color::Reset
+            } else {
+                match ev.event_type {
+                    0 => Color::Green,
+                    1 => Color::Blue,
+                    2 => Color::Red,
+                    _ => Color::White,
+                }
+            };
+            let line = format!(
+                "[{label:>7}] pid={:<7} ppid={:<7} comm={:<16} uid={}",
+                ev.pid, ev.ppid, ev.comm, ev.uid
+            );
+            ListItem::new(Line::from(line)).style(Style::default().fg(color))
+        })
+        .collect();
+
+    let title = if shared.stream_connected {
+        "Event Feed".to_string()
+    } else {
+        "Event Feed (disconnected from /stream)".to_string()
+    };
+    let list = List::new(items).block(Block::default().borders(Borders::ALL).title(title));
+    f.render_widget(list, area);
+}
+
+fn draw_processes(
+    f: &mut Frame,
+    area: Rect,
+    processes: &[LocalProcess],
+    sort_key: SortKey,
+    selected: usize,
+    alerts: &[ProcessAlert],
+) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(5), Constraint::Length(4)])
+        .split(area);
+
+    let rows: Vec<Row> = processes
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            let style = if i == selected {
+                Style::default().add_modifier(Modifier::REVERSED)
+            } else {
+                Style::default()
+            };
+            Row::new(vec![
+                Cell::from(p.pid.to_string()),
+                Cell::from(p.name.clone()),
+                Cell::from(format!("{:.1}%", p.cpu)),
+                Cell::from(format!("{:.1} MB", p.mem_bytes as f64 / (1024.0 * 1024.0))),
+            ])
+            .style(style)
+        })
+        .collect();
+
+    let sort_label = match sort_key {
+        SortKey::Cpu => "cpu",
+        SortKey::Mem => "mem",
+    };
+    let widths = [
+        Constraint::Length(8),
+        Constraint::Min(10),
+        Constraint::Length(8),
+        Constraint::Length(10),
+    ];
+    let table = Table::new(rows, widths)
+        .header(Row::new(vec!["PID", "NAME", "CPU", "MEM"]))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Top Processes (sorted by {sort_label})")),
+        );
+    f.render_widget(table, chunks[0]);
+
+    let detail = processes
+        .get(selected)
+        .map(|p| {
+            alerts
+                .iter()
+                .find(|a| a.pid == p.pid)
+                .map(|a| format!("pid {} tags={:?} reason={}", a.pid, a.tags, a.reason))
+                .unwrap_or_else(|| format!("pid {} -- no active alert", p.pid))
+        })
+        .unwrap_or_else(|| "no process selected".to_string());
+
+    let detail_view = Paragraph::new(detail).block(Block::default().borders(Borders::ALL).title("Selected"));
+    f.render_widget(detail_view, chunks[1]);
+}
+
+fn draw_gauges(f: &mut Frame, area: Rect, shared: &Shared, psi: &PsiMetrics) {
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(34),
+            Constraint::Percentage(33),
+            Constraint::Percentage(33),
+        ])
+        .split(area);
+
+    let (cpu_pct, mem_pct, load1) = shared
+        .snapshot
+        .as_ref()
+        .map(|s| (s.cpu_percent, s.mem_percent, s.load_avg[0]))
+        .unwrap_or((0.0, 0.0, 0.0));
+    let source = if shared.system_connected {
+        "cognitod /system"
+    } else {
+        "cognitod /system, disconnected"
+    };
+
+    f.render_widget(
+        Gauge::default()
+            .block(Block::default().borders(Borders::ALL).title(format!("CPU% ({source})")))
+            .ratio((cpu_pct as f64 / 100.0).clamp(0.0, 1.0))
+            .label(format!("{cpu_pct:.1}% load1={load1:.2}")),
+        chunks[0],
+    );
+    f.render_widget(
+        Gauge::default()
+            .block(Block::default().borders(Borders::ALL).title("MEM%"))
+            .ratio((mem_pct as f64 / 100.0).clamp(0.0, 1.0))
+            .label(format!("{mem_pct:.1}%")),
+        chunks[1],
+    );
+
+    let mem_full = psi.memory.full.map(|r| r.avg10).unwrap_or(0.0);
+    let io_full = psi.io.full.map(|r| r.avg10).unwrap_or(0.0);
+    let psi_lines = vec![
+        Line::from(format!("cpu  some={:.1}", psi.cpu.some.avg10)),
+        Line::from(format!("mem  some={:.1} full={:.1}", psi.memory.some.avg10, mem_full)),
+        Line::from(format!("io   some={:.1} full={:.1}", psi.io.some.avg10, io_full)),
+    ];
+    let psi_view = Paragraph::new(psi_lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("PSI avg10% (/proc/pressure)"),
+    );
+    f.render_widget(psi_view, chunks[2]);
+}
+
+fn draw_status(
+    f: &mut Frame,
+    area: Rect,
+    input_mode: InputMode,
+    filter_input: &str,
+    filter_error: Option<&str>,
+    paused: bool,
+    shared: &Shared,
+) {
+    let text = match input_mode {
+        InputMode::EditingFilter => format!("filter> {filter_input}_"),
+        InputMode::Normal => {
+            let mut line =
+                String::from("q:quit  p:pause  /:filter  c:sort-cpu  m:sort-mem  j/k:select");
+            if paused {
+                line.push_str("  [PAUSED]");
+            }
+            if let Some(err) = filter_error {
+                line.push_str(&format!("  filter error: {err}"));
+            }
+            if !shared.stream_connected {
+                line.push_str("  [stream disconnected]");
+            }
+            line
+        }
+    };
+    let status = Paragraph::new(text).block(Block::default().borders(Borders::ALL).title("Status"));
+    f.render_widget(status, area);
+}